@@ -3,20 +3,50 @@ use rocket::serde::json::Json;
 use serde::{Deserialize, Serialize};
 use chrono::Utc;
 use std::fmt;
+use crate::services::providers::ProviderError;
+use crate::utils::redact::redact;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
     pub error: String,
     pub message: String,
     pub timestamp: String,
+    /// Provider yang gagal dan alasannya (hanya untuk error dari upstream)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_providers: Vec<ProviderFailure>,
 }
 
 impl ErrorResponse {
     pub fn new(error: &str, message: &str) -> Self {
         Self {
             error: error.to_string(),
-            message: message.to_string(),
+            message: redact(message).into_owned(),
             timestamp: Utc::now().to_rfc3339(),
+            failed_providers: Vec::new(),
+        }
+    }
+
+    pub fn with_failed_providers(mut self, failed_providers: &[ProviderFailure]) -> Self {
+        self.failed_providers = failed_providers.to_vec();
+        self
+    }
+}
+
+/// Satu provider yang gagal, dalam bentuk yang bisa dibaca client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderFailure {
+    pub provider: String,
+    /// Kode alasan, lihat `ProviderError::code`
+    pub reason: String,
+    pub message: String,
+}
+
+impl ProviderFailure {
+    pub fn new(provider: &str, error: &ProviderError) -> Self {
+        Self {
+            provider: provider.to_string(),
+            reason: error.code().to_string(),
+            message: redact(&error.to_string()).into_owned(),
         }
     }
 }
@@ -25,9 +55,22 @@ impl ErrorResponse {
 pub enum ApiError {
     CityNotFound(String),
     InvalidInput(String),
+    #[allow(dead_code)]
     WeatherProviderError(String),
     #[allow(dead_code)]
     Timeout,
+    /// 504: semua provider yang gagal karena timeout
+    UpstreamTimeout(Vec<ProviderFailure>),
+    /// 502: provider menjawab, tapi datanya rusak/kosong
+    BadUpstreamData(Vec<ProviderFailure>),
+    /// 429: kuota/rate limit kita ke provider habis
+    QuotaExhausted(Vec<ProviderFailure>),
+    /// 503: provider tidak bisa dipakai (campuran penyebab, auth, breaker terbuka, dll)
+    ProvidersUnavailable(Vec<ProviderFailure>),
+    /// 500: bug/kondisi internal, misalnya perhitungan tanggal gagal
+    Internal(String),
+    /// 401: endpoint admin tanpa API key yang benar
+    Unauthorized(String),
 }
 
 impl fmt::Display for ApiError {
@@ -37,6 +80,12 @@ impl fmt::Display for ApiError {
             ApiError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
             ApiError::WeatherProviderError(msg) => write!(f, "Weather provider error: {}", msg),
             ApiError::Timeout => write!(f, "Request timeout"),
+            ApiError::UpstreamTimeout(failed) => write!(f, "Upstream timeout: {}", describe(failed)),
+            ApiError::BadUpstreamData(failed) => write!(f, "Bad upstream data: {}", describe(failed)),
+            ApiError::QuotaExhausted(failed) => write!(f, "Quota exhausted: {}", describe(failed)),
+            ApiError::ProvidersUnavailable(failed) => write!(f, "Providers unavailable: {}", describe(failed)),
+            ApiError::Internal(msg) => write!(f, "Internal error: {}", msg),
+            ApiError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
         }
     }
 }
@@ -51,10 +100,52 @@ impl ApiError {
         ApiError::InvalidInput(message.to_string())
     }
 
+    #[allow(dead_code)]
     pub fn provider_error(message: &str) -> Self {
         ApiError::WeatherProviderError(message.to_string())
     }
 
+    pub fn internal_error(message: &str) -> Self {
+        ApiError::Internal(message.to_string())
+    }
+
+    pub fn unauthorized(message: &str) -> Self {
+        ApiError::Unauthorized(message.to_string())
+    }
+
+    /// Pilih kelas error dari kegagalan provider. Kalau semua gagal dengan kelas yang sama,
+    /// status-nya spesifik (504/502/429); kalau campuran, 503.
+    pub fn from_provider_errors<'a, I>(errors: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a ProviderError)>,
+    {
+        let errors: Vec<(&str, &ProviderError)> = errors.into_iter().collect();
+        let failed = errors
+            .iter()
+            .map(|(provider, e)| ProviderFailure::new(provider, e))
+            .collect();
+
+        let class = |e: &ProviderError| match e {
+            ProviderError::Timeout => 504,
+            ProviderError::SchemaMismatch(_)
+            | ProviderError::EmptyData
+            | ProviderError::LocationMismatch { .. } => 502,
+            ProviderError::QuotaExhausted { .. }
+            | ProviderError::RateLimited { .. }
+            | ProviderError::QuotaLimitReached { .. } => 429,
+            _ => 503,
+        };
+        let first = errors.first().map(|(_, e)| class(e));
+        let uniform = first.filter(|c| errors.iter().all(|(_, e)| class(e) == *c));
+
+        match uniform {
+            Some(504) => ApiError::UpstreamTimeout(failed),
+            Some(502) => ApiError::BadUpstreamData(failed),
+            Some(429) => ApiError::QuotaExhausted(failed),
+            _ => ApiError::ProvidersUnavailable(failed),
+        }
+    }
+
     pub fn to_response(&self) -> (Status, Json<ErrorResponse>) {
         match self {
             ApiError::CityNotFound(city) => (
@@ -82,6 +173,101 @@ impl ApiError {
                     "Request timed out. Please try again later.",
                 )),
             ),
+            ApiError::UpstreamTimeout(failed) => (
+                Status::GatewayTimeout,
+                Json(ErrorResponse::new(
+                    "UPSTREAM_TIMEOUT",
+                    "Weather providers did not respond in time. Please try again later.",
+                ).with_failed_providers(failed)),
+            ),
+            ApiError::BadUpstreamData(failed) => (
+                Status::BadGateway,
+                Json(ErrorResponse::new(
+                    "BAD_UPSTREAM_DATA",
+                    "Weather providers returned unusable data.",
+                ).with_failed_providers(failed)),
+            ),
+            ApiError::QuotaExhausted(failed) => (
+                Status::TooManyRequests,
+                Json(ErrorResponse::new(
+                    "QUOTA_EXHAUSTED",
+                    "Weather provider quota is exhausted. Please try again later.",
+                ).with_failed_providers(failed)),
+            ),
+            ApiError::ProvidersUnavailable(failed) => (
+                Status::ServiceUnavailable,
+                Json(ErrorResponse::new(
+                    "SERVICE_UNAVAILABLE",
+                    "All weather providers are currently unavailable. Please try again later.",
+                ).with_failed_providers(failed)),
+            ),
+            ApiError::Internal(_) => (
+                Status::InternalServerError,
+                Json(ErrorResponse::new(
+                    "INTERNAL_ERROR",
+                    "An internal error occurred while building the forecast.",
+                )),
+            ),
+            ApiError::Unauthorized(msg) => (
+                Status::Unauthorized,
+                Json(ErrorResponse::new("UNAUTHORIZED", msg)),
+            ),
         }
     }
 }
+
+fn describe(failed: &[ProviderFailure]) -> String {
+    if failed.is_empty() {
+        return "no provider details".to_string();
+    }
+    failed
+        .iter()
+        .map(|f| format!("{} ({})", f.provider, f.message))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_uniform_failures_get_specific_status() {
+        let timeout = ProviderError::Timeout;
+        let (status, json) = ApiError::from_provider_errors([("open_meteo", &timeout), ("weather_api", &timeout)])
+            .to_response();
+        assert_eq!(status, Status::GatewayTimeout);
+        assert_eq!(json.error, "UPSTREAM_TIMEOUT");
+        assert_eq!(json.failed_providers.len(), 2);
+        assert_eq!(json.failed_providers[0].reason, "timeout");
+
+        let schema = ProviderError::SchemaMismatch("missing field `daily`".to_string());
+        let (status, _) = ApiError::from_provider_errors([("open_meteo", &schema)]).to_response();
+        assert_eq!(status, Status::BadGateway);
+
+        let quota = ProviderError::QuotaExhausted { retry_after: None };
+        let (status, _) = ApiError::from_provider_errors([("open_weather", &quota)]).to_response();
+        assert_eq!(status, Status::TooManyRequests);
+    }
+
+    #[test]
+    fn test_mixed_failures_are_unavailable() {
+        let timeout = ProviderError::Timeout;
+        let auth = ProviderError::AuthFailure { status: StatusCode::UNAUTHORIZED };
+        let (status, json) = ApiError::from_provider_errors([("open_meteo", &timeout), ("open_weather", &auth)])
+            .to_response();
+        assert_eq!(status, Status::ServiceUnavailable);
+        assert_eq!(json.error, "SERVICE_UNAVAILABLE");
+        assert_eq!(json.failed_providers[1].provider, "open_weather");
+        assert_eq!(json.failed_providers[1].reason, "auth_failure");
+    }
+
+    #[test]
+    fn test_internal_error_is_500_without_provider_list() {
+        let (status, json) = ApiError::internal_error("Date calculation error").to_response();
+        assert_eq!(status, Status::InternalServerError);
+        assert_eq!(json.error, "INTERNAL_ERROR");
+        assert!(serde_json::to_value(&*json).unwrap().get("failed_providers").is_none());
+    }
+}
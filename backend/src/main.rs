@@ -12,19 +12,23 @@ mod cities;
 mod errors;
 mod runtime;
 
-use utils::{Config, init_logger};
+use utils::{Config, init_logger, load_config_file};
+use utils::redact::{redact, register_config_secrets};
 use routes::routes;
 use services::{WeatherService, ForecastCache};
-use models::EnsembleForecast;
+use services::providers::{ProviderRegistry, UpstreamClient};
+use models::{CurrentConditions, EnsembleForecast, HourlyEnsembleForecast};
 use runtime::{init_runtime, log_runtime_config, WorkerPool, get_worker_count};
 
 #[tokio::main(flavor = "multi_thread", worker_threads = 3)]
 async fn main() {
     dotenvy::dotenv().ok();
     init_logger();
+    load_config_file();
     init_runtime();
     log_runtime_config();
     let config = Config::from_env();
+    register_config_secrets(&config);
 
     info!("Starting IndoPrint API server on port {}", config.server_port);
 
@@ -33,15 +37,24 @@ async fn main() {
     let semaphore = Arc::new(Semaphore::new(3));
     info!("Created rate limiting semaphore with 3 permits");
 
-    let weather_service = WeatherService::new(
-        config.openweather_key.clone(),
-        config.weatherapi_key.clone(),
+    // Satu HTTP client (connection pool) untuk semua provider
+    let upstream = Arc::new(
+        UpstreamClient::from_config(&config).expect("Failed to create upstream HTTP client"),
     );
+    let provider_registry = Arc::new(ProviderRegistry::from_config(&config, upstream.clone()));
+    let quota = upstream.quota().cloned();
+    let weather_service = WeatherService::new(provider_registry.clone());
 
     // Cache ensemble forecast: TTL 1 jam, max 100 entries
     let ensemble_cache = Arc::new(ForecastCache::<EnsembleForecast>::new(3600, 100));
     info!("Created ensemble forecast cache with 1 hour TTL");
 
+    // Cache ensemble per jam: TTL 30 menit, key-nya sudah berganti tiap jam
+    let hourly_cache = Arc::new(ForecastCache::<HourlyEnsembleForecast>::new(1800, 100));
+
+    // Cache kondisi saat ini: TTL 10 menit, observasi provider diperbarui tiap 15 menit-an
+    let current_cache = Arc::new(ForecastCache::<CurrentConditions>::new(600, 100));
+
     let shutdown = Arc::new(Notify::new());
     let shutdown_clone = shutdown.clone();
 
@@ -104,11 +117,15 @@ async fn main() {
         .manage(worker_pool)
         .manage(semaphore)
         .manage(ensemble_cache)
+        .manage(hourly_cache)
+        .manage(current_cache)
+        .manage(provider_registry)
+        .manage(upstream)
         .manage(config.clone())
         .attach(cors)
         .attach(AdHoc::on_request("Request Logger", |req, _| {
             Box::pin(async move {
-                info!("{} {}", req.method(), req.uri());
+                info!("{} {}", req.method(), redact(&req.uri().to_string()));
             })
         }))
         .attach(AdHoc::on_response("Response Logger", |_req, res| {
@@ -128,5 +145,10 @@ async fn main() {
 
     launch_task.abort();
 
+    // Counter kuota ditulis di background; simpan yang terakhir sebelum keluar
+    if let Some(quota) = quota {
+        quota.flush();
+    }
+
     info!("Server shutdown complete");
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use serde::{Serialize, Deserialize};
+use chrono::FixedOffset;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct City {
@@ -8,3 +9,46 @@ pub struct City {
     pub latitude: f64,
     pub longitude: f64,
 }
+
+/// Provinsi di zona WITA (UTC+8)
+const WITA_PROVINCES: &[&str] = &[
+    "Bali", "NTB", "NTT", "Kalimantan Selatan", "Kalimantan Timur", "Kalimantan Utara",
+    "Sulawesi Barat", "Sulawesi Selatan", "Sulawesi Tengah", "Sulawesi Tenggara", "Sulawesi Utara",
+    "Gorontalo",
+];
+
+/// Provinsi di zona WIT (UTC+9)
+const WIT_PROVINCES: &[&str] = &["Maluku", "Maluku Utara", "Papua", "Papua Barat"];
+
+impl City {
+    /// Zona waktu lokal kota (WIB/WITA/WIT) berdasarkan provinsinya
+    pub fn utc_offset(&self) -> FixedOffset {
+        let hours = if WIT_PROVINCES.contains(&self.province) {
+            9
+        } else if WITA_PROVINCES.contains(&self.province) {
+            8
+        } else {
+            7
+        };
+        FixedOffset::east_opt(hours * 3600).expect("offset WIB/WITA/WIT valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cities::CITIES;
+
+    #[test]
+    fn test_utc_offset_follows_province() {
+        let offset_of = |name: &str| {
+            CITIES
+                .iter()
+                .find(|c| c.name == name)
+                .map(|c| c.utc_offset().local_minus_utc() / 3600)
+                .unwrap()
+        };
+        assert_eq!(offset_of("Jakarta"), 7);
+        assert_eq!(offset_of("Makassar"), 8);
+        assert_eq!(offset_of("Jayapura"), 9);
+    }
+}
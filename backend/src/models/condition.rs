@@ -0,0 +1,235 @@
+use serde::de::Deserializer;
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Intensitas presipitasi (WMO slight/moderate/heavy)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Intensity {
+    Light,
+    Moderate,
+    Heavy,
+}
+
+/// Kategori cuaca standar; semua provider dipetakan ke sini, voting & confidence memakainya.
+/// Urutan varian = urutan keparahan (dipakai sebagai tie-break), `Unknown` paling bawah.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Condition {
+    /// Kode/teks provider yang tidak dikenali
+    Unknown,
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Foggy,
+    Drizzle(Intensity),
+    Rain(Intensity),
+    /// Hujan lokal/sebentar (konvektif), beda dengan hujan merata
+    RainShowers(Intensity),
+    Snow(Intensity),
+    Thunderstorm,
+}
+
+/// Seberapa kasar kondisi dibandingkan saat voting ensemble
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VoteGranularity {
+    /// Kondisi persis, termasuk intensitas
+    Exact,
+    /// Abaikan intensitas; hujan vs hujan lokal tetap dibedakan
+    Type,
+    /// Kategori besar: cerah, berawan, kabut, hujan, salju, badai petir
+    #[default]
+    Category,
+}
+
+impl VoteGranularity {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "exact" => Some(VoteGranularity::Exact),
+            "type" => Some(VoteGranularity::Type),
+            "category" => Some(VoteGranularity::Category),
+            _ => None,
+        }
+    }
+}
+
+impl Condition {
+    /// Label yang dipakai di API
+    pub fn as_str(&self) -> &'static str {
+        use Intensity::*;
+        match self {
+            Condition::Unknown => "Unknown",
+            Condition::Clear => "Clear",
+            Condition::PartlyCloudy => "Partly Cloudy",
+            Condition::Cloudy => "Cloudy",
+            Condition::Foggy => "Foggy",
+            Condition::Drizzle(Light) => "Light Drizzle",
+            Condition::Drizzle(Moderate) => "Drizzle",
+            Condition::Drizzle(Heavy) => "Heavy Drizzle",
+            Condition::Rain(Light) => "Light Rain",
+            Condition::Rain(Moderate) => "Rain",
+            Condition::Rain(Heavy) => "Heavy Rain",
+            Condition::RainShowers(Light) => "Light Rain Showers",
+            Condition::RainShowers(Moderate) => "Rain Showers",
+            Condition::RainShowers(Heavy) => "Heavy Rain Showers",
+            Condition::Snow(Light) => "Light Snow",
+            Condition::Snow(Moderate) => "Snow",
+            Condition::Snow(Heavy) => "Heavy Snow",
+            Condition::Thunderstorm => "Thunderstorm",
+        }
+    }
+
+    /// Nama icon untuk frontend
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Condition::Unknown => "unknown",
+            Condition::Clear => "sunny",
+            Condition::PartlyCloudy | Condition::Cloudy => "cloudy",
+            Condition::Foggy => "fog",
+            Condition::Drizzle(_) | Condition::Rain(_) | Condition::RainShowers(_) => "rainy",
+            Condition::Snow(_) => "snowy",
+            Condition::Thunderstorm => "stormy",
+        }
+    }
+
+    /// Wakil kondisi ini pada tingkat kekasaran `granularity`
+    pub fn coarsen(self, granularity: VoteGranularity) -> Condition {
+        use Condition::*;
+        use Intensity::Moderate;
+        match granularity {
+            VoteGranularity::Exact => self,
+            VoteGranularity::Type => match self {
+                Drizzle(_) => Drizzle(Moderate),
+                Rain(_) => Rain(Moderate),
+                RainShowers(_) => RainShowers(Moderate),
+                Snow(_) => Snow(Moderate),
+                c => c,
+            },
+            VoteGranularity::Category => match self {
+                PartlyCloudy => Cloudy,
+                Drizzle(_) | Rain(_) | RainShowers(_) => Rain(Moderate),
+                Snow(_) => Snow(Moderate),
+                c => c,
+            },
+        }
+    }
+
+    /// Voting kondisi: kelompok terbanyak pada `granularity` menang, lalu kondisi persis
+    /// yang paling sering di kelompok itu yang dilaporkan. Seri → yang paling parah.
+    /// `Unknown` hanya menang kalau tidak ada kondisi lain.
+    pub fn vote(conditions: impl IntoIterator<Item = Condition>, granularity: VoteGranularity) -> Option<Condition> {
+        let conditions: Vec<Condition> = conditions.into_iter().collect();
+        let known: Vec<Condition> = conditions.iter().copied().filter(|c| *c != Condition::Unknown).collect();
+        if known.is_empty() {
+            return conditions.first().copied();
+        }
+
+        let group = majority(known.iter().map(|c| c.coarsen(granularity)))?;
+        majority(known.into_iter().filter(|c| c.coarsen(granularity) == group))
+    }
+}
+
+/// Nilai terbanyak; kalau seri, yang paling besar (paling parah)
+fn majority(conditions: impl Iterator<Item = Condition>) -> Option<Condition> {
+    let mut counts: Vec<(Condition, usize)> = Vec::new();
+    for condition in conditions {
+        match counts.iter_mut().find(|(c, _)| *c == condition) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((condition, 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .max_by_key(|(condition, n)| (*n, *condition))
+        .map(|(condition, _)| condition)
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Intensity::*;
+        let mut all = [
+            Condition::Unknown,
+            Condition::Clear,
+            Condition::PartlyCloudy,
+            Condition::Cloudy,
+            Condition::Foggy,
+            Condition::Thunderstorm,
+        ]
+        .into_iter()
+        .chain([Light, Moderate, Heavy].into_iter().flat_map(|i| {
+            [Condition::Drizzle(i), Condition::Rain(i), Condition::RainShowers(i), Condition::Snow(i)]
+        }));
+
+        all.find(|c| c.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown weather condition '{}'", s))
+    }
+}
+
+impl Serialize for Condition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Condition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let label = String::deserialize(deserializer)?;
+        label.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Intensity::*;
+
+    #[test]
+    fn test_serde_names_match_api() {
+        assert_eq!(serde_json::to_string(&Condition::Thunderstorm).unwrap(), "\"Thunderstorm\"");
+        assert_eq!(serde_json::to_string(&Condition::RainShowers(Heavy)).unwrap(), "\"Heavy Rain Showers\"");
+        let parsed: Condition = serde_json::from_str("\"Light Rain\"").unwrap();
+        assert_eq!(parsed, Condition::Rain(Light));
+        assert_eq!(Condition::PartlyCloudy.to_string(), "Partly Cloudy");
+        assert!(serde_json::from_str::<Condition>("\"Sunshine\"").is_err());
+    }
+
+    #[test]
+    fn test_vote_breaks_ties_by_severity() {
+        use Condition::*;
+        let exact = VoteGranularity::Exact;
+        assert_eq!(Condition::vote([Clear, Rain(Light), Clear], exact), Some(Clear));
+        assert_eq!(Condition::vote([Clear, Thunderstorm, Cloudy], exact), Some(Thunderstorm));
+        assert_eq!(Condition::vote([], exact), None);
+    }
+
+    #[test]
+    fn test_vote_granularity() {
+        use Condition::*;
+        let votes = [Rain(Light), RainShowers(Heavy), Drizzle(Light), Cloudy, Cloudy];
+
+        // Tiap varian hujan berbeda: Cloudy menang 2 suara
+        assert_eq!(Condition::vote(votes, VoteGranularity::Exact), Some(Cloudy));
+        // Kategori hujan 3 suara; yang dilaporkan tetap kondisi aslinya
+        assert_eq!(Condition::vote(votes, VoteGranularity::Category), Some(RainShowers(Heavy)));
+
+        let votes = [Rain(Light), Rain(Heavy), RainShowers(Light)];
+        assert_eq!(Condition::vote(votes, VoteGranularity::Exact), Some(RainShowers(Light)));
+        assert_eq!(Condition::vote(votes, VoteGranularity::Type), Some(Rain(Heavy)));
+    }
+
+    #[test]
+    fn test_unknown_only_wins_alone() {
+        use Condition::*;
+        let granularity = VoteGranularity::Category;
+        assert_eq!(Condition::vote([Unknown, Unknown, Clear], granularity), Some(Clear));
+        assert_eq!(Condition::vote([Unknown], granularity), Some(Unknown));
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use super::{Condition, WeatherDetails};
+
+/// Kondisi saat ini dari satu provider. `observed_at` = waktu observasi dalam zona lokal kota
+/// (RFC 3339). Curah hujan (`precipitation_mm`) adalah akumulasi periode observasi terakhir.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrentWeather {
+    pub observed_at: String,
+    pub temperature: f32,
+    pub feels_like: Option<f32>,
+    pub condition: Condition,
+    #[serde(flatten)]
+    pub details: WeatherDetails,
+}
+
+/// Selisih terbesar-terkecil antar provider untuk kondisi saat ini
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CurrentSpread {
+    pub temperature: f32,
+    pub feels_like: Option<f32>,
+    #[serde(flatten)]
+    pub details: WeatherDetails,
+}
+
+/// Hasil rekonsiliasi kondisi saat ini dari semua provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinalCurrentWeather {
+    pub temperature: f32,
+    pub feels_like: Option<f32>,
+    pub condition: Condition,
+    #[serde(flatten)]
+    pub details: WeatherDetails,
+    pub spread: CurrentSpread,
+    pub provider_count: usize,
+}
+
+/// Kondisi saat ini untuk satu kota (per-source + final)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrentConditions {
+    pub city: String,
+    pub province: String,
+    pub country: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub utc_offset: String,
+    pub source_timestamp: String,
+    pub per_source: BTreeMap<String, CurrentWeather>,
+    pub final_conditions: FinalCurrentWeather,
+}
+
+impl CurrentWeather {
+    pub fn new(observed_at: String, temperature: f32, condition: Condition) -> Self {
+        Self {
+            observed_at,
+            temperature,
+            feels_like: None,
+            condition,
+            details: WeatherDetails::default(),
+        }
+    }
+
+    pub fn with_feels_like(mut self, feels_like: Option<f32>) -> Self {
+        self.feels_like = feels_like;
+        self
+    }
+
+    pub fn with_details(mut self, details: WeatherDetails) -> Self {
+        self.details = details;
+        self
+    }
+}
@@ -1,20 +1,61 @@
+use serde::de::Deserializer;
+use serde::ser::{SerializeMap, Serializer};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use super::Condition;
+
+/// ID provider, dipakai sebagai key di `per_source`
+pub const OPEN_METEO: &str = "open_meteo";
+pub const OPEN_WEATHER: &str = "open_weather";
+pub const WEATHER_API: &str = "weather_api";
+
+/// Key yang selalu ada di JSON `per_source` (null kalau kosong) demi kompatibilitas client lama
+const LEGACY_SOURCES: [&str; 3] = [OPEN_METEO, OPEN_WEATHER, WEATHER_API];
+
+/// Variabel cuaca selain suhu & kondisi; `None` = provider tidak punya datanya
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeatherDetails {
+    /// Kelembapan rata-rata (%)
+    pub humidity: Option<f32>,
+    /// Kecepatan angin maksimum (km/jam)
+    pub wind_speed: Option<f32>,
+    /// Hembusan angin maksimum (km/jam)
+    pub wind_gust: Option<f32>,
+    /// Total curah hujan sehari (mm)
+    pub precipitation_mm: Option<f32>,
+    /// Peluang hujan (%)
+    pub precipitation_probability: Option<f32>,
+}
+
+/// Selisih nilai terbesar dan terkecil antar provider, per variabel
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForecastSpread {
+    pub temp_max: f32,
+    pub temp_min: f32,
+    #[serde(flatten)]
+    pub details: WeatherDetails,
+}
 
 /// Forecast dari satu provider untuk satu hari
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProviderForecast {
     pub date: String,
     pub temp_max: f32,
     pub temp_min: f32,
-    pub condition: String,
+    pub condition: Condition,
+    #[serde(flatten)]
+    pub details: WeatherDetails,
+    /// Nilai turunan (bukan forecast asli provider); tidak ikut dihitung di hasil akhir
+    #[serde(default)]
+    pub extrapolated: bool,
 }
 
-/// Data per-source dari 3 provider
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Data per-source, key-nya ID provider (jumlah provider bebas)
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct PerSourceData {
-    pub open_meteo: Option<ProviderForecast>,
-    pub open_weather: Option<ProviderForecast>,
-    pub weather_api: Option<ProviderForecast>,
+    sources: BTreeMap<String, ProviderForecast>,
 }
 
 /// Hasil ensemble akhir untuk satu hari
@@ -22,8 +63,13 @@ pub struct PerSourceData {
 pub struct FinalForecast {
     pub temp_max: f32,
     pub temp_min: f32,
-    pub condition: String,
+    pub condition: Condition,
     pub confidence: String, // Tingkat confidence: "high", "medium", "low"
+    /// Rata-rata variabel lain dari provider yang punya datanya
+    #[serde(flatten)]
+    pub details: WeatherDetails,
+    #[serde(default)]
+    pub spread: ForecastSpread,
 }
 
 /// Gabungan forecast harian (per-source + final)
@@ -47,70 +93,103 @@ pub struct EnsembleForecast {
 }
 
 impl ProviderForecast {
-    pub fn new(date: String, temp_max: f32, temp_min: f32, condition: String) -> Self {
+    pub fn new(date: String, temp_max: f32, temp_min: f32, condition: Condition) -> Self {
         Self {
             date,
             temp_max,
             temp_min,
             condition,
+            details: WeatherDetails::default(),
+            extrapolated: false,
         }
     }
+
+    pub fn with_details(mut self, details: WeatherDetails) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// Tandai sebagai nilai turunan
+    #[allow(dead_code)]
+    pub fn extrapolated(mut self) -> Self {
+        self.extrapolated = true;
+        self
+    }
 }
 
 impl PerSourceData {
     pub fn new() -> Self {
-        Self {
-            open_meteo: None,
-            open_weather: None,
-            weather_api: None,
-        }
+        Self::default()
     }
 
-    pub fn with_open_meteo(mut self, forecast: ProviderForecast) -> Self {
-        self.open_meteo = Some(forecast);
+    /// Tambah data dari provider dengan ID tertentu (builder style)
+    pub fn with_provider(mut self, provider_id: &str, forecast: ProviderForecast) -> Self {
+        self.insert(provider_id, forecast);
         self
     }
 
-    pub fn with_open_weather(mut self, forecast: ProviderForecast) -> Self {
-        self.open_weather = Some(forecast);
-        self
+    #[allow(dead_code)]
+    pub fn with_open_meteo(self, forecast: ProviderForecast) -> Self {
+        self.with_provider(OPEN_METEO, forecast)
     }
 
-    pub fn with_weather_api(mut self, forecast: ProviderForecast) -> Self {
-        self.weather_api = Some(forecast);
-        self
+    #[allow(dead_code)]
+    pub fn with_open_weather(self, forecast: ProviderForecast) -> Self {
+        self.with_provider(OPEN_WEATHER, forecast)
+    }
+
+    #[allow(dead_code)]
+    pub fn with_weather_api(self, forecast: ProviderForecast) -> Self {
+        self.with_provider(WEATHER_API, forecast)
+    }
+
+    pub fn insert(&mut self, provider_id: &str, forecast: ProviderForecast) {
+        self.sources.insert(provider_id.to_string(), forecast);
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, provider_id: &str) -> Option<&ProviderForecast> {
+        self.sources.get(provider_id)
+    }
+
+    /// Iterasi (ID provider, forecast) terurut berdasarkan ID
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ProviderForecast)> {
+        self.sources.iter().map(|(id, forecast)| (id.as_str(), forecast))
     }
 
     /// Hitung berapa provider yang kasih data
     pub fn provider_count(&self) -> usize {
-        [&self.open_meteo, &self.open_weather, &self.weather_api]
-            .iter()
-            .filter(|p| p.is_some())
-            .count()
+        self.sources.len()
+    }
+
+    /// Tanggal (lokal kota) yang dicocokkan untuk semua provider di hari ini
+    pub fn date(&self) -> Option<&str> {
+        self.sources.values().next().map(|pf| pf.date.as_str())
+    }
+
+    /// Forecast asli saja; nilai `extrapolated` tidak ikut voting
+    fn forecasts(&self) -> impl Iterator<Item = &ProviderForecast> {
+        self.sources.values().filter(|pf| !pf.extrapolated)
     }
 
     /// Ambil semua suhu maksimal yang valid
     pub fn get_max_temperatures(&self) -> Vec<f32> {
-        [&self.open_meteo, &self.open_weather, &self.weather_api]
-            .iter()
-            .filter_map(|p| p.as_ref().map(|pf| pf.temp_max))
-            .collect()
+        self.forecasts().map(|pf| pf.temp_max).collect()
     }
 
     /// Ambil semua suhu minimal yang valid
     pub fn get_min_temperatures(&self) -> Vec<f32> {
-        [&self.open_meteo, &self.open_weather, &self.weather_api]
-            .iter()
-            .filter_map(|p| p.as_ref().map(|pf| pf.temp_min))
-            .collect()
+        self.forecasts().map(|pf| pf.temp_min).collect()
+    }
+
+    /// Ambil humidity/angin/hujan semua provider
+    pub fn get_details(&self) -> Vec<&WeatherDetails> {
+        self.forecasts().map(|pf| &pf.details).collect()
     }
 
     /// Ambil semua kondisi cuaca
-    pub fn get_conditions(&self) -> Vec<String> {
-        [&self.open_meteo, &self.open_weather, &self.weather_api]
-            .iter()
-            .filter_map(|p| p.as_ref().map(|pf| pf.condition.clone()))
-            .collect()
+    pub fn get_conditions(&self) -> Vec<Condition> {
+        self.forecasts().map(|pf| pf.condition).collect()
     }
 
     /// Extract semua suhu untuk dirata-ratakan
@@ -121,15 +200,48 @@ impl PerSourceData {
     }
 }
 
+impl Serialize for PerSourceData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut entries: BTreeMap<&str, Option<&ProviderForecast>> =
+            LEGACY_SOURCES.iter().map(|id| (*id, None)).collect();
+        entries.extend(self.iter().map(|(id, forecast)| (id, Some(forecast))));
+
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for (id, forecast) in entries {
+            map.serialize_entry(id, &forecast)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for PerSourceData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries = BTreeMap::<String, Option<ProviderForecast>>::deserialize(deserializer)?;
+        let sources = entries
+            .into_iter()
+            .filter_map(|(id, forecast)| forecast.map(|f| (id, f)))
+            .collect();
+        Ok(Self { sources })
+    }
+}
+
 impl FinalForecast {
-    pub fn new(temp_max: f32, temp_min: f32, condition: String, confidence: String) -> Self {
+    pub fn new(temp_max: f32, temp_min: f32, condition: Condition, confidence: String) -> Self {
         Self {
             temp_max,
             temp_min,
             condition,
             confidence,
+            details: WeatherDetails::default(),
+            spread: ForecastSpread::default(),
         }
     }
+
+    pub fn with_details(mut self, details: WeatherDetails, spread: ForecastSpread) -> Self {
+        self.details = details;
+        self.spread = spread;
+        self
+    }
 }
 
 impl DayEnsemble {
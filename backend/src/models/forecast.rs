@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use super::Condition;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherForecast {
@@ -17,8 +18,16 @@ pub struct DailyForecast {
     pub temp_max: f32,
     pub temp_min: f32,
     pub temp_avg: f32,
-    pub condition: String,
-    pub humidity: u32,
-    pub wind_speed: f32,
+    pub condition: Condition,
+    /// Kelembapan rata-rata (%); `None` kalau provider tidak punya datanya
+    pub humidity: Option<u32>,
+    /// Kecepatan angin maksimum (km/jam)
+    pub wind_speed: Option<f32>,
+    /// Hembusan angin maksimum (km/jam)
+    pub wind_gust: Option<f32>,
+    /// Total curah hujan (mm)
+    pub precipitation_mm: Option<f32>,
+    /// Peluang hujan (%)
+    pub precipitation_probability: Option<u32>,
     pub icon: String,
 }
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use super::{Condition, WeatherDetails};
+
+/// Forecast satu jam dari satu provider. `time` = jam lokal kota ("YYYY-MM-DDTHH:00").
+/// Curah hujan (`precipitation_mm`) adalah total jam itu saja.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HourlyForecast {
+    pub time: String,
+    pub temperature: f32,
+    pub condition: Condition,
+    #[serde(flatten)]
+    pub details: WeatherDetails,
+    /// Diisi dari slot 3-jam terdekat, bukan nilai asli provider untuk jam ini
+    #[serde(default)]
+    pub interpolated: bool,
+}
+
+/// Selisih terbesar-terkecil antar provider untuk satu jam
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HourlySpread {
+    pub temperature: f32,
+    #[serde(flatten)]
+    pub details: WeatherDetails,
+}
+
+/// Hasil ensemble satu jam
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinalHourlyForecast {
+    pub temperature: f32,
+    pub condition: Condition,
+    #[serde(flatten)]
+    pub details: WeatherDetails,
+    pub spread: HourlySpread,
+    /// Jumlah provider yang punya data untuk jam ini
+    pub provider_count: usize,
+}
+
+/// Gabungan forecast satu jam (per-source + final)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HourEnsemble {
+    pub time: String,
+    pub per_source: BTreeMap<String, HourlyForecast>,
+    pub final_forecast: FinalHourlyForecast,
+}
+
+/// Ensemble per jam untuk satu kota, mulai jam sekarang (waktu lokal)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HourlyEnsembleForecast {
+    pub city: String,
+    pub province: String,
+    pub country: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Offset UTC dari jam-jam di `hours`, misal "+08:00"
+    pub utc_offset: String,
+    pub source_timestamp: String,
+    pub hours: Vec<HourEnsemble>,
+}
+
+impl HourlyForecast {
+    pub fn new(time: String, temperature: f32, condition: Condition) -> Self {
+        Self {
+            time,
+            temperature,
+            condition,
+            details: WeatherDetails::default(),
+            interpolated: false,
+        }
+    }
+
+    pub fn with_details(mut self, details: WeatherDetails) -> Self {
+        self.details = details;
+        self
+    }
+}
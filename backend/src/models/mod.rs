@@ -1,19 +1,29 @@
 use serde::{Deserialize, Serialize};
 
 pub mod city;
+pub mod condition;
 pub mod forecast;
 pub mod ensemble;
+pub mod hourly;
+pub mod current;
 pub mod forecast_request;
 
 #[allow(unused_imports)]
 pub use city::City;
 #[allow(unused_imports)]
+pub use condition::{Condition, Intensity, VoteGranularity};
+#[allow(unused_imports)]
 pub use forecast::{DailyForecast, WeatherForecast};
 #[allow(unused_imports)]
 pub use ensemble::{
-    DayEnsemble, EnsembleForecast, FinalForecast, PerSourceData, ProviderForecast,
+    DayEnsemble, EnsembleForecast, FinalForecast, ForecastSpread, PerSourceData, ProviderForecast,
+    WeatherDetails,
 };
 #[allow(unused_imports)]
+pub use hourly::{FinalHourlyForecast, HourEnsemble, HourlyEnsembleForecast, HourlyForecast, HourlySpread};
+#[allow(unused_imports)]
+pub use current::{CurrentConditions, CurrentSpread, CurrentWeather, FinalCurrentWeather};
+#[allow(unused_imports)]
 pub use forecast_request::ForecastPeriodRequest;
 
 #[allow(dead_code)]
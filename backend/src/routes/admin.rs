@@ -0,0 +1,95 @@
+use rocket::{get, State};
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::serde::json::Json;
+use serde::Serialize;
+use log::{info, warn};
+use std::sync::Arc;
+use crate::errors::{ApiError, ErrorResponse};
+use crate::services::providers::{KeyStatus, KeyUsageReport, ProviderRegistry, UpstreamClient};
+use crate::utils::Config;
+
+/// Isi header `X-API-Key` (kalau ada)
+pub struct ApiKeyHeader(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ApiKeyHeader {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(ApiKeyHeader(req.headers().get_one("X-API-Key").map(str::to_string)))
+    }
+}
+
+/// Cocokkan header dengan `API_KEY`. Tanpa `API_KEY` semua request admin ditolak.
+fn authorize(header: &ApiKeyHeader, config: &Config, context: &str) -> Result<(), (Status, Json<ErrorResponse>)> {
+    let err = match (&config.api_key, &header.0) {
+        (Some(expected), Some(given)) if constant_time_eq(expected.as_bytes(), given.as_bytes()) => return Ok(()),
+        (None, _) => ApiError::unauthorized("Admin endpoints are disabled: API_KEY is not configured"),
+        _ => ApiError::unauthorized("Missing or invalid X-API-Key header"),
+    };
+    warn!("[{}] {}", context, err);
+    Err(err.to_response())
+}
+
+/// Bandingkan tanpa berhenti di byte pertama yang beda, supaya waktu respons tidak membocorkan key
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuotaResponse {
+    pub generated_at: String,
+    pub keys: Vec<KeyUsageReport>,
+}
+
+#[get("/admin/quota")]
+pub fn get_quota(
+    api_key: ApiKeyHeader,
+    config: &State<Config>,
+    upstream: &State<Arc<UpstreamClient>>,
+) -> Result<Json<QuotaResponse>, (Status, Json<ErrorResponse>)> {
+    authorize(&api_key, config, "Admin")?;
+    info!("GET /admin/quota - Upstream usage report");
+
+    let keys = upstream.quota().map(|q| q.report()).unwrap_or_default();
+    Ok(Json(QuotaResponse {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        keys,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeysResponse {
+    pub keys: Vec<KeyStatus>,
+}
+
+#[get("/admin/keys")]
+pub fn get_keys(
+    api_key: ApiKeyHeader,
+    config: &State<Config>,
+    registry: &State<Arc<ProviderRegistry>>,
+) -> Result<Json<KeysResponse>, (Status, Json<ErrorResponse>)> {
+    authorize(&api_key, config, "Admin")?;
+    info!("GET /admin/keys - Provider API key rotation status");
+
+    Ok(Json(KeysResponse {
+        keys: registry.api_keys(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret-key", b"secret-key"));
+        assert!(!constant_time_eq(b"secret-key", b"secret-kez"));
+        assert!(!constant_time_eq(b"secret-key", b"secret"));
+        assert!(!constant_time_eq(b"", b"x"));
+    }
+}
@@ -1,13 +1,22 @@
-use rocket::get;
+use rocket::{get, State};
 use rocket::serde::json::Json;
 use serde::{Serialize, Deserialize};
 use log::info;
+use std::sync::Arc;
+use crate::services::providers::{BreakerSnapshot, BreakerState, ProviderRegistry};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HealthResponse {
     pub status: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ProviderHealthResponse {
+    /// "ok" semua breaker closed, "degraded" sebagian tidak, "down" semua open
+    pub status: String,
+    pub providers: Vec<BreakerSnapshot>,
+}
+
 #[get("/health")]
 pub fn health() -> Json<HealthResponse> {
     info!("GET /health - Health check");
@@ -15,3 +24,24 @@ pub fn health() -> Json<HealthResponse> {
         status: "ok".to_string(),
     })
 }
+
+#[get("/health/providers")]
+pub fn provider_health(registry: &State<Arc<ProviderRegistry>>) -> Json<ProviderHealthResponse> {
+    info!("GET /health/providers - Provider circuit breaker status");
+    let providers = registry.breaker_states();
+
+    let open = providers.iter().filter(|p| p.state == BreakerState::Open).count();
+    let closed = providers.iter().filter(|p| p.state == BreakerState::Closed).count();
+    let status = if closed == providers.len() {
+        "ok"
+    } else if open == providers.len() {
+        "down"
+    } else {
+        "degraded"
+    };
+
+    Json(ProviderHealthResponse {
+        status: status.to_string(),
+        providers,
+    })
+}
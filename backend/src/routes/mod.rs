@@ -5,8 +5,9 @@ use log::info;
 
 pub mod weather;
 pub mod health;
+pub mod admin;
 
-use weather::{get_cities, get_weather, get_weather_parallel, get_ensemble_forecast};
+use weather::{get_cities, get_weather, get_weather_parallel, get_ensemble_forecast, get_hourly_forecast, get_current_conditions};
 
 #[get("/")]
 pub fn index() -> Json<Value> {
@@ -19,5 +20,5 @@ pub fn index() -> Json<Value> {
 }
 
 pub fn routes() -> Vec<rocket::Route> {
-    routes![index, health::health, get_cities, get_weather, get_weather_parallel, get_ensemble_forecast]
+    routes![index, health::health, health::provider_health, admin::get_quota, admin::get_keys, get_cities, get_weather, get_weather_parallel, get_ensemble_forecast, get_hourly_forecast, get_current_conditions]
 }
@@ -1,9 +1,10 @@
 use rocket::{get, State, serde::json::Json, http::Status};
 use log::{info, warn, error, debug};
-use crate::models::{WeatherForecast, City, EnsembleForecast, ForecastPeriodRequest};
-use crate::services::{WeatherService, ForecastCache, EnsembleOrchestrator, find_city, validate_city_input, get_all_cities};
-use crate::utils::Config;
+use crate::models::{WeatherForecast, City, EnsembleForecast, ForecastPeriodRequest, HourlyEnsembleForecast, CurrentConditions};
+use crate::services::{WeatherService, ForecastCache, EnsembleOrchestrator, HourlyOrchestrator, CurrentOrchestrator, find_city, validate_city_input, get_all_cities};
+use crate::services::providers::ProviderRegistry;
 use crate::errors::{ApiError, ErrorResponse};
+use crate::utils::Config;
 use serde::{Serialize, Deserialize};
 use std::sync::Arc;
 use tokio::sync::Semaphore;
@@ -115,7 +116,7 @@ pub async fn get_weather(
         })
         .map_err(|e| {
             error!("[Weather] Provider error for city '{}': {}", city_data.name, e);
-            ApiError::provider_error(&e).to_response()
+            e.to_response()
         })
 }
 
@@ -142,7 +143,7 @@ pub async fn get_weather_parallel(
         })
         .map_err(|e| {
             error!("[WeatherParallel] Provider error for city '{}': {}", city_data.name, e);
-            ApiError::provider_error(&e).to_response()
+            e.to_response()
         })
 }
 
@@ -152,6 +153,7 @@ pub async fn get_ensemble_forecast(
     period: Option<String>,
     day: Option<u32>,
     cache: &State<Arc<ForecastCache<EnsembleForecast>>>,
+    registry: &State<Arc<ProviderRegistry>>,
     config: &State<Config>,
 ) -> Result<Json<EnsembleForecast>, (Status, Json<ErrorResponse>)> {
     // Validasi dan cari city (chained validation)
@@ -178,9 +180,9 @@ pub async fn get_ensemble_forecast(
 
     let orchestrator = EnsembleOrchestrator::new(
         cache.inner().clone(),
-        config.openweather_key.clone(),
-        config.weatherapi_key.clone(),
-    );
+        registry.inner().clone(),
+    )
+    .with_vote_granularity(config.condition_vote_granularity);
 
     orchestrator.get_forecast(&city_data, forecast_period).await
         .map(|ensemble| {
@@ -193,6 +195,50 @@ pub async fn get_ensemble_forecast(
         })
         .map_err(|e| {
             error!("[Ensemble] Failed to fetch ensemble forecast for '{}': {}", city_data.name, e);
-            ApiError::provider_error(&e).to_response()
+            e.to_response()
+        })
+}
+
+#[get("/api/weather/hourly?<city>")]
+pub async fn get_hourly_forecast(
+    city: Option<String>,
+    cache: &State<Arc<ForecastCache<HourlyEnsembleForecast>>>,
+    registry: &State<Arc<ProviderRegistry>>,
+    config: &State<Config>,
+) -> Result<Json<HourlyEnsembleForecast>, (Status, Json<ErrorResponse>)> {
+    let city_data = validate_and_find_city(city, "Hourly")?;
+
+    info!("[Hourly] GET /api/weather/hourly?city={} - Fetching hourly ensemble", city_data.name);
+
+    let orchestrator = HourlyOrchestrator::new(cache.inner().clone(), registry.inner().clone())
+        .with_vote_granularity(config.condition_vote_granularity);
+
+    orchestrator.get_hourly(&city_data).await
+        .map(Json)
+        .map_err(|e| {
+            error!("[Hourly] Failed to fetch hourly ensemble for '{}': {}", city_data.name, e);
+            e.to_response()
+        })
+}
+
+#[get("/api/weather/current?<city>")]
+pub async fn get_current_conditions(
+    city: Option<String>,
+    cache: &State<Arc<ForecastCache<CurrentConditions>>>,
+    registry: &State<Arc<ProviderRegistry>>,
+    config: &State<Config>,
+) -> Result<Json<CurrentConditions>, (Status, Json<ErrorResponse>)> {
+    let city_data = validate_and_find_city(city, "Current")?;
+
+    info!("[Current] GET /api/weather/current?city={} - Fetching current conditions", city_data.name);
+
+    let orchestrator = CurrentOrchestrator::new(cache.inner().clone(), registry.inner().clone())
+        .with_vote_granularity(config.condition_vote_granularity);
+
+    orchestrator.get_current(&city_data).await
+        .map(Json)
+        .map_err(|e| {
+            error!("[Current] Failed to fetch current conditions for '{}': {}", city_data.name, e);
+            e.to_response()
         })
 }
@@ -14,8 +14,12 @@ use log::debug;
 /// 
 /// # Example
 /// ```
+/// # use backend::services::city_service::find_city;
+/// # fn main() -> Result<(), backend::errors::ApiError> {
 /// let city = find_city("Jakarta")?;
 /// println!("Found city: {} at ({}, {})", city.name, city.latitude, city.longitude);
+/// # Ok(())
+/// # }
 /// ```
 pub fn find_city(name: &str) -> Result<City, ApiError> {
     let query = name.trim().to_lowercase();
@@ -1,9 +1,15 @@
-use crate::models::PerSourceData;
+use crate::models::{Condition, PerSourceData, VoteGranularity};
 use log::debug;
 
-/// HIGH: 3 provider, ±2°C, kondisi sama | MEDIUM: 2 provider atau 3 dengan ±4°C | LOW: 1 provider atau beda >4°C
-pub fn calculate_confidence(per_source: &PerSourceData, _final_temps: (f32, f32)) -> String {
-    let provider_count = per_source.provider_count();
+/// HIGH: >=3 provider, ±2°C, kondisi sama | MEDIUM: 2 provider atau 3 dengan ±4°C | LOW: 1 provider atau beda >4°C
+/// Kesepakatan kondisi dinilai pada `granularity` yang sama dengan voting
+pub fn calculate_confidence(
+    per_source: &PerSourceData,
+    _final_temps: (f32, f32),
+    granularity: VoteGranularity,
+) -> String {
+    // Hanya forecast asli yang dihitung; nilai extrapolated tidak menambah keyakinan
+    let provider_count = per_source.get_max_temperatures().len();
     
     debug!("[ConfidenceCalc] Provider count: {}", provider_count);
     
@@ -14,7 +20,11 @@ pub fn calculate_confidence(per_source: &PerSourceData, _final_temps: (f32, f32)
     
     let max_temps = per_source.get_max_temperatures();
     let min_temps = per_source.get_min_temperatures();
-    let conditions = per_source.get_conditions();
+    let conditions: Vec<Condition> = per_source
+        .get_conditions()
+        .into_iter()
+        .map(|c| c.coarsen(granularity))
+        .collect();
     
     if max_temps.is_empty() || min_temps.is_empty() {
         debug!("[ConfidenceCalc] Missing temperature data, returning LOW");
@@ -32,21 +42,21 @@ pub fn calculate_confidence(per_source: &PerSourceData, _final_temps: (f32, f32)
         max_variance, min_variance, avg_variance, condition_agreement
     );
     
-    // HIGH: 3 providers dengan variance rendah (<= 2°C) DAN kondisi sama
-    if provider_count == 3 && avg_variance <= 2.0 && condition_agreement {
-        debug!("[ConfidenceCalc] HIGH - 3 providers, variance {:.2}°C <= 2.0°C, conditions agree", avg_variance);
+    // HIGH: 3+ providers dengan variance rendah (<= 2°C) DAN kondisi sama
+    if provider_count >= 3 && avg_variance <= 2.0 && condition_agreement {
+        debug!("[ConfidenceCalc] HIGH - {} providers, variance {:.2}°C <= 2.0°C, conditions agree", provider_count, avg_variance);
         return "high".to_string();
     }
     
-    // HIGH: 3 providers dengan variance sangat rendah (<= 1.5°C) meskipun kondisi beda
-    if provider_count == 3 && avg_variance <= 1.5 {
-        debug!("[ConfidenceCalc] HIGH - 3 providers, excellent variance {:.2}°C <= 1.5°C", avg_variance);
+    // HIGH: 3+ providers dengan variance sangat rendah (<= 1.5°C) meskipun kondisi beda
+    if provider_count >= 3 && avg_variance <= 1.5 {
+        debug!("[ConfidenceCalc] HIGH - {} providers, excellent variance {:.2}°C <= 1.5°C", provider_count, avg_variance);
         return "high".to_string();
     }
     
-    // MEDIUM: 3 providers dengan variance moderat (2-4°C)
-    if provider_count == 3 && avg_variance > 2.0 && avg_variance <= 4.0 {
-        debug!("[ConfidenceCalc] MEDIUM - 3 providers, moderate variance {:.2}°C (2-4°C)", avg_variance);
+    // MEDIUM: 3+ providers dengan variance moderat (2-4°C)
+    if provider_count >= 3 && avg_variance > 2.0 && avg_variance <= 4.0 {
+        debug!("[ConfidenceCalc] MEDIUM - {} providers, moderate variance {:.2}°C (2-4°C)", provider_count, avg_variance);
         return "medium".to_string();
     }
     
@@ -81,7 +91,7 @@ fn calculate_variance(temps: &[f32]) -> f32 {
     max_deviation
 }
 
-fn check_condition_agreement(conditions: &[String]) -> bool {
+fn check_condition_agreement(conditions: &[Condition]) -> bool {
     if conditions.is_empty() {
         return false;
     }
@@ -90,45 +100,15 @@ fn check_condition_agreement(conditions: &[String]) -> bool {
         return true;
     }
     
-    let normalized: Vec<String> = conditions
+    let max_count = conditions
         .iter()
-        .map(|c| normalize_condition(c))
-        .collect();
-    
-    let mut counts = std::collections::HashMap::new();
-    for cond in &normalized {
-        *counts.entry(cond.clone()).or_insert(0) += 1;
-    }
-    
-    let max_count = counts.values().max().unwrap_or(&0);
+        .map(|c| conditions.iter().filter(|other| *other == c).count())
+        .max()
+        .unwrap_or(0);
     
-    if normalized.len() == 2 {
-        *max_count == 2
+    if conditions.len() == 2 {
+        max_count == 2
     } else {
-        *max_count >= 2
+        max_count >= 2
     }
 }
-
-
-fn normalize_condition(condition: &str) -> String {
-    let lower = condition.to_lowercase();
-    
-    const PATTERNS: &[(&[&str], &str)] = &[
-        (&["clear", "sunny"], "clear"),
-        (&["cloud", "overcast"], "cloudy"),
-        (&["rain", "drizzle", "shower"], "rainy"),
-        (&["storm", "thunder"], "stormy"),
-        (&["snow", "sleet"], "snowy"),
-        (&["fog", "mist", "haze"], "foggy"),
-    ];
-    
-    // Find first matching pattern
-    PATTERNS
-        .iter()
-        .find(|(keywords, _)| keywords.iter().any(|kw| lower.contains(kw)))
-        .map(|(_, category)| category.to_string())
-        .unwrap_or(lower)
-}
-
-
-
@@ -0,0 +1,206 @@
+use crate::errors::ApiError;
+use crate::models::{
+    City, Condition, CurrentConditions, CurrentSpread, CurrentWeather, FinalCurrentWeather, VoteGranularity,
+    WeatherDetails,
+};
+use crate::services::cache::ForecastCache;
+use crate::services::ensemble_fetcher::{blend_details, mean, range};
+use crate::services::providers::{CurrentResult, ProviderError, ProviderRegistry};
+use futures::future::join_all;
+use log::{info, warn};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Hasil fetch kondisi saat ini satu provider
+pub struct ProviderCurrent {
+    pub provider_id: &'static str,
+    pub result: CurrentResult,
+}
+
+/// Kondisi saat ini: observasi terakhir tiap provider direkonsiliasi seperti forecast harian
+/// (rata-rata nilai, voting kondisi). Cache-nya terpisah dengan TTL pendek.
+pub struct CurrentOrchestrator {
+    cache: Arc<ForecastCache<CurrentConditions>>,
+    registry: Arc<ProviderRegistry>,
+    granularity: VoteGranularity,
+}
+
+impl CurrentOrchestrator {
+    pub fn new(cache: Arc<ForecastCache<CurrentConditions>>, registry: Arc<ProviderRegistry>) -> Self {
+        Self {
+            cache,
+            registry,
+            granularity: VoteGranularity::default(),
+        }
+    }
+
+    pub fn with_vote_granularity(mut self, granularity: VoteGranularity) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
+    pub async fn get_current(&self, city: &City) -> Result<CurrentConditions, ApiError> {
+        let cache_key = format!("current:{}", city.name.to_lowercase());
+
+        if let Some(cached) = self.cache.get(&cache_key).await {
+            info!("[Current] Cache HIT: {}", city.name);
+            return Ok(cached);
+        }
+
+        let sources = fetch_current_sources(city, &self.registry).await;
+        let per_source: BTreeMap<String, CurrentWeather> = sources
+            .iter()
+            .filter_map(|source| Some((source.provider_id.to_string(), source.result.as_ref().ok()?.clone())))
+            .collect();
+        if per_source.is_empty() {
+            return Err(to_api_error(&sources));
+        }
+
+        let conditions = CurrentConditions {
+            city: city.name.to_string(),
+            province: city.province.to_string(),
+            country: "Indonesia".to_string(),
+            latitude: city.latitude,
+            longitude: city.longitude,
+            utc_offset: city.utc_offset().to_string(),
+            source_timestamp: chrono::Local::now().to_rfc3339(),
+            final_conditions: calculate_final_current(&per_source, self.granularity),
+            per_source,
+        };
+        self.cache.insert(cache_key, conditions.clone()).await;
+
+        info!(
+            "[Current] Reconciled current conditions for {} from {} provider(s)",
+            city.name,
+            conditions.per_source.len()
+        );
+        Ok(conditions)
+    }
+}
+
+/// Fetch kondisi saat ini paralel ke semua provider di registry
+pub async fn fetch_current_sources(city: &City, registry: &ProviderRegistry) -> Vec<ProviderCurrent> {
+    info!("[Current] Fetching current conditions for {} from {} provider(s)", city.name, registry.len());
+
+    let tasks = registry.providers().iter().map(|provider| async move {
+        let result = registry.fetch_current(provider.as_ref(), city).await;
+        match &result {
+            Ok(current) => info!("[Current] {} observed at {}", provider.name(), current.observed_at),
+            Err(e) if e.is_skipped() => info!("[Current] {} skipped for {}: {}", provider.name(), city.name, e),
+            Err(e) => warn!("[Current] {} error for {}: {}", provider.name(), city.name, e),
+        }
+        ProviderCurrent {
+            provider_id: provider.id(),
+            result,
+        }
+    });
+
+    join_all(tasks).await
+}
+
+/// Rata-rata suhu, feels-like & detail, voting kondisi, plus selisih antar provider
+pub fn calculate_final_current(
+    per_source: &BTreeMap<String, CurrentWeather>,
+    granularity: VoteGranularity,
+) -> FinalCurrentWeather {
+    let temperatures: Vec<f32> = per_source.values().map(|c| c.temperature).collect();
+    let feels_like: Vec<f32> = per_source.values().filter_map(|c| c.feels_like).collect();
+    let details: Vec<&WeatherDetails> = per_source.values().map(|c| &c.details).collect();
+    let (details, details_spread) = blend_details(&details);
+
+    FinalCurrentWeather {
+        temperature: mean(&temperatures).unwrap_or_default(),
+        feels_like: mean(&feels_like),
+        condition: Condition::vote(per_source.values().map(|c| c.condition), granularity)
+            .unwrap_or(Condition::Unknown),
+        details,
+        spread: CurrentSpread {
+            temperature: range(&temperatures).unwrap_or(0.0),
+            feels_like: range(&feels_like),
+            details: details_spread,
+        },
+        provider_count: per_source.len(),
+    }
+}
+
+/// Error API kalau tidak ada provider yang berhasil; provider tanpa API current tidak dihitung
+fn to_api_error(sources: &[ProviderCurrent]) -> ApiError {
+    ApiError::from_provider_errors(sources.iter().filter_map(|source| match &source.result {
+        Err(ProviderError::Unsupported(_)) => None,
+        Err(e) => Some((source.provider_id, e)),
+        Ok(_) => None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::providers::test_support::{
+        open_meteo_fixture, openweather_current_fixture, spawn_stub_server, weatherapi_current_fixture, StubRoute,
+    };
+    use crate::services::providers::UpstreamClient;
+    use crate::utils::Config;
+
+    fn registry(openweather: StubRoute) -> Arc<ProviderRegistry> {
+        let base_url = spawn_stub_server(vec![
+            StubRoute::new("/v1/current.json", 200, &weatherapi_current_fixture(30.0)),
+            StubRoute::new("/v1/forecast", 200, &open_meteo_fixture(30.0)),
+            openweather,
+        ]);
+
+        let mut config = Config::from_env();
+        config.enabled_providers = ["open_meteo", "open_weather", "weather_api"].map(String::from).to_vec();
+        config.openweather_keys = vec!["test-key".to_string()];
+        config.weatherapi_keys = vec!["test-key".to_string()];
+        config.open_meteo_base_url = base_url.clone();
+        config.openweather_base_url = base_url.clone();
+        config.weatherapi_base_url = base_url;
+        config.quota_state_file = None;
+        let upstream = Arc::new(UpstreamClient::from_config(&config).unwrap());
+        Arc::new(ProviderRegistry::from_config(&config, upstream))
+    }
+
+    fn jakarta() -> City {
+        City {
+            id: 1,
+            name: "Jakarta",
+            province: "DKI Jakarta",
+            latitude: -6.2088,
+            longitude: 106.8456,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_providers_are_reconciled() {
+        let openweather = StubRoute::new("/data/2.5/weather", 200, &openweather_current_fixture(29.0));
+        let orchestrator = CurrentOrchestrator::new(Arc::new(ForecastCache::new(60, 10)), registry(openweather));
+        let current = orchestrator.get_current(&jakarta()).await.unwrap();
+
+        assert_eq!(current.utc_offset, "+07:00");
+        assert_eq!(current.per_source["open_weather"].observed_at, "2025-01-06T13:00:00+07:00");
+        assert_eq!(current.per_source["weather_api"].observed_at, "2025-01-06T13:15:00+07:00");
+
+        let final_conditions = &current.final_conditions;
+        assert_eq!(final_conditions.provider_count, 3);
+        // Suhu 28 (OM), 29 (OWM), 30 (WA); feels-like 32, 34, 34.5
+        assert!((final_conditions.temperature - 29.0).abs() < 0.01);
+        assert!((final_conditions.spread.temperature - 2.0).abs() < 0.01);
+        assert!((final_conditions.feels_like.unwrap() - 33.5).abs() < 0.01);
+        // Satu kelompok berawan; berawan sebagian paling sering
+        assert_eq!(final_conditions.condition, Condition::PartlyCloudy);
+        assert!((final_conditions.details.humidity.unwrap() - 72.0).abs() < 0.01);
+        // Hanya OWM yang tidak mengirim gust
+        assert!((final_conditions.details.wind_gust.unwrap() - 23.0).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_failed_provider_is_left_out() {
+        let openweather = StubRoute::new("/data/2.5/weather", 401, r#"{"cod":401}"#);
+        let orchestrator = CurrentOrchestrator::new(Arc::new(ForecastCache::new(60, 10)), registry(openweather));
+        let current = orchestrator.get_current(&jakarta()).await.unwrap();
+
+        assert_eq!(current.final_conditions.provider_count, 2);
+        assert!(!current.per_source.contains_key("open_weather"));
+        assert!((current.final_conditions.temperature - 29.0).abs() < 0.01);
+    }
+}
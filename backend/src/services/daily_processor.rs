@@ -1,76 +1,102 @@
+use crate::errors::ApiError;
 use crate::models::{City, DailyForecast};
-use crate::services::providers::{
-    fetch_open_meteo, 
-    fetch_openweather, 
-    fetch_weatherapi
-};
+use crate::services::providers::{ProviderError, ProviderRegistry, ProviderResult};
+use chrono::NaiveDate;
 use log::{info, warn, error};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
 use tokio::time::timeout;
 
+/// Data provider yang di-share antar task harian dalam satu request.
+/// Tiap provider di-fetch paling banyak sekali, dan hanya kalau memang dibutuhkan fallback.
+pub struct SharedProviderData {
+    city: City,
+    registry: Arc<ProviderRegistry>,
+    results: Vec<OnceCell<ProviderResult>>,
+    /// Tanggal hari ke-0 (hari ini di zona lokal kota)
+    start_date: NaiveDate,
+}
+
+impl SharedProviderData {
+    pub fn new(city: &City, registry: Arc<ProviderRegistry>) -> Self {
+        let results = registry.providers().iter().map(|_| OnceCell::new()).collect();
+        Self {
+            city: city.clone(),
+            registry,
+            results,
+            start_date: chrono::Utc::now().with_timezone(&city.utc_offset()).date_naive(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn with_start_date(mut self, start_date: NaiveDate) -> Self {
+        self.start_date = start_date;
+        self
+    }
+
+    /// Tanggal hari ke-`day`; provider bisa melewatkan hari, jadi data dicari per tanggal
+    fn date_of(&self, day: usize) -> String {
+        (self.start_date + chrono::Duration::days(day as i64)).format("%Y-%m-%d").to_string()
+    }
+
+    /// Error API dari provider yang gagal. Provider yang belum selesai di-fetch
+    /// (task-nya kena timeout harian) dihitung sebagai timeout.
+    pub fn to_api_error(&self) -> ApiError {
+        const UNFINISHED: ProviderError = ProviderError::Timeout;
+        ApiError::from_provider_errors(
+            self.registry
+                .providers()
+                .iter()
+                .zip(&self.results)
+                .filter_map(|(provider, cell)| match cell.get() {
+                    Some(Err(e)) => Some((provider.id(), e)),
+                    Some(Ok(_)) => None,
+                    None => Some((provider.id(), &UNFINISHED)),
+                }),
+        )
+    }
+
+    async fn provider_result(&self, index: usize) -> &ProviderResult {
+        let provider = &self.registry.providers()[index];
+        self.results[index]
+            .get_or_init(|| async {
+                self.registry.fetch(provider.as_ref(), &self.city).await
+            })
+            .await
+    }
+}
+
 /// Proses forecast untuk satu hari dengan timeout dan error handling
 pub async fn process_day(
     day: usize,
-    city: &City,
-    openweather_key: &str,
-    weatherapi_key: &str,
+    shared: &SharedProviderData,
 ) -> Result<DailyForecast, String> {
+    let city = &shared.city;
+    let date = shared.date_of(day);
     let start_time = Instant::now();
     info!("Starting processing day {} for city: {} ({})", day, city.name, city.province);
     
     // Timeout 5 detik per hari
     let process_result = timeout(Duration::from_secs(5), async {
-        // Coba Open-Meteo dulu (gratis, gak butuh API key)
-        info!("Day {}: Attempting Open-Meteo provider", day);
-        match fetch_open_meteo(city.latitude, city.longitude).await {
-            Ok(mut forecast) => {
-                if forecast.len() > day {
-                    info!("Day {}: Successfully retrieved from Open-Meteo", day);
-                    return Ok(forecast.swap_remove(day));
-                }
-                warn!("Day {}: Open-Meteo returned insufficient forecast data", day);
-            }
-            Err(e) => {
-                warn!("Day {}: Open-Meteo provider failed: {:?}", day, e);
-            }
-        }
-
-        // Coba OpenWeatherMap kalau API key-nya ada
-        if !openweather_key.is_empty() && openweather_key != "your-key-here" {
-            info!("Day {}: Attempting OpenWeatherMap provider", day);
-            match fetch_openweather(city.latitude, city.longitude, openweather_key).await {
-                Ok(mut forecast) => {
-                    if forecast.len() > day {
-                        info!("Day {}: Successfully retrieved from OpenWeatherMap", day);
-                        return Ok(forecast.swap_remove(day));
+        // Coba provider sesuai urutan registry (Open-Meteo dulu, gratis tanpa API key)
+        for (index, provider) in shared.registry.providers().iter().enumerate() {
+            info!("Day {}: Attempting {} provider", day, provider.name());
+            match shared.provider_result(index).await {
+                Ok(forecast) => {
+                    if let Some(daily) = forecast.iter().find(|d| d.date == date) {
+                        info!("Day {}: Successfully retrieved from {}", day, provider.name());
+                        return Ok(daily.clone());
                     }
-                    warn!("Day {}: OpenWeatherMap returned insufficient forecast data", day);
+                    warn!("Day {}: {} returned insufficient forecast data", day, provider.name());
                 }
-                Err(e) => {
-                    warn!("Day {}: OpenWeatherMap provider failed: {:?}", day, e);
-                }
-            }
-        } else {
-            warn!("Day {}: OpenWeatherMap API key not configured", day);
-        }
-
-        // Coba WeatherAPI kalau API key-nya ada
-        if !weatherapi_key.is_empty() && weatherapi_key != "your-key-here" {
-            info!("Day {}: Attempting WeatherAPI provider", day);
-            match fetch_weatherapi(city.name, weatherapi_key).await {
-                Ok(mut forecast) => {
-                    if forecast.len() > day {
-                        info!("Day {}: Successfully retrieved from WeatherAPI", day);
-                        return Ok(forecast.swap_remove(day));
-                    }
-                    warn!("Day {}: WeatherAPI returned insufficient forecast data", day);
+                Err(e) if e.is_skipped() => {
+                    info!("Day {}: {} skipped: {}", day, provider.name(), e);
                 }
                 Err(e) => {
-                    warn!("Day {}: WeatherAPI provider failed: {:?}", day, e);
+                    warn!("Day {}: {} provider failed: {}", day, provider.name(), e);
                 }
             }
-        } else {
-            warn!("Day {}: WeatherAPI key not configured", day);
         }
 
         Err("All providers failed for this day".to_string())
@@ -92,4 +118,4 @@ pub async fn process_day(
             Err("Day processing timed out".to_string())
         }
     }
-}
\ No newline at end of file
+}
@@ -1,122 +1,124 @@
-use crate::models::{City, PerSourceData, ProviderForecast};
-use crate::services::providers::{
-    fetch_open_meteo, 
-    fetch_openweather, 
-    fetch_weatherapi
+use crate::errors::ApiError;
+use crate::models::{
+    City, Condition, DailyForecast, ForecastSpread, PerSourceData, ProviderForecast, VoteGranularity, WeatherDetails,
 };
-use log::{info, warn};
+use crate::services::providers::{ProviderError, ProviderRegistry, ProviderResult};
+use chrono::NaiveDate;
 use futures::future::join_all;
+use log::{info, warn};
 
-/// Helper untuk cek apakah API key valid
-fn is_valid_api_key(key: &str) -> bool {
-    !key.is_empty() && key != "your-key-here"
+/// Hasil fetch satu provider untuk seluruh minggu
+pub struct ProviderWeek {
+    pub provider_id: &'static str,
+    pub provider_name: &'static str,
+    /// Jumlah hari yang memang bisa dikirim provider (`ProviderCapabilities::forecast_days`)
+    pub forecast_days: usize,
+    pub result: ProviderResult,
 }
 
-/// Fetch paralel ke 3 provider, kumpulkan data yang berhasil
-pub async fn fetch_ensemble_day(
+/// Data satu hari, atau error dari provider yang seharusnya punya data hari itu
+pub type DayResult = Result<PerSourceData, ApiError>;
+
+/// Data 7 hari mentah dari semua provider (satu kali fetch per provider)
+pub struct EnsembleWeek {
+    sources: Vec<ProviderWeek>,
+}
+
+impl EnsembleWeek {
+    pub fn new(sources: Vec<ProviderWeek>) -> Self {
+        Self { sources }
+    }
+
+    /// Error API dari provider yang gagal. Provider yang mengirim lebih sedikit hari dari
+    /// kemampuannya dihitung `EmptyData`; feed 5 hari OWM sendiri bukan kegagalan.
+    pub fn to_api_error(&self) -> ApiError {
+        self.error_for(|source, days| days.len() < source.forecast_days.min(7))
+    }
+
+    /// Error API untuk hari ke-`day` yang kosong: hanya provider yang jangkauannya sampai
+    /// hari itu yang ikut disalahkan
+    pub fn day_error(&self, day: usize) -> ApiError {
+        let date = self.date_of(day);
+        self.error_for(|source, days| {
+            day < source.forecast_days && !days.iter().any(|d| Some(&d.date) == date.as_ref())
+        })
+    }
+
+    fn error_for(&self, is_short: impl Fn(&ProviderWeek, &[DailyForecast]) -> bool) -> ApiError {
+        const SHORT: ProviderError = ProviderError::EmptyData;
+        ApiError::from_provider_errors(self.sources.iter().filter_map(|source| match &source.result {
+            Err(e) => Some((source.provider_id, e)),
+            Ok(days) if is_short(source, days) => Some((source.provider_id, &SHORT)),
+            Ok(_) => None,
+        }))
+    }
+
+    /// Tanggal hari ke-`day`, dihitung dari tanggal paling awal yang dikirim provider
+    fn date_of(&self, day: usize) -> Option<String> {
+        let start = self.sources.iter()
+            .filter_map(|source| source.result.as_ref().ok()?.first())
+            .filter_map(|first| NaiveDate::parse_from_str(&first.date, "%Y-%m-%d").ok())
+            .min()?;
+        let date = start.checked_add_signed(chrono::Duration::days(day as i64))?;
+        Some(date.format("%Y-%m-%d").to_string())
+    }
+}
+
+/// Fetch paralel ke semua provider di registry, masing-masing cukup sekali untuk seluruh minggu
+pub async fn fetch_ensemble_sources(city: &City, registry: &ProviderRegistry) -> EnsembleWeek {
+    info!("[Ensemble] Fetching weekly data for {} from {} provider(s)", city.name, registry.len());
+
+    let tasks = registry.providers().iter().map(|provider| async move {
+        ProviderWeek {
+            provider_id: provider.id(),
+            provider_name: provider.name(),
+            forecast_days: provider.capabilities().forecast_days,
+            result: registry.fetch(provider.as_ref(), city).await,
+        }
+    });
+
+    EnsembleWeek::new(join_all(tasks).await)
+}
+
+/// Ambil data satu hari dari hasil fetch mingguan, kumpulkan provider yang punya data.
+/// Dicocokkan per tanggal, karena provider bisa melewatkan hari yang datanya kosong.
+pub fn split_ensemble_day(
     day: usize,
     city: &City,
-    openweather_key: &str,
-    weatherapi_key: &str,
+    week: &EnsembleWeek,
 ) -> Result<PerSourceData, String> {
-    info!("[Ensemble] Fetching day {} for {} from all providers", day, city.name);
-    
-    let open_meteo_task = fetch_open_meteo(city.latitude, city.longitude);
-    let open_weather_task = fetch_openweather(city.latitude, city.longitude, openweather_key);
-    let weather_api_task = fetch_weatherapi(city.name, weatherapi_key);
-    
-    let results = tokio::join!(open_meteo_task, open_weather_task, weather_api_task);
-    
-    // Process Open-Meteo (always processed, free API)
-    let per_source = match &results.0 {
-        Ok(forecast) if forecast.len() > day => {
-            let daily = &forecast[day];
-            let provider_forecast = ProviderForecast::new(
-                daily.date.clone(),
-                daily.temp_max,
-                daily.temp_min,
-                daily.condition.clone(),
-            );
-            info!("[Ensemble] Open-Meteo data available for day {}", day);
-            PerSourceData::new().with_open_meteo(provider_forecast)
-        }
-        Ok(_) => {
-            warn!("[Ensemble] Open-Meteo returned insufficient data for day {}", day);
-            PerSourceData::new()
-        }
-        Err(e) => {
-            warn!("[Ensemble] Open-Meteo failed: {}", e);
-            PerSourceData::new()
-        }
-    };
-    
-    // Process OpenWeatherMap (if API key valid)
-    let per_source = if is_valid_api_key(openweather_key) {
-        match &results.1 {
-            Ok(forecast) if forecast.len() > day => {
-                let daily = &forecast[day];
-                let provider_forecast = ProviderForecast::new(
-                    daily.date.clone(),
-                    daily.temp_max,
-                    daily.temp_min,
-                    daily.condition.clone(),
-                );
-                info!("[Ensemble] OpenWeatherMap data available for day {}", day);
-                per_source.with_open_weather(provider_forecast)
-            }
-            Ok(_) => {
-                warn!("[Ensemble] OpenWeatherMap returned insufficient data for day {}", day);
-                per_source
-            }
-            Err(e) => {
-                warn!("[Ensemble] OpenWeatherMap failed: {}", e);
+    let total = week.sources.len();
+    let date = week.date_of(day);
+
+    let per_source = week.sources.iter().fold(PerSourceData::new(), |per_source, source| {
+        match &source.result {
+            Ok(forecast) => match forecast.iter().find(|d| Some(&d.date) == date.as_ref()) {
+                Some(daily) => {
+                    info!("[Ensemble] {} data available for day {}", source.provider_name, day);
+                    per_source.with_provider(source.provider_id, to_provider_forecast(daily))
+                }
+                None => {
+                    warn!("[Ensemble] {} returned insufficient data for day {}", source.provider_name, day);
+                    per_source
+                }
+            },
+            Err(e) if e.is_skipped() => {
+                info!("[Ensemble] {} skipped for {} day {}: {}", source.provider_name, city.name, day, e);
                 per_source
             }
-        }
-    } else {
-        warn!("[Ensemble] OpenWeatherMap API key not configured");
-        per_source
-    };
-    
-    // Process WeatherAPI (if API key valid)
-    let per_source = if is_valid_api_key(weatherapi_key) {
-        match &results.2 {
-            Ok(forecast) if forecast.len() > day => {
-                let daily = &forecast[day];
-                let provider_forecast = ProviderForecast::new(
-                    daily.date.clone(),
-                    daily.temp_max,
-                    daily.temp_min,
-                    daily.condition.clone(),
-                );
-                info!("[Ensemble] WeatherAPI data available for day {}", day);
-                per_source.with_weather_api(provider_forecast)
-            }
-            Ok(_) => {
-                warn!("[Ensemble] WeatherAPI returned insufficient data for day {}", day);
+            Err(e) if e.is_transient_network() => {
+                // Graceful degradation: timeout/koneksi adalah common issue - log sebagai info saja
+                info!("[Ensemble] {} {} for {} day {} (continuing with {}/{} providers)",
+                      source.provider_name, e, city.name, day, per_source.provider_count(), total);
                 per_source
             }
             Err(e) => {
-                // Graceful degradation: timeout tidak fatal
-                let error_str = e.to_string();
-                if error_str.contains("deadline has elapsed") || error_str.contains("timed out") {
-                    // Timeout adalah common issue - log sebagai info saja
-                    info!("[Ensemble] WeatherAPI timeout for {} day {} (continuing with {}/3 providers)", 
-                          city.name, day, per_source.provider_count());
-                } else if error_str.contains("connect") {
-                    info!("[Ensemble] WeatherAPI connection issue for {} day {} (continuing with {}/3 providers)", 
-                          city.name, day, per_source.provider_count());
-                } else {
-                    warn!("[Ensemble] WeatherAPI error for {} day {}: {} (continuing with {}/3 providers)", 
-                          city.name, day, e, per_source.provider_count());
-                }
+                warn!("[Ensemble] {} error for {} day {}: {} (continuing with {}/{} providers)", 
+                      source.provider_name, city.name, day, e, per_source.provider_count(), total);
                 per_source
             }
         }
-    } else {
-        info!("[Ensemble] WeatherAPI key not configured (using Open-Meteo + OpenWeatherMap only)");
-        per_source
-    };
+    });
     
     // Validate at least one provider succeeded
     if per_source.provider_count() == 0 {
@@ -127,34 +129,42 @@ pub async fn fetch_ensemble_day(
     Ok(per_source)
 }
 
-/// Normalisasi condition dari berbagai provider ke kategori standar
-fn normalize_condition(condition: &str) -> &'static str {
-    let condition_lower = condition.to_lowercase();
-    
-    // Mapping berdasarkan keyword (prioritas dari paling spesifik)
-    if condition_lower.contains("thunder") || condition_lower.contains("storm") {
-        "Thunderstorm"
-    } else if condition_lower.contains("snow") || condition_lower.contains("salju") {
-        "Snow"
-    } else if condition_lower.contains("rain") || condition_lower.contains("hujan") || condition_lower.contains("drizzle") {
-        "Rainy"
-    } else if condition_lower.contains("fog") || condition_lower.contains("mist") || condition_lower.contains("kabut") {
-        "Foggy"
-    } else if condition_lower.contains("cloud") || condition_lower.contains("berawan") || condition_lower.contains("overcast") {
-        "Cloudy"
-    } else if condition_lower.contains("clear") || condition_lower.contains("sunny") || condition_lower.contains("cerah") {
-        "Clear"
-    } else if condition_lower.contains("partly") {
-        "Partly Cloudy"
-    } else {
-        "Clear"  // Default fallback
-    }
+fn to_provider_forecast(daily: &DailyForecast) -> ProviderForecast {
+    ProviderForecast::new(
+        daily.date.clone(),
+        daily.temp_max,
+        daily.temp_min,
+        daily.condition,
+    )
+    .with_details(WeatherDetails {
+        humidity: daily.humidity.map(|h| h as f32),
+        wind_speed: daily.wind_speed,
+        wind_gust: daily.wind_gust,
+        precipitation_mm: daily.precipitation_mm,
+        precipitation_probability: daily.precipitation_probability.map(|p| p as f32),
+    })
 }
 
-/// Rata-rata suhu, ambil kondisi yang paling sering muncul (dengan normalisasi)
-pub fn calculate_final_forecast(per_source: &PerSourceData, _date: String) -> Result<(f32, f32, String), String> {
+/// Fetch semua provider lalu ambil satu hari (untuk kebutuhan satu hari saja)
+#[allow(dead_code)]
+pub async fn fetch_ensemble_day(
+    day: usize,
+    city: &City,
+    registry: &ProviderRegistry,
+) -> Result<PerSourceData, String> {
+    info!("[Ensemble] Fetching day {} for {} from all providers", day, city.name);
+
+    let week = fetch_ensemble_sources(city, registry).await;
+    split_ensemble_day(day, city, &week)
+}
+
+/// Rata-rata suhu, ambil kondisi yang paling sering muncul (dibandingkan pada `granularity`)
+pub fn calculate_final_forecast(
+    per_source: &PerSourceData,
+    _date: String,
+    granularity: VoteGranularity,
+) -> Result<(f32, f32, Condition), String> {
     let (max_temps, min_temps) = per_source.extract_temperatures();
-    let conditions = per_source.get_conditions();
     
     if max_temps.is_empty() || min_temps.is_empty() {
         return Err("No temperature data available".to_string());
@@ -163,67 +173,95 @@ pub fn calculate_final_forecast(per_source: &PerSourceData, _date: String) -> Re
     let final_temp_max: f32 = max_temps.iter().sum::<f32>() / max_temps.len() as f32;
     let final_temp_min: f32 = min_temps.iter().sum::<f32>() / min_temps.len() as f32;
     
-    let final_condition = if conditions.is_empty() {
-        "Clear".to_string()
-    } else {
-        // Normalisasi semua condition sebelum voting
-        let normalized_conditions: Vec<&'static str> = conditions
-            .iter()
-            .map(|c| normalize_condition(c))
-            .collect();
-        
-        // Count occurrences dari normalized conditions
-        let counts = normalized_conditions.iter()
-            .fold(std::collections::HashMap::new(), |mut acc, cond| {
-                *acc.entry(*cond).or_insert(0) += 1;
-                acc
-            });
-        
-        // Ambil kondisi dengan vote terbanyak
-        counts.into_iter()
-            .max_by_key(|(_, count)| *count)
-            .map(|(cond, _)| cond.to_string())
-            .unwrap_or_else(|| "Clear".to_string())
-    };
+    // Seri diputus ke kondisi yang lebih parah (lihat `Condition::vote`)
+    let final_condition = Condition::vote(per_source.get_conditions(), granularity).unwrap_or(Condition::Unknown);
     
     Ok((final_temp_max, final_temp_min, final_condition))
 }
 
-/// Fetch 7 hari secara paralel
+/// Rata-rata humidity/angin/hujan dari provider yang melaporkannya, plus selisih
+/// terbesar-terkecil tiap variabel sebagai ukuran seberapa jauh provider berbeda
+pub fn calculate_final_details(per_source: &PerSourceData) -> (WeatherDetails, ForecastSpread) {
+    let (details, details_spread) = blend_details(&per_source.get_details());
+    let (max_temps, min_temps) = per_source.extract_temperatures();
+    let spread = ForecastSpread {
+        temp_max: range(&max_temps).unwrap_or(0.0),
+        temp_min: range(&min_temps).unwrap_or(0.0),
+        details: details_spread,
+    };
+    (details, spread)
+}
+
+/// Rata-rata dan rentang (max − min) tiap variabel; provider tanpa nilai tidak dihitung
+pub fn blend_details(details: &[&WeatherDetails]) -> (WeatherDetails, WeatherDetails) {
+    let blend = |variable: fn(&WeatherDetails) -> Option<f32>| {
+        let values: Vec<f32> = details.iter().filter_map(|d| variable(d)).collect();
+        (mean(&values), range(&values))
+    };
+    let (humidity, humidity_range) = blend(|d| d.humidity);
+    let (wind_speed, wind_speed_range) = blend(|d| d.wind_speed);
+    let (wind_gust, wind_gust_range) = blend(|d| d.wind_gust);
+    let (precipitation_mm, precipitation_mm_range) = blend(|d| d.precipitation_mm);
+    let (precipitation_probability, precipitation_probability_range) = blend(|d| d.precipitation_probability);
+
+    let average = WeatherDetails {
+        humidity,
+        wind_speed,
+        wind_gust,
+        precipitation_mm,
+        precipitation_probability,
+    };
+    let spread = WeatherDetails {
+        humidity: humidity_range,
+        wind_speed: wind_speed_range,
+        wind_gust: wind_gust_range,
+        precipitation_mm: precipitation_mm_range,
+        precipitation_probability: precipitation_probability_range,
+    };
+    (average, spread)
+}
+
+pub fn mean(values: &[f32]) -> Option<f32> {
+    (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+}
+
+pub fn range(values: &[f32]) -> Option<f32> {
+    let max = values.iter().copied().reduce(f32::max)?;
+    let min = values.iter().copied().reduce(f32::min)?;
+    Some(max - min)
+}
+
+/// Fetch tiap provider sekali, lalu pecah jadi 7 hari. Hari tanpa data tetap ada di
+/// posisinya sebagai `Err`, berisi provider yang gagal untuk hari itu.
 pub async fn fetch_ensemble_week(
     city: &City,
-    openweather_key: &str,
-    weatherapi_key: &str,
-) -> Result<Vec<PerSourceData>, String> {
+    registry: &ProviderRegistry,
+) -> Result<Vec<DayResult>, ApiError> {
     info!("[Ensemble] Fetching 7-day ensemble for {}", city.name);
     
-    // BUAT futures UNTUK 7 HARI
-    let futures: Vec<_> = (0..7)
-        .map(|day| fetch_ensemble_day(day, city, openweather_key, weatherapi_key))
-        .collect();
-    
-    let results = join_all(futures).await;
+    let week = fetch_ensemble_sources(city, registry).await;
     
     // Process results 
-    let (per_source_days, failed_count): (Vec<PerSourceData>, usize) = results
-        .into_iter()
+    let (per_source_days, failed_count): (Vec<DayResult>, usize) = (0..7)
+        .map(|day| split_ensemble_day(day, city, &week))
         .enumerate()
         .fold((Vec::new(), 0), |(mut days, fails), (day_idx, result)| {
             match result {
                 Ok(per_source) => {
-                    days.push(per_source);
+                    days.push(Ok(per_source));
                     (days, fails)
                 }
                 Err(e) => {
                     warn!("[Ensemble] Day {} failed: {}", day_idx, e);
-                    days.push(PerSourceData::new());
+                    days.push(Err(week.day_error(day_idx)));
                     (days, fails + 1)
                 }
             }
         });
     
     if failed_count > 4 {
-        return Err(format!("Too many failed days: {}/7", failed_count));
+        warn!("[Ensemble] Too many failed days: {}/7", failed_count);
+        return Err(week.to_api_error());
     }
     
     info!("[Ensemble] Successfully fetched ensemble data: {}/7 days", 7 - failed_count);
@@ -233,7 +271,13 @@ pub async fn fetch_ensemble_week(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::City;
+    use crate::models::{City, Intensity};
+    use crate::models::ensemble::{OPEN_METEO, OPEN_WEATHER, WEATHER_API};
+    use crate::services::providers::test_support::{
+        open_meteo_fixture, openweather_fixture, spawn_stub_server, StubRoute,
+    };
+    use crate::services::providers::{ApiKeyPool, OpenMeteoProvider, OpenWeatherProvider, UpstreamClient};
+    use std::sync::Arc;
 
     fn create_test_city() -> City {
         City {
@@ -248,14 +292,25 @@ mod tests {
     #[tokio::test]
     async fn test_fetch_ensemble_day_structure() {
         let city = create_test_city();
-        
-       
-        let result = fetch_ensemble_day(0, &city, "invalid", "invalid").await;
-        
-       
-        if let Ok(per_source) = result {
-            assert!(per_source.provider_count() >= 1);
-        }
+        let base_url = spawn_stub_server(vec![
+            StubRoute::new("/v1/forecast", 200, &open_meteo_fixture(30.0)),
+            StubRoute::new("/data/2.5/forecast", 200, &openweather_fixture(40.0)),
+        ]);
+        let upstream = Arc::new(UpstreamClient::default());
+        let registry = ProviderRegistry::new(vec![
+            Arc::new(OpenMeteoProvider::new(upstream.clone(), base_url.clone())),
+            Arc::new(OpenWeatherProvider::new(upstream, base_url, ApiKeyPool::single(OPEN_WEATHER, "test-key"))),
+        ]);
+
+        let per_source = fetch_ensemble_day(2, &city, &registry).await.unwrap();
+
+        assert_eq!(per_source.provider_count(), 2);
+        let open_meteo = per_source.get(OPEN_METEO).unwrap();
+        assert_eq!(open_meteo.date, "2025-01-08");
+        assert_eq!(open_meteo.temp_max, 32.0);
+        let open_weather = per_source.get(OPEN_WEATHER).unwrap();
+        assert_eq!(open_weather.date, "2025-01-08");
+        assert_eq!(open_weather.temp_max, 42.0);
     }
 
     #[test]
@@ -265,36 +320,179 @@ mod tests {
                 "2024-01-01".to_string(),
                 30.0,
                 22.0,
-                "Sunny".to_string(),
+                Condition::Clear,
             ))
             .with_open_weather(ProviderForecast::new(
                 "2024-01-01".to_string(),
                 31.0,
                 23.0,
-                "Clear".to_string(),
+                Condition::Clear,
             ));
 
-        let result = calculate_final_forecast(&per_source, "2024-01-01".to_string());
+        let result = calculate_final_forecast(&per_source, "2024-01-01".to_string(), VoteGranularity::Category);
         assert!(result.is_ok());
         
         let (temp_max, temp_min, condition) = result.unwrap();
         assert!((temp_max - 30.5).abs() < 0.1);
         assert!((temp_min - 22.5).abs() < 0.1);
-        assert!(!condition.is_empty());
+        assert_eq!(condition, Condition::Clear);
     }
 
     #[test]
     fn test_calculate_final_forecast_no_data() {
         let per_source = PerSourceData::new();
-        let result = calculate_final_forecast(&per_source, "2024-01-01".to_string());
+        let result = calculate_final_forecast(&per_source, "2024-01-01".to_string(), VoteGranularity::Category);
         assert!(result.is_err());
     }
 
+    fn create_test_week(offset: f32) -> Vec<DailyForecast> {
+        (0..7)
+            .map(|i| DailyForecast {
+                date: format!("2024-01-0{}", i + 1),
+                temp_max: 30.0 + i as f32 + offset,
+                temp_min: 22.0 + i as f32 + offset,
+                temp_avg: 26.0 + i as f32 + offset,
+                condition: Condition::Cloudy,
+                humidity: Some(80),
+                wind_speed: None,
+                wind_gust: None,
+                precipitation_mm: None,
+                precipitation_probability: None,
+                icon: "cloudy".to_string(),
+            })
+            .collect()
+    }
+
+    fn test_source(provider_id: &'static str, result: ProviderResult) -> ProviderWeek {
+        ProviderWeek {
+            provider_id,
+            provider_name: provider_id,
+            forecast_days: if provider_id == OPEN_WEATHER { 5 } else { 7 },
+            result,
+        }
+    }
+
     #[test]
-    fn test_is_valid_api_key() {
-        assert!(!is_valid_api_key(""));
-        assert!(!is_valid_api_key("your-key-here"));
-        assert!(is_valid_api_key("valid-api-key-123"));
-        assert!(is_valid_api_key("abc123xyz"));
+    fn test_split_ensemble_day_uses_same_index_per_provider() {
+        let city = create_test_city();
+        let week = EnsembleWeek::new(vec![
+            test_source(OPEN_METEO, Ok(create_test_week(0.0))),
+            test_source(OPEN_WEATHER, Ok(create_test_week(1.0))),
+            test_source(WEATHER_API, Err(ProviderError::Connection("WeatherAPI down".to_string()))),
+        ]);
+
+        let per_source = split_ensemble_day(3, &city, &week).unwrap();
+        assert_eq!(per_source.provider_count(), 2);
+        assert_eq!(per_source.get(OPEN_METEO).unwrap().date, "2024-01-04");
+        assert_eq!(per_source.get(OPEN_METEO).unwrap().temp_max, 33.0);
+        assert_eq!(per_source.get(OPEN_WEATHER).unwrap().temp_max, 34.0);
+        assert!(per_source.get(WEATHER_API).is_none());
+    }
+
+    #[test]
+    fn test_split_ensemble_day_matches_by_date() {
+        let city = create_test_city();
+        // Open-Meteo melewatkan hari ke-2 (data null); urutan index jadi bergeser
+        let gappy_week = create_test_week(0.0).into_iter().filter(|d| d.date != "2024-01-02").collect();
+        let week = EnsembleWeek::new(vec![
+            test_source(OPEN_METEO, Ok(gappy_week)),
+            test_source(OPEN_WEATHER, Ok(create_test_week(1.0))),
+        ]);
+
+        let day_1 = split_ensemble_day(1, &city, &week).unwrap();
+        assert!(day_1.get(OPEN_METEO).is_none());
+        assert_eq!(day_1.get(OPEN_WEATHER).unwrap().date, "2024-01-02");
+
+        let day_2 = split_ensemble_day(2, &city, &week).unwrap();
+        assert_eq!(day_2.get(OPEN_METEO).unwrap().date, "2024-01-03");
+        assert_eq!(day_2.get(OPEN_WEATHER).unwrap().date, "2024-01-03");
+    }
+
+    #[test]
+    fn test_short_provider_is_absent_for_missing_days() {
+        let city = create_test_city();
+        let short_week = create_test_week(1.0).into_iter().take(5).collect();
+        let week = EnsembleWeek::new(vec![
+            test_source(OPEN_METEO, Ok(create_test_week(0.0))),
+            test_source(OPEN_WEATHER, Ok(short_week)),
+        ]);
+
+        let per_source = split_ensemble_day(6, &city, &week).unwrap();
+        assert_eq!(per_source.provider_count(), 1);
+        assert!(per_source.get(OPEN_WEATHER).is_none());
+    }
+
+    #[test]
+    fn test_short_feed_within_capability_is_not_blamed() {
+        let short_week = create_test_week(1.0).into_iter().take(5).collect();
+        let week = EnsembleWeek::new(vec![
+            test_source(OPEN_WEATHER, Ok(short_week)),
+            test_source(WEATHER_API, Err(ProviderError::Timeout)),
+        ]);
+
+        let failed = |error: ApiError| match error {
+            ApiError::UpstreamTimeout(failed) => failed.into_iter().map(|f| f.provider).collect::<Vec<_>>(),
+            other => panic!("Expected UpstreamTimeout, got {:?}", other),
+        };
+        assert_eq!(failed(week.to_api_error()), vec![WEATHER_API]);
+        // Hari ke-6 di luar jangkauan OWM, jadi hanya WeatherAPI yang disalahkan
+        assert_eq!(failed(week.day_error(6)), vec![WEATHER_API]);
+    }
+
+    #[test]
+    fn test_extrapolated_values_do_not_vote() {
+        let per_source = PerSourceData::new()
+            .with_open_meteo(ProviderForecast::new("2024-01-07".to_string(), 30.0, 22.0, Condition::Rain(Intensity::Heavy)))
+            .with_open_weather(
+                ProviderForecast::new("2024-01-07".to_string(), 36.0, 28.0, Condition::Clear).extrapolated(),
+            );
+
+        let (temp_max, temp_min, condition) = calculate_final_forecast(&per_source, "2024-01-07".to_string(), VoteGranularity::Category).unwrap();
+        assert_eq!((temp_max, temp_min), (30.0, 22.0));
+        assert_eq!(condition, Condition::Rain(Intensity::Heavy));
+        // Tetap tampil di per_source dengan penandanya
+        assert!(per_source.get(OPEN_WEATHER).unwrap().extrapolated);
+    }
+
+    #[test]
+    fn test_final_details_average_only_reporting_providers() {
+        let details = |humidity, precipitation_mm| WeatherDetails {
+            humidity: Some(humidity),
+            precipitation_mm,
+            ..WeatherDetails::default()
+        };
+        let per_source = PerSourceData::new()
+            .with_open_meteo(
+                ProviderForecast::new("2024-01-07".to_string(), 30.0, 22.0, Condition::Cloudy)
+                    .with_details(details(70.0, Some(2.0))),
+            )
+            .with_open_weather(
+                ProviderForecast::new("2024-01-07".to_string(), 33.0, 23.0, Condition::Cloudy)
+                    .with_details(details(80.0, None)),
+            )
+            .with_weather_api(
+                ProviderForecast::new("2024-01-07".to_string(), 31.0, 25.0, Condition::Cloudy)
+                    .with_details(details(90.0, Some(6.0))),
+            );
+
+        let (final_details, spread) = calculate_final_details(&per_source);
+        assert_eq!(final_details.humidity, Some(80.0));
+        // Provider tanpa data hujan tidak dihitung sebagai 0 mm
+        assert_eq!(final_details.precipitation_mm, Some(4.0));
+        assert_eq!(final_details.wind_speed, None);
+
+        assert_eq!(spread.temp_max, 3.0);
+        assert_eq!(spread.temp_min, 3.0);
+        assert_eq!(spread.details.humidity, Some(20.0));
+        assert_eq!(spread.details.precipitation_mm, Some(4.0));
+        assert_eq!(spread.details.wind_gust, None);
+    }
+
+    #[test]
+    fn test_split_ensemble_day_all_missing() {
+        let city = create_test_city();
+        let week = EnsembleWeek::new(vec![test_source(OPEN_METEO, Ok(create_test_week(0.0)))]);
+
+        assert!(split_ensemble_day(7, &city, &week).is_err());
     }
 }
@@ -1,34 +1,43 @@
-use crate::models::{City, EnsembleForecast, ForecastPeriodRequest, DayEnsemble, FinalForecast};
+use crate::errors::ApiError;
+use crate::models::{
+    City, EnsembleForecast, ForecastPeriodRequest, DayEnsemble, FinalForecast, PerSourceData, VoteGranularity,
+};
 use crate::services::cache::ForecastCache;
-use crate::services::ensemble_fetcher::{fetch_ensemble_week, calculate_final_forecast};
+use crate::services::ensemble_fetcher::{fetch_ensemble_week, calculate_final_details, calculate_final_forecast};
 use crate::services::confidence_calculator::calculate_confidence;
+use crate::services::providers::{ProviderError, ProviderRegistry};
 use crate::utils::date_utils::{get_forecast_dates, ForecastPeriod};
 use std::sync::Arc;
 
 pub struct EnsembleOrchestrator {
     cache: Arc<ForecastCache<EnsembleForecast>>,
-    openweather_key: String,
-    weatherapi_key: String,
+    registry: Arc<ProviderRegistry>,
+    granularity: VoteGranularity,
 }
 
 impl EnsembleOrchestrator {
     pub fn new(
         cache: Arc<ForecastCache<EnsembleForecast>>,
-        openweather_key: String,
-        weatherapi_key: String,
+        registry: Arc<ProviderRegistry>,
     ) -> Self {
         Self {
             cache,
-            openweather_key,
-            weatherapi_key,
+            registry,
+            granularity: VoteGranularity::default(),
         }
     }
 
+    /// Tingkat kekasaran kondisi saat voting (`CONDITION_VOTE_GRANULARITY`)
+    pub fn with_vote_granularity(mut self, granularity: VoteGranularity) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
     pub async fn get_forecast(
         &self,
         city: &City,
         period: ForecastPeriodRequest,
-    ) -> Result<EnsembleForecast, String> {
+    ) -> Result<EnsembleForecast, ApiError> {
         match period {
             ForecastPeriodRequest::CurrentWeek => {
                 self.get_current_week(city).await
@@ -39,7 +48,7 @@ impl EnsembleOrchestrator {
         }
     }
 
-    async fn get_current_week(&self, city: &City) -> Result<EnsembleForecast, String> {
+    async fn get_current_week(&self, city: &City) -> Result<EnsembleForecast, ApiError> {
         let cache_key = format!("forecast:{}:current_week", city.name.to_lowercase());
 
         if let Some(cached) = self.cache.get(&cache_key).await {
@@ -49,14 +58,7 @@ impl EnsembleOrchestrator {
 
         log::info!("[Orchestrator] Fetching current week for {}", city.name);
 
-        let per_source_days = fetch_ensemble_week(
-            city,
-            &self.openweather_key,
-            &self.weatherapi_key,
-        ).await?;
-
-        let dates = get_forecast_dates(ForecastPeriod::CurrentWeek)
-            .map_err(|e| format!("Date calculation error: {}", e))?;
+        let per_source_days = fetch_ensemble_week(city, &self.registry).await?;
 
         let mut forecast = EnsembleForecast::new(
             city.name.to_string(),
@@ -66,19 +68,23 @@ impl EnsembleOrchestrator {
             city.longitude,
         );
 
-        for (idx, per_source) in per_source_days.iter().enumerate() {
-            let date: String = dates.get(idx)
-                .ok_or_else(|| format!("Missing date for day {}", idx))?
-                .clone();
-
-            let (temp_max, temp_min, condition) = calculate_final_forecast(per_source, date.clone())?;
-
-            let confidence = calculate_confidence(per_source, (temp_max, temp_min));
-
-            let final_forecast = FinalForecast::new(temp_max, temp_min, condition, confidence);
-            let day_ensemble = DayEnsemble::new(date, per_source.clone(), final_forecast);
-
-            forecast.add_day(day_ensemble);
+        for (idx, day) in per_source_days.into_iter().enumerate() {
+            // Label hari diambil dari tanggal provider yang dicocokkan (waktu lokal kota),
+            // bukan dari jam server
+            let day = day.and_then(|per_source| {
+                let date = per_source
+                    .date()
+                    .map(str::to_string)
+                    .ok_or_else(|| ApiError::internal_error(&format!("Missing date for day {}", idx)))?;
+                self.build_day(city, date, per_source)
+            });
+
+            // Hari tanpa data dari provider mana pun dilewati; `fetch_ensemble_week` sudah
+            // menggagalkan request kalau terlalu banyak hari yang kosong
+            match day {
+                Ok(day_ensemble) => forecast.add_day(day_ensemble),
+                Err(e) => log::warn!("[Orchestrator] Skipping day {} for {}: {}", idx, city.name, e),
+            }
         }
 
         self.cache.insert(cache_key, forecast.clone()).await;
@@ -90,9 +96,9 @@ impl EnsembleOrchestrator {
     }
 
     /// Ambil forecast minggu depan (satu hari)
-    async fn get_next_week(&self, city: &City, base_day: u32) -> Result<EnsembleForecast, String> {
+    async fn get_next_week(&self, city: &City, base_day: u32) -> Result<EnsembleForecast, ApiError> {
         if base_day > 6 {
-            return Err(format!("Invalid day: {}", base_day));
+            return Err(ApiError::invalid_params(&format!("Invalid day: {}", base_day)));
         }
 
         let cache_key = format!(
@@ -111,10 +117,10 @@ impl EnsembleOrchestrator {
 
         // Ambil tanggal target (D+7 minggu depan)
         let dates = get_forecast_dates(ForecastPeriod::NextWeek { base_day })
-            .map_err(|e| format!("Date calculation error: {}", e))?;
+            .map_err(|e| ApiError::internal_error(&format!("Date calculation error: {}", e)))?;
 
         if dates.is_empty() {
-            return Err("No target date calculated for next week".to_string());
+            return Err(ApiError::internal_error("No target date calculated for next week"));
         }
 
         let target_date = &dates[0];
@@ -122,11 +128,7 @@ impl EnsembleOrchestrator {
 
         // Ambil data ensemble untuk 7 hari ke depan (biar dapet D+7)
         // Harus fetch 7 hari karena provider kasih forecast berurutan
-        let per_source_days = fetch_ensemble_week(
-            city,
-            &self.openweather_key,
-            &self.weatherapi_key,
-        ).await?;
+        let per_source_days = fetch_ensemble_week(city, &self.registry).await?;
 
         // Buat EnsembleForecast cuma untuk satu hari ini
         // Catatan: Untuk D+7, kita cuma tampilkan forecast 1 hari
@@ -142,20 +144,11 @@ impl EnsembleOrchestrator {
         // Untuk sementara, pakai hari ke-7 (index 6) sebagai proxy D+7
         // Ini limitasi API gratis yang biasanya cuma kasih 7 hari
         let day_idx = 6; // Hari terakhir dari forecast 7 hari
-        if let Some(per_source) = per_source_days.get(day_idx) {
-            // Calculate final forecast values
-            let (temp_max, temp_min, condition) = calculate_final_forecast(per_source, target_date.clone())?;
-
-            // Calculate confidence level
-            let confidence = calculate_confidence(per_source, (temp_max, temp_min));
-
-            let final_forecast = FinalForecast::new(temp_max, temp_min, condition, confidence);
-            let day_ensemble = DayEnsemble::new(target_date.clone(), per_source.clone(), final_forecast);
-
-            forecast.add_day(day_ensemble);
-        } else {
-            return Err("Failed to fetch next week forecast".to_string());
-        }
+        let day = per_source_days
+            .into_iter()
+            .nth(day_idx)
+            .ok_or_else(|| ApiError::internal_error("Missing last day of the weekly forecast"))?;
+        forecast.add_day(self.build_day(city, target_date.clone(), day?)?);
 
         // Cache the result
         self.cache.insert(cache_key, forecast.clone()).await;
@@ -164,4 +157,198 @@ impl EnsembleOrchestrator {
 
         Ok(forecast)
     }
+
+    /// Forecast final, confidence, serta humidity/angin/hujan dan spread-nya untuk satu hari
+    fn build_day(&self, city: &City, date: String, per_source: PerSourceData) -> Result<DayEnsemble, ApiError> {
+        let (temp_max, temp_min, condition) = calculate_final_forecast(&per_source, date.clone(), self.granularity)
+            .map_err(|e| no_usable_data(city, &per_source, &e))?;
+
+        let confidence = calculate_confidence(&per_source, (temp_max, temp_min), self.granularity);
+
+        let (details, spread) = calculate_final_details(&per_source);
+
+        let final_forecast = FinalForecast::new(temp_max, temp_min, condition, confidence)
+            .with_details(details, spread);
+        Ok(DayEnsemble::new(date, per_source, final_forecast))
+    }
+}
+
+/// Data provider ada tapi tidak cukup untuk menghitung forecast final;
+/// provider yang datanya tidak terpakai dilaporkan sebagai `EmptyData`
+fn no_usable_data(city: &City, per_source: &PerSourceData, reason: &str) -> ApiError {
+    const UNUSABLE: ProviderError = ProviderError::EmptyData;
+    log::warn!("[Orchestrator] No usable provider data for {}: {}", city.name, reason);
+    ApiError::from_provider_errors(per_source.iter().map(|(provider_id, _)| (provider_id, &UNUSABLE)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::providers::test_support::{
+        open_meteo_fixture, openweather_fixture, spawn_stub_server, weatherapi_fixture, StubRoute,
+    };
+    use crate::services::providers::{FixtureMode, UpstreamClient};
+    use crate::models::Condition;
+    use crate::utils::Config;
+
+    fn create_test_city() -> City {
+        City {
+            id: 1,
+            name: "Jakarta",
+            province: "DKI Jakarta",
+            latitude: -6.2088,
+            longitude: 106.8456,
+        }
+    }
+
+    fn stubbed_config() -> Config {
+        stubbed_config_with(vec![
+            StubRoute::new("/v1/forecast.json", 200, &weatherapi_fixture(32.0)),
+            StubRoute::new("/v1/forecast", 200, &open_meteo_fixture(30.0)),
+            StubRoute::new("/data/2.5/forecast", 200, &openweather_fixture(31.0)),
+        ])
+    }
+
+    /// Hanya OWM yang berhasil, dengan feed 5 hari seperti aslinya
+    fn openweather_only_config() -> Config {
+        let mut five_days: serde_json::Value = serde_json::from_str(&openweather_fixture(31.0)).unwrap();
+        five_days["list"].as_array_mut().unwrap().truncate(5);
+        stubbed_config_with(vec![
+            StubRoute::new("/v1/forecast.json", 401, r#"{"error":{"code":2008}}"#),
+            StubRoute::new("/v1/forecast", 403, "{}"),
+            StubRoute::new("/data/2.5/forecast", 200, &five_days.to_string()),
+        ])
+    }
+
+    fn stubbed_config_with(routes: Vec<StubRoute>) -> Config {
+        let base_url = spawn_stub_server(routes);
+
+        let mut config = Config::from_env();
+        config.enabled_providers = vec![
+            "open_meteo".to_string(),
+            "open_weather".to_string(),
+            "weather_api".to_string(),
+        ];
+        config.openweather_keys = vec!["test-key".to_string()];
+        config.weatherapi_keys = vec!["test-key".to_string()];
+        config.open_meteo_base_url = base_url.clone();
+        config.openweather_base_url = base_url.clone();
+        config.weatherapi_base_url = base_url;
+        config.quota_state_file = None;
+        config
+    }
+
+    fn registry_for(config: &Config) -> Arc<ProviderRegistry> {
+        let upstream = Arc::new(UpstreamClient::from_config(config).unwrap());
+        Arc::new(ProviderRegistry::from_config(config, upstream))
+    }
+
+    #[tokio::test]
+    async fn test_current_week_against_stub_providers() {
+        let registry = registry_for(&stubbed_config());
+        let cache = Arc::new(ForecastCache::new(60, 10));
+        let orchestrator = EnsembleOrchestrator::new(cache, registry);
+
+        let forecast = orchestrator
+            .get_forecast(&create_test_city(), ForecastPeriodRequest::CurrentWeek)
+            .await
+            .unwrap();
+
+        assert_eq!(forecast.days.len(), 7);
+        for (i, day) in forecast.days.iter().enumerate() {
+            assert_eq!(day.date, format!("2025-01-{:02}", 6 + i));
+            assert!(day.per_source.iter().all(|(_, pf)| pf.date == day.date));
+            assert_eq!(day.per_source.provider_count(), 3);
+            // Rata-rata (30 + 31 + 32) / 3 = 31, naik 1°C tiap hari
+            assert!((day.final_forecast.temp_max - (31.0 + i as f32)).abs() < 0.01);
+            assert!((day.final_forecast.temp_min - (23.0 + i as f32)).abs() < 0.01);
+            assert_eq!(day.final_forecast.condition, Condition::Cloudy);
+            assert_eq!(day.final_forecast.confidence, "high");
+            assert_eq!(day.final_forecast.details.humidity, Some(80.0));
+            assert!((day.final_forecast.details.precipitation_mm.unwrap() - 2.4).abs() < 0.01);
+            assert!((day.final_forecast.spread.temp_max - 2.0).abs() < 0.01);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_days_without_data_are_skipped() {
+        let orchestrator = EnsembleOrchestrator::new(
+            Arc::new(ForecastCache::new(60, 10)),
+            registry_for(&openweather_only_config()),
+        );
+
+        let forecast = orchestrator
+            .get_forecast(&create_test_city(), ForecastPeriodRequest::CurrentWeek)
+            .await
+            .unwrap();
+        assert_eq!(forecast.days.len(), 5);
+        assert_eq!(forecast.days[4].date, "2025-01-10");
+    }
+
+    #[tokio::test]
+    async fn test_empty_next_week_day_reports_failed_providers() {
+        let orchestrator = EnsembleOrchestrator::new(
+            Arc::new(ForecastCache::new(60, 10)),
+            registry_for(&openweather_only_config()),
+        );
+
+        let err = orchestrator
+            .get_forecast(&create_test_city(), ForecastPeriodRequest::NextWeek { base_day: 0 })
+            .await
+            .unwrap_err();
+        let ApiError::ProvidersUnavailable(failed) = err else {
+            panic!("Expected ProvidersUnavailable, got {:?}", err);
+        };
+        // OWM memang tidak sampai hari ke-7, jadi tidak ikut disalahkan
+        let providers: Vec<&str> = failed.iter().map(|f| f.provider.as_str()).collect();
+        assert_eq!(providers, vec!["open_meteo", "weather_api"]);
+    }
+
+    #[tokio::test]
+    async fn test_recorded_fixtures_replay_without_network() {
+        let fixture_dir = std::env::temp_dir().join(format!(
+            "weather-fixtures-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let city = create_test_city();
+
+        // Record dari stub server
+        let mut config = stubbed_config();
+        config.fixture_mode = FixtureMode::Record;
+        config.fixture_dir = fixture_dir.to_string_lossy().to_string();
+        config.fixture_date = Some("2025-01-06".to_string());
+        let recorder = EnsembleOrchestrator::new(
+            Arc::new(ForecastCache::new(60, 10)),
+            registry_for(&config),
+        );
+        let recorded = recorder
+            .get_forecast(&city, ForecastPeriodRequest::CurrentWeek)
+            .await
+            .unwrap();
+        assert!(fixture_dir.join("weather_api").join("-6.2088_106.8456").join("2025-01-06.json").exists());
+
+        // Replay dengan base URL yang tidak bisa dihubungi
+        config.fixture_mode = FixtureMode::Replay;
+        config.open_meteo_base_url = "http://127.0.0.1:9".to_string();
+        config.openweather_base_url = "http://127.0.0.1:9".to_string();
+        config.weatherapi_base_url = "http://127.0.0.1:9".to_string();
+        let replayer = EnsembleOrchestrator::new(
+            Arc::new(ForecastCache::new(60, 10)),
+            registry_for(&config),
+        );
+        let replayed = replayer
+            .get_forecast(&city, ForecastPeriodRequest::CurrentWeek)
+            .await
+            .unwrap();
+
+        let _ = std::fs::remove_dir_all(&fixture_dir);
+
+        assert_eq!(replayed.days.len(), recorded.days.len());
+        for (a, b) in recorded.days.iter().zip(replayed.days.iter()) {
+            assert_eq!(a.per_source, b.per_source);
+            assert_eq!(a.final_forecast.temp_max, b.final_forecast.temp_max);
+            assert_eq!(a.final_forecast.condition, b.final_forecast.condition);
+        }
+    }
 }
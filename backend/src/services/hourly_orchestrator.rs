@@ -0,0 +1,280 @@
+use crate::errors::ApiError;
+use crate::models::{
+    City, Condition, FinalHourlyForecast, HourEnsemble, HourlyEnsembleForecast, HourlyForecast, HourlySpread,
+    VoteGranularity, WeatherDetails,
+};
+use crate::services::cache::ForecastCache;
+use crate::services::ensemble_fetcher::{blend_details, mean, range};
+use crate::services::providers::{local_hour, HourlyResult, ProviderError, ProviderRegistry};
+use futures::future::join_all;
+use log::{info, warn};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Jumlah jam ke depan (mulai jam sekarang) yang dikembalikan endpoint hourly
+pub const HOURLY_HORIZON: usize = 48;
+
+/// Hasil fetch per jam satu provider
+pub struct ProviderHours {
+    pub provider_id: &'static str,
+    pub result: HourlyResult,
+}
+
+/// Ensemble per jam: semua provider yang punya data per jam dicocokkan di grid jam lokal kota,
+/// lalu dirata-rata dan di-voting sama seperti ensemble harian
+pub struct HourlyOrchestrator {
+    cache: Arc<ForecastCache<HourlyEnsembleForecast>>,
+    registry: Arc<ProviderRegistry>,
+    granularity: VoteGranularity,
+}
+
+impl HourlyOrchestrator {
+    pub fn new(cache: Arc<ForecastCache<HourlyEnsembleForecast>>, registry: Arc<ProviderRegistry>) -> Self {
+        Self {
+            cache,
+            registry,
+            granularity: VoteGranularity::default(),
+        }
+    }
+
+    pub fn with_vote_granularity(mut self, granularity: VoteGranularity) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
+    pub async fn get_hourly(&self, city: &City) -> Result<HourlyEnsembleForecast, ApiError> {
+        self.get_hourly_from(city, chrono::Utc::now().timestamp()).await
+    }
+
+    /// Ensemble `HOURLY_HORIZON` jam mulai jam yang memuat `now` (unix timestamp)
+    pub async fn get_hourly_from(&self, city: &City, now: i64) -> Result<HourlyEnsembleForecast, ApiError> {
+        let start = now - now.rem_euclid(3600);
+        // Jam mulai ikut di key, jadi grid tidak pernah tertinggal walau TTL lebih panjang
+        let cache_key = format!("hourly:{}:{}", city.name.to_lowercase(), start);
+
+        if let Some(cached) = self.cache.get(&cache_key).await {
+            info!("[Hourly] Cache HIT: {}", city.name);
+            return Ok(cached);
+        }
+
+        let offset = city.utc_offset();
+        let grid: Vec<String> = (0..HOURLY_HORIZON as i64)
+            .filter_map(|h| local_hour(start + h * 3600, offset))
+            .collect();
+
+        let sources = fetch_hourly_sources(city, &self.registry).await;
+        let hours = build_hourly_ensemble(&grid, &sources, self.granularity);
+        if hours.is_empty() {
+            return Err(to_api_error(&sources));
+        }
+
+        let forecast = HourlyEnsembleForecast {
+            city: city.name.to_string(),
+            province: city.province.to_string(),
+            country: "Indonesia".to_string(),
+            latitude: city.latitude,
+            longitude: city.longitude,
+            utc_offset: offset.to_string(),
+            source_timestamp: chrono::Local::now().to_rfc3339(),
+            hours,
+        };
+        self.cache.insert(cache_key, forecast.clone()).await;
+
+        info!("[Hourly] Built hourly ensemble for {} with {} hours", city.name, forecast.hours.len());
+        Ok(forecast)
+    }
+}
+
+/// Fetch per jam paralel ke semua provider di registry
+pub async fn fetch_hourly_sources(city: &City, registry: &ProviderRegistry) -> Vec<ProviderHours> {
+    info!("[Hourly] Fetching hourly data for {} from {} provider(s)", city.name, registry.len());
+
+    let tasks = registry.providers().iter().map(|provider| async move {
+        let result = registry.fetch_hourly(provider.as_ref(), city).await;
+        match &result {
+            Ok(hours) => info!("[Hourly] {} returned {} hours", provider.name(), hours.len()),
+            Err(e) if e.is_skipped() => info!("[Hourly] {} skipped for {}: {}", provider.name(), city.name, e),
+            Err(e) => warn!("[Hourly] {} error for {}: {}", provider.name(), city.name, e),
+        }
+        ProviderHours {
+            provider_id: provider.id(),
+            result,
+        }
+    });
+
+    join_all(tasks).await
+}
+
+/// Cocokkan data tiap provider ke `grid` (jam lokal). Jam yang tidak punya data dari
+/// provider mana pun tidak dimasukkan.
+pub fn build_hourly_ensemble(
+    grid: &[String],
+    sources: &[ProviderHours],
+    granularity: VoteGranularity,
+) -> Vec<HourEnsemble> {
+    let indexed: Vec<(&str, HashMap<&str, &HourlyForecast>)> = sources
+        .iter()
+        .filter_map(|source| {
+            let hours = source.result.as_ref().ok()?;
+            Some((source.provider_id, hours.iter().map(|h| (h.time.as_str(), h)).collect()))
+        })
+        .collect();
+
+    grid.iter()
+        .filter_map(|time| {
+            let per_source: BTreeMap<String, HourlyForecast> = indexed
+                .iter()
+                .filter_map(|(id, hours)| Some((id.to_string(), (*hours.get(time.as_str())?).clone())))
+                .collect();
+            if per_source.is_empty() {
+                return None;
+            }
+            Some(HourEnsemble {
+                time: time.clone(),
+                final_forecast: calculate_final_hour(&per_source, granularity),
+                per_source,
+            })
+        })
+        .collect()
+}
+
+/// Rata-rata suhu & detail, voting kondisi, dan jumlah provider untuk satu jam
+fn calculate_final_hour(
+    per_source: &BTreeMap<String, HourlyForecast>,
+    granularity: VoteGranularity,
+) -> FinalHourlyForecast {
+    let temperatures: Vec<f32> = per_source.values().map(|h| h.temperature).collect();
+    let details: Vec<&WeatherDetails> = per_source.values().map(|h| &h.details).collect();
+    let (details, details_spread) = blend_details(&details);
+
+    FinalHourlyForecast {
+        temperature: mean(&temperatures).unwrap_or_default(),
+        condition: Condition::vote(per_source.values().map(|h| h.condition), granularity)
+            .unwrap_or(Condition::Unknown),
+        details,
+        spread: HourlySpread {
+            temperature: range(&temperatures).unwrap_or(0.0),
+            details: details_spread,
+        },
+        provider_count: per_source.len(),
+    }
+}
+
+/// Error API kalau tidak ada satu jam pun yang bisa dihitung
+fn to_api_error(sources: &[ProviderHours]) -> ApiError {
+    const EMPTY: ProviderError = ProviderError::EmptyData;
+    ApiError::from_provider_errors(sources.iter().filter_map(|source| match &source.result {
+        Err(ProviderError::Unsupported(_)) => None,
+        Err(e) => Some((source.provider_id, e)),
+        // Data ada tapi tidak ada yang jatuh di grid (misal data lama)
+        Ok(_) => Some((source.provider_id, &EMPTY)),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Intensity;
+    use crate::services::providers::test_support::{
+        fixture_hour, open_meteo_fixture, openweather_fixture, spawn_stub_server, weatherapi_fixture, StubRoute,
+    };
+    use crate::services::providers::UpstreamClient;
+    use crate::utils::Config;
+
+    fn registry() -> Arc<ProviderRegistry> {
+        registry_with(&openweather_fixture(31.0))
+    }
+
+    fn registry_with(openweather: &str) -> Arc<ProviderRegistry> {
+        let base_url = spawn_stub_server(vec![
+            StubRoute::new("/v1/forecast.json", 200, &weatherapi_fixture(32.0)),
+            StubRoute::new("/v1/forecast", 200, &open_meteo_fixture(30.0)),
+            StubRoute::new("/data/2.5/forecast", 200, openweather),
+        ]);
+
+        let mut config = Config::from_env();
+        config.enabled_providers = ["open_meteo", "open_weather", "weather_api"].map(String::from).to_vec();
+        config.openweather_keys = vec!["test-key".to_string()];
+        config.weatherapi_keys = vec!["test-key".to_string()];
+        config.open_meteo_base_url = base_url.clone();
+        config.openweather_base_url = base_url.clone();
+        config.weatherapi_base_url = base_url;
+        config.quota_state_file = None;
+        let upstream = Arc::new(UpstreamClient::from_config(&config).unwrap());
+        Arc::new(ProviderRegistry::from_config(&config, upstream))
+    }
+
+    fn jakarta() -> City {
+        City {
+            id: 1,
+            name: "Jakarta",
+            province: "DKI Jakarta",
+            latitude: -6.2088,
+            longitude: 106.8456,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hours_are_aligned_across_providers() {
+        let orchestrator = HourlyOrchestrator::new(Arc::new(ForecastCache::new(60, 10)), registry());
+        // 13:25 WIB hari pertama fixture: grid mulai 13:00
+        let now = fixture_hour(0, 13) + 25 * 60;
+        let forecast = orchestrator.get_hourly_from(&jakarta(), now).await.unwrap();
+
+        assert_eq!(forecast.utc_offset, "+07:00");
+        assert_eq!(forecast.hours.len(), HOURLY_HORIZON);
+        assert_eq!(forecast.hours[0].time, "2025-01-06T13:00");
+
+        // 14:00: ketiga provider ada (OWM dari slot 12:00 yang dipecah per jam)
+        let afternoon = &forecast.hours[1].final_forecast;
+        assert_eq!(afternoon.provider_count, 3);
+        assert!(forecast.hours[1].per_source["open_weather"].interpolated);
+        // Hujan lokal (Open-Meteo) + hujan ringan (WeatherAPI) mengalahkan mendung (OWM)
+        assert_eq!(afternoon.condition, Condition::RainShowers(Intensity::Light));
+        // (1.5 + 2.4/3 + 2.0) / 3
+        assert!((afternoon.details.precipitation_mm.unwrap() - 1.4333).abs() < 0.01);
+        // Suhu 26 (OM), 29 (OWM), 28 (WA)
+        assert!((afternoon.temperature - 27.6667).abs() < 0.01);
+        assert!((afternoon.spread.temperature - 3.0).abs() < 0.01);
+
+        // 16:00: di luar slot OWM
+        assert_eq!(forecast.hours[3].final_forecast.provider_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_provider_timezone_does_not_shift_hours() {
+        // OWM melaporkan WITA (+08:00) untuk kota WIB; jam tetap dilabeli pada grid kota
+        let mut openweather: serde_json::Value = serde_json::from_str(&openweather_fixture(31.0)).unwrap();
+        openweather["city"]["timezone"] = serde_json::json!(8 * 3600);
+        let orchestrator = HourlyOrchestrator::new(
+            Arc::new(ForecastCache::new(60, 10)),
+            registry_with(&openweather.to_string()),
+        );
+        let forecast = orchestrator.get_hourly_from(&jakarta(), fixture_hour(0, 13)).await.unwrap();
+
+        // Slot 05:00 UTC = 12:00-14:00 WIB
+        assert!(forecast.hours[0].per_source["open_weather"].interpolated);
+        assert_eq!(forecast.hours[1].final_forecast.provider_count, 3);
+        assert!(!forecast.hours[2].per_source.contains_key("open_weather"));
+    }
+
+    #[test]
+    fn test_unsupported_providers_are_not_errors() {
+        let sources = vec![
+            ProviderHours {
+                provider_id: "open_meteo",
+                result: Err(ProviderError::Timeout),
+            },
+            ProviderHours {
+                provider_id: "bmkg",
+                result: Err(ProviderError::Unsupported("hourly forecasts")),
+            },
+        ];
+        let grid = vec!["2025-01-06T13:00".to_string()];
+        assert!(build_hourly_ensemble(&grid, &sources, VoteGranularity::Category).is_empty());
+
+        let (status, json) = to_api_error(&sources).to_response();
+        assert_eq!(status, rocket::http::Status::GatewayTimeout);
+        assert_eq!(json.failed_providers.len(), 1);
+    }
+}
@@ -1,5 +1,7 @@
+use crate::errors::ApiError;
 use crate::models::{City, DailyForecast, WeatherForecast};
-use crate::services::daily_processor::process_day;
+use crate::services::daily_processor::{process_day, SharedProviderData};
+use crate::services::providers::ProviderRegistry;
 use futures::future::join_all;
 use log::{info, warn, error};
 use std::sync::Arc;
@@ -18,6 +20,12 @@ pub struct TaskMetrics {
     pub timed_out_tasks: usize,
 }
 
+impl Default for TaskMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TaskMetrics {
     pub fn new() -> Self {
         Self {
@@ -66,26 +74,24 @@ impl TaskMetrics {
     }
 }
 
-/// Spawn 7 task paralel untuk 7 hari
+/// Spawn 7 task paralel untuk 7 hari (provider di-fetch sekali, di-share antar task)
 pub async fn fetch_forecast_parallel(
     city: &City,
-    openweather_key: &str,
-    weatherapi_key: &str,
-) -> Result<WeatherForecast, String> {
+    registry: Arc<ProviderRegistry>,
+) -> Result<WeatherForecast, ApiError> {
     let mut metrics = TaskMetrics::new();
     info!("Starting parallel forecast processing for city: {} ({})", city.name, city.province);
     
+    let shared = Arc::new(SharedProviderData::new(city, registry));
     let mut handles: Vec<JoinHandle<Result<DailyForecast, String>>> = Vec::new();
 
     for day in 0..7 {
-        let city_clone = city.clone();
-        let openweather_key = openweather_key.to_string();
-        let weatherapi_key = weatherapi_key.to_string();
+        let shared = shared.clone();
         
         let handle = tokio::spawn(async move {
             let task_start = Instant::now();
             info!("Day {} task started at {:?}", day, task_start);
-            let result = process_day(day, &city_clone, &openweather_key, &weatherapi_key).await;
+            let result = process_day(day, &shared).await;
             let task_end = Instant::now();
             info!("Day {} task completed at {:?} (duration: {:?})", day, task_end, task_end.duration_since(task_start));
             result
@@ -128,7 +134,7 @@ pub async fn fetch_forecast_parallel(
     // Check if we have enough successful days
     if successful_days.len() < 3 {
         error!("Too many failed days ({}/7), cannot generate reliable forecast", 7 - successful_days.len());
-        return Err(format!("Insufficient successful forecast days: {}/7", successful_days.len()));
+        return Err(shared.to_api_error());
     }
 
     // Sort successful days by date
@@ -161,20 +167,18 @@ pub async fn fetch_forecast_parallel(
 pub async fn fetch_forecast_with_rate_limit(
     city: &City,
     semaphore: Arc<Semaphore>,
-    openweather_key: &str,
-    weatherapi_key: &str,
-) -> Result<WeatherForecast, String> {
+    registry: Arc<ProviderRegistry>,
+) -> Result<WeatherForecast, ApiError> {
     let mut metrics = TaskMetrics::new();
     info!("Starting rate-limited parallel forecast processing for city: {} ({})", city.name, city.province);
     info!("Semaphore permits available: {}", semaphore.available_permits());
     
+    let shared = Arc::new(SharedProviderData::new(city, registry));
     let mut handles: Vec<JoinHandle<Result<DailyForecast, String>>> = Vec::new();
 
     for day in 0..7 {
-        let city_clone = city.clone();
         let sem = semaphore.clone();
-        let openweather_key = openweather_key.to_string();
-        let weatherapi_key = weatherapi_key.to_string();
+        let shared = shared.clone();
         
         let handle = tokio::spawn(async move {
             let _permit = match sem.acquire().await {
@@ -189,7 +193,7 @@ pub async fn fetch_forecast_with_rate_limit(
             };
 
             info!("Day {}: Starting processing with semaphore protection", day);
-            let result = process_day(day, &city_clone, &openweather_key, &weatherapi_key).await;
+            let result = process_day(day, &shared).await;
             
             info!("Day {}: Released semaphore permit", day);
             result
@@ -233,7 +237,7 @@ pub async fn fetch_forecast_with_rate_limit(
     // Check if we have enough successful days
     if successful_days.len() < 3 {
         error!("Too many failed days ({}/7), cannot generate reliable forecast", 7 - successful_days.len());
-        return Err(format!("Insufficient successful forecast days: {}/7", successful_days.len()));
+        return Err(shared.to_api_error());
     }
 
     // Sort successful days by date
@@ -266,9 +270,30 @@ pub async fn fetch_forecast_with_rate_limit(
 mod tests {
     use super::*;
     use crate::models::City;
+    use crate::models::ensemble::{OPEN_WEATHER, WEATHER_API};
+    use crate::services::providers::{
+        ApiKeyPool, OpenMeteoProvider, OpenWeatherProvider, UpstreamClient, WeatherApiProvider,
+    };
+    use crate::services::providers::test_support::{
+        open_meteo_fixture, openweather_fixture, spawn_stub_server, StubRoute,
+    };
     use std::sync::Arc;
     use tokio::sync::Semaphore;
 
+    fn create_invalid_key_registry() -> Arc<ProviderRegistry> {
+        // Stub server yang selalu balas 401, jadi test tidak butuh internet
+        let base_url = spawn_stub_server(vec![StubRoute::new("/", 401, r#"{"cod":401}"#)]);
+        let upstream = Arc::new(UpstreamClient::default());
+        Arc::new(ProviderRegistry::new(vec![
+            Arc::new(OpenWeatherProvider::new(
+                upstream.clone(),
+                base_url.clone(),
+                ApiKeyPool::single(OPEN_WEATHER, "invalid-key"),
+            )),
+            Arc::new(WeatherApiProvider::new(upstream, base_url, ApiKeyPool::single(WEATHER_API, "invalid-key"))),
+        ]))
+    }
+
     fn create_test_city() -> City {
         City {
             id: 1,
@@ -308,30 +333,55 @@ mod tests {
         let city = create_test_city();
         
         // This should fail due to invalid API keys and timeout
-        let result = process_day(
-            0,
-            &city,
-            "invalid-key",
-            "invalid-key"
-        ).await;
+        let shared = SharedProviderData::new(&city, create_invalid_key_registry());
+        let result = process_day(0, &shared).await;
         
         // We expect this to fail since we're using invalid keys
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_process_day_matches_by_date() {
+        // Open-Meteo melewatkan 2025-01-07 (suhu null), jadi index 1-nya adalah 2025-01-08
+        let mut gappy: serde_json::Value = serde_json::from_str(&open_meteo_fixture(30.0)).unwrap();
+        gappy["daily"]["temperature_2m_max"][1] = serde_json::Value::Null;
+        let base_url = spawn_stub_server(vec![
+            StubRoute::new("/v1/forecast", 200, &gappy.to_string()),
+            StubRoute::new("/data/2.5/forecast", 200, &openweather_fixture(40.0)),
+        ]);
+        let upstream = Arc::new(UpstreamClient::default());
+        let registry = Arc::new(ProviderRegistry::new(vec![
+            Arc::new(OpenMeteoProvider::new(upstream.clone(), base_url.clone())),
+            Arc::new(OpenWeatherProvider::new(upstream, base_url, ApiKeyPool::single(OPEN_WEATHER, "test-key"))),
+        ]));
+        let shared = SharedProviderData::new(&create_test_city(), registry)
+            .with_start_date(chrono::NaiveDate::from_ymd_opt(2025, 1, 6).unwrap());
+
+        let day_1 = process_day(1, &shared).await.unwrap();
+        assert_eq!(day_1.date, "2025-01-07");
+        // Diambil dari OWM (fallback), bukan hari berikutnya dari Open-Meteo
+        assert_eq!(day_1.temp_max, 41.0);
+
+        let day_2 = process_day(2, &shared).await.unwrap();
+        assert_eq!(day_2.date, "2025-01-08");
+        assert_eq!(day_2.temp_max, 32.0);
+    }
+
     #[tokio::test]
     async fn test_parallel_forecast_structure() {
         let city = create_test_city();
         
         // Test that the function signature works (will fail with invalid keys)
-        let result = fetch_forecast_parallel(
-            &city,
-            "invalid-key",
-            "invalid-key"
-        ).await;
+        let result = fetch_forecast_parallel(&city, create_invalid_key_registry()).await;
         
         // Should fail due to invalid API keys, but structure should be correct
-        assert!(result.is_err());
+        match result {
+            Err(ApiError::ProvidersUnavailable(failed)) => {
+                assert_eq!(failed.len(), 2);
+                assert!(failed.iter().all(|f| f.reason == "auth_failure"));
+            }
+            other => panic!("Expected ProvidersUnavailable, got {:?}", other.map(|f| f.city)),
+        }
     }
 
     #[tokio::test]
@@ -343,8 +393,7 @@ mod tests {
         let result = fetch_forecast_with_rate_limit(
             &city,
             semaphore,
-            "invalid-key",
-            "invalid-key"
+            create_invalid_key_registry(),
         ).await;
         
         // Should fail due to invalid API keys, but structure should be correct
@@ -0,0 +1,222 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use log::{info, warn};
+use serde::Serialize;
+use super::error::ProviderError;
+
+/// Pengaturan circuit breaker, sama untuk semua provider
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BreakerConfig {
+    /// Jumlah kegagalan berturut-turut sebelum breaker terbuka
+    pub failure_threshold: u32,
+    /// Lama breaker terbuka sebelum satu request percobaan (half-open) diizinkan
+    pub cool_down: Duration,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            cool_down: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// Kondisi breaker untuk log dan endpoint health
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakerSnapshot {
+    pub provider: &'static str,
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+    /// Sisa waktu sampai percobaan berikutnya diizinkan (hanya saat open)
+    pub retry_in_secs: Option<u64>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: BreakerState,
+    consecutive_failures: u32,
+    /// Waktu terakhir breaker dibuka atau percobaan half-open dimulai
+    since: Instant,
+}
+
+/// Circuit breaker satu provider: closed → open setelah N gagal berturut-turut,
+/// open → half-open setelah cool-down, half-open → closed/open tergantung hasil percobaan
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    provider: &'static str,
+    config: BreakerConfig,
+    inner: Mutex<Inner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(provider: &'static str, config: BreakerConfig) -> Self {
+        Self {
+            provider,
+            config,
+            inner: Mutex::new(Inner {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                since: Instant::now(),
+            }),
+        }
+    }
+
+    /// Izin untuk mengirim request. Saat half-open hanya satu percobaan per cool-down
+    /// (kalau percobaan sebelumnya hilang karena timeout, percobaan baru diizinkan lagi).
+    pub fn try_acquire(&self) -> Result<(), ProviderError> {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.state {
+            BreakerState::Closed => Ok(()),
+            BreakerState::Open | BreakerState::HalfOpen => {
+                let elapsed = inner.since.elapsed();
+                if elapsed >= self.config.cool_down {
+                    if inner.state == BreakerState::Open {
+                        info!("[Breaker] {} half-open, allowing a trial request", self.provider);
+                    }
+                    inner.state = BreakerState::HalfOpen;
+                    inner.since = Instant::now();
+                    Ok(())
+                } else {
+                    Err(ProviderError::CircuitOpen {
+                        provider: self.provider,
+                        retry_in: self.config.cool_down - elapsed,
+                    })
+                }
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state != BreakerState::Closed {
+            info!("[Breaker] {} closed, provider recovered", self.provider);
+        }
+        inner.state = BreakerState::Closed;
+        inner.consecutive_failures = 0;
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures += 1;
+
+        let should_open = match inner.state {
+            BreakerState::HalfOpen => true,
+            BreakerState::Closed => inner.consecutive_failures >= self.config.failure_threshold,
+            BreakerState::Open => false,
+        };
+        if should_open {
+            warn!(
+                "[Breaker] {} open after {} consecutive failure(s), skipping for {}s",
+                self.provider,
+                inner.consecutive_failures,
+                self.config.cool_down.as_secs()
+            );
+            inner.state = BreakerState::Open;
+            inner.since = Instant::now();
+        }
+    }
+
+    /// Percobaan half-open berakhir dengan error yang bukan tanda provider sehat/bermasalah:
+    /// slot percobaan dilepas, breaker kembali open dengan cool-down baru
+    pub fn release_trial(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state == BreakerState::HalfOpen {
+            info!("[Breaker] {} trial request inconclusive, back to open", self.provider);
+            inner.state = BreakerState::Open;
+            inner.since = Instant::now();
+        }
+    }
+
+    pub fn snapshot(&self) -> BreakerSnapshot {
+        let inner = self.inner.lock().unwrap();
+        let retry_in_secs = (inner.state != BreakerState::Closed)
+            .then(|| self.config.cool_down.saturating_sub(inner.since.elapsed()).as_secs());
+        BreakerSnapshot {
+            provider: self.provider,
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            retry_in_secs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(cool_down: Duration) -> CircuitBreaker {
+        CircuitBreaker::new(
+            "weather_api",
+            BreakerConfig {
+                failure_threshold: 2,
+                cool_down,
+            },
+        )
+    }
+
+    #[test]
+    fn test_opens_after_threshold() {
+        let breaker = breaker(Duration::from_secs(60));
+        breaker.record_failure();
+        assert!(breaker.try_acquire().is_ok());
+        breaker.record_failure();
+
+        assert_eq!(breaker.snapshot().state, BreakerState::Open);
+        let err = breaker.try_acquire().unwrap_err();
+        assert!(matches!(err, ProviderError::CircuitOpen { provider: "weather_api", .. }));
+    }
+
+    #[test]
+    fn test_success_resets_failure_count() {
+        let breaker = breaker(Duration::from_secs(60));
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        assert_eq!(breaker.snapshot().state, BreakerState::Closed);
+    }
+
+    #[test]
+    fn test_half_open_trial() {
+        let breaker = breaker(Duration::ZERO);
+        breaker.record_failure();
+        breaker.record_failure();
+
+        // Cool-down 0: percobaan langsung diizinkan
+        assert!(breaker.try_acquire().is_ok());
+        assert_eq!(breaker.snapshot().state, BreakerState::HalfOpen);
+        breaker.record_failure();
+        assert_eq!(breaker.snapshot().state, BreakerState::Open);
+
+        assert!(breaker.try_acquire().is_ok());
+        breaker.record_success();
+        assert_eq!(breaker.snapshot().state, BreakerState::Closed);
+        assert_eq!(breaker.snapshot().consecutive_failures, 0);
+    }
+
+    #[test]
+    fn test_inconclusive_trial_is_released() {
+        let breaker = breaker(Duration::ZERO);
+        breaker.record_failure();
+        breaker.record_failure();
+
+        assert!(breaker.try_acquire().is_ok());
+        breaker.release_trial();
+        assert_eq!(breaker.snapshot().state, BreakerState::Open);
+        assert_eq!(breaker.snapshot().consecutive_failures, 2);
+
+        // Saat closed tidak ada slot percobaan yang dilepas
+        assert!(breaker.try_acquire().is_ok());
+        breaker.record_success();
+        breaker.release_trial();
+        assert_eq!(breaker.snapshot().state, BreakerState::Closed);
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt;
+use std::time::Duration;
+use reqwest::StatusCode;
+use crate::utils::redact::redact;
+
+/// Jenis kegagalan provider. Logging, retry dan mapping error API bercabang di sini,
+/// bukan dengan mencocokkan teks pesan error.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProviderError {
+    /// Request melewati batas waktu
+    Timeout,
+    /// Gagal konek / transport error lain sebelum dapat response
+    Connection(String),
+    /// Response non-2xx selain auth dan kuota
+    HttpStatus {
+        status: StatusCode,
+        retry_after: Option<Duration>,
+    },
+    /// 429: kuota atau rate limit provider habis
+    QuotaExhausted { retry_after: Option<Duration> },
+    /// 401/403: API key salah, dicabut, atau tidak punya akses
+    AuthFailure { status: StatusCode },
+    /// Body tidak sesuai format yang diharapkan
+    SchemaMismatch(String),
+    /// Response valid tapi tidak berisi data forecast
+    EmptyData,
+    /// Provider me-resolve lokasi ke tempat lain (terlalu jauh dari koordinat kota)
+    LocationMismatch { resolved: String, distance_km: f64 },
+    /// Dilewati karena circuit breaker provider sedang terbuka
+    CircuitOpen {
+        provider: &'static str,
+        retry_in: Duration,
+    },
+    /// Ditolak rate limiter lokal karena budget provider sedang habis (tidak ada request keluar)
+    RateLimited { provider: String, retry_in: Duration },
+    /// Hard limit kuota harian/bulanan API key tercapai, provider mati sampai window berganti
+    QuotaLimitReached {
+        provider: String,
+        window: &'static str,
+        resets_in: Duration,
+    },
+    /// Fixture replay tidak ditemukan / tidak bisa dibaca
+    Fixture(String),
+    /// Provider tidak menyediakan jenis data ini (misal forecast per jam)
+    Unsupported(&'static str),
+}
+
+impl ProviderError {
+    /// Klasifikasi status HTTP non-2xx
+    pub fn from_status(status: StatusCode, retry_after: Option<Duration>) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ProviderError::AuthFailure { status },
+            StatusCode::TOO_MANY_REQUESTS => ProviderError::QuotaExhausted { retry_after },
+            _ => ProviderError::HttpStatus { status, retry_after },
+        }
+    }
+
+    /// Error sementara yang layak di-retry: timeout, koneksi, 429, 408 dan 5xx
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ProviderError::Timeout | ProviderError::Connection(_) => true,
+            ProviderError::QuotaExhausted { .. } => true,
+            ProviderError::HttpStatus { status, .. } => {
+                *status == StatusCode::REQUEST_TIMEOUT || status.is_server_error()
+            }
+            _ => false,
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ProviderError::HttpStatus { retry_after, .. }
+            | ProviderError::QuotaExhausted { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Kode stabil untuk client (field `reason` di response error)
+    pub fn code(&self) -> &'static str {
+        match self {
+            ProviderError::Timeout => "timeout",
+            ProviderError::Connection(_) => "connection",
+            ProviderError::HttpStatus { .. } => "http_status",
+            ProviderError::QuotaExhausted { .. } => "quota_exhausted",
+            ProviderError::AuthFailure { .. } => "auth_failure",
+            ProviderError::SchemaMismatch(_) => "schema_mismatch",
+            ProviderError::EmptyData => "empty_data",
+            ProviderError::LocationMismatch { .. } => "location_mismatch",
+            ProviderError::CircuitOpen { .. } => "circuit_open",
+            ProviderError::RateLimited { .. } => "rate_limited",
+            ProviderError::QuotaLimitReached { .. } => "quota_limit_reached",
+            ProviderError::Fixture(_) => "fixture",
+            ProviderError::Unsupported(_) => "unsupported",
+        }
+    }
+
+    /// Provider dilewati tanpa request keluar (circuit breaker, rate limiter, kuota lokal,
+    /// atau data yang memang tidak disediakan provider)
+    pub fn is_skipped(&self) -> bool {
+        matches!(
+            self,
+            ProviderError::CircuitOpen { .. }
+                | ProviderError::RateLimited { .. }
+                | ProviderError::QuotaLimitReached { .. }
+                | ProviderError::Unsupported(_)
+        )
+    }
+
+    /// Masalah jaringan biasa (timeout/koneksi), cukup di-log sebagai info
+    pub fn is_transient_network(&self) -> bool {
+        matches!(self, ProviderError::Timeout | ProviderError::Connection(_))
+    }
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProviderError::Timeout => write!(f, "Request timed out"),
+            ProviderError::Connection(msg) => write!(f, "Connection error: {}", msg),
+            ProviderError::HttpStatus { status, .. } => write!(f, "HTTP error: {}", status),
+            ProviderError::QuotaExhausted { .. } => write!(f, "Quota exhausted (HTTP 429)"),
+            ProviderError::AuthFailure { status } => write!(f, "Authentication failed: {}", status),
+            ProviderError::SchemaMismatch(msg) => write!(f, "Unexpected response format: {}", msg),
+            ProviderError::EmptyData => write!(f, "No weather data available"),
+            ProviderError::LocationMismatch { resolved, distance_km } => write!(
+                f,
+                "Resolved location '{}' is {:.1} km away from the requested city",
+                resolved, distance_km
+            ),
+            ProviderError::CircuitOpen { provider, retry_in } => write!(
+                f,
+                "Circuit breaker open for {}, next attempt in {}s",
+                provider,
+                retry_in.as_secs()
+            ),
+            ProviderError::RateLimited { provider, retry_in } => write!(
+                f,
+                "Local rate limit reached for {}, budget frees up in {}s",
+                provider,
+                retry_in.as_secs()
+            ),
+            ProviderError::QuotaLimitReached { provider, window, resets_in } => write!(
+                f,
+                "{} {} quota hard limit reached, resets in {}s",
+                provider,
+                window,
+                resets_in.as_secs()
+            ),
+            ProviderError::Fixture(msg) => write!(f, "Fixture error: {}", msg),
+            ProviderError::Unsupported(what) => write!(f, "Provider does not offer {}", what),
+        }
+    }
+}
+
+impl std::error::Error for ProviderError {}
+
+/// URL request (berisi `key=`/`appid=`) dibuang dari pesan reqwest sebelum disimpan
+impl From<reqwest::Error> for ProviderError {
+    fn from(e: reqwest::Error) -> Self {
+        let e = e.without_url();
+        if e.is_timeout() {
+            ProviderError::Timeout
+        } else if let Some(status) = e.status() {
+            ProviderError::from_status(status, None)
+        } else if e.is_decode() {
+            ProviderError::SchemaMismatch(redact(&e.to_string()).into_owned())
+        } else {
+            ProviderError::Connection(redact(&e.to_string()).into_owned())
+        }
+    }
+}
+
+impl From<serde_json::Error> for ProviderError {
+    fn from(e: serde_json::Error) -> Self {
+        ProviderError::SchemaMismatch(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_classification() {
+        assert_eq!(
+            ProviderError::from_status(StatusCode::UNAUTHORIZED, None),
+            ProviderError::AuthFailure { status: StatusCode::UNAUTHORIZED }
+        );
+        assert_eq!(
+            ProviderError::from_status(StatusCode::TOO_MANY_REQUESTS, Some(Duration::from_secs(3))),
+            ProviderError::QuotaExhausted { retry_after: Some(Duration::from_secs(3)) }
+        );
+        assert!(matches!(
+            ProviderError::from_status(StatusCode::NOT_FOUND, None),
+            ProviderError::HttpStatus { .. }
+        ));
+    }
+
+    #[test]
+    fn test_retryable_variants() {
+        assert!(ProviderError::Timeout.is_retryable());
+        assert!(ProviderError::Connection("refused".into()).is_retryable());
+        assert!(ProviderError::from_status(StatusCode::SERVICE_UNAVAILABLE, None).is_retryable());
+        assert!(ProviderError::from_status(StatusCode::TOO_MANY_REQUESTS, None).is_retryable());
+        assert!(!ProviderError::from_status(StatusCode::UNAUTHORIZED, None).is_retryable());
+        assert!(!ProviderError::from_status(StatusCode::NOT_FOUND, None).is_retryable());
+        assert!(!ProviderError::SchemaMismatch("missing field".into()).is_retryable());
+        assert!(!ProviderError::EmptyData.is_retryable());
+    }
+}
@@ -0,0 +1,138 @@
+use super::error::ProviderError;
+use std::fs;
+use std::path::PathBuf;
+use log::{info, warn};
+use crate::utils::Config;
+
+/// Mode fixture untuk response upstream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureMode {
+    /// Normal: selalu ke network, tidak menyimpan apa-apa
+    Off,
+    /// Ke network, lalu simpan body response yang sukses ke disk
+    Record,
+    /// Baca dari disk saja, tanpa network sama sekali
+    Replay,
+}
+
+impl FixtureMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "" | "off" => Some(FixtureMode::Off),
+            "record" => Some(FixtureMode::Record),
+            "replay" => Some(FixtureMode::Replay),
+            _ => None,
+        }
+    }
+}
+
+/// Penyimpanan fixture di disk: `<dir>/<provider>/<lokasi>/<tanggal>.json`
+#[derive(Debug, Clone)]
+pub struct FixtureStore {
+    mode: FixtureMode,
+    dir: PathBuf,
+    /// Tanggal key fixture; default tanggal lokal hari ini
+    date: Option<String>,
+}
+
+impl FixtureStore {
+    pub fn new(mode: FixtureMode, dir: impl Into<PathBuf>, date: Option<String>) -> Self {
+        Self {
+            mode,
+            dir: dir.into(),
+            date,
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self::new(FixtureMode::Off, PathBuf::new(), None)
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        let store = Self::new(
+            config.fixture_mode,
+            config.fixture_dir.clone(),
+            config.fixture_date.clone(),
+        );
+        if store.mode != FixtureMode::Off {
+            info!(
+                "[Fixtures] Provider fixtures in {:?} mode at {} (date: {})",
+                store.mode,
+                store.dir.display(),
+                store.date_key()
+            );
+        }
+        store
+    }
+
+    pub fn mode(&self) -> FixtureMode {
+        self.mode
+    }
+
+    fn date_key(&self) -> String {
+        self.date
+            .clone()
+            .unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string())
+    }
+
+    /// Path fixture untuk provider + lokasi (koordinat atau nama kota) + tanggal
+    pub fn path_for(&self, provider_id: &str, location: &str) -> PathBuf {
+        self.dir
+            .join(provider_id)
+            .join(sanitize(location))
+            .join(format!("{}.json", self.date_key()))
+    }
+
+    /// Baca fixture (mode replay)
+    pub fn load(&self, provider_id: &str, location: &str) -> Result<String, ProviderError> {
+        let path = self.path_for(provider_id, location);
+        fs::read_to_string(&path).map_err(|e| {
+            ProviderError::Fixture(format!("not found for replay: {} ({})", path.display(), e))
+        })
+    }
+
+    /// Simpan body response (mode record). Gagal menulis tidak menggagalkan request.
+    pub fn save(&self, provider_id: &str, location: &str, body: &str) {
+        let path = self.path_for(provider_id, location);
+        let result = path
+            .parent()
+            .map(fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| fs::write(&path, body));
+
+        match result {
+            Ok(()) => info!("[Fixtures] Recorded {}", path.display()),
+            Err(e) => warn!("[Fixtures] Failed to record {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// Bikin lokasi aman dipakai sebagai nama direktori
+fn sanitize(location: &str) -> String {
+    location
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_mode_parse() {
+        assert_eq!(FixtureMode::parse("record"), Some(FixtureMode::Record));
+        assert_eq!(FixtureMode::parse("REPLAY"), Some(FixtureMode::Replay));
+        assert_eq!(FixtureMode::parse(""), Some(FixtureMode::Off));
+        assert_eq!(FixtureMode::parse("sometimes"), None);
+    }
+
+    #[test]
+    fn test_fixture_path_is_keyed_by_provider_location_and_date() {
+        let store = FixtureStore::new(FixtureMode::Replay, "fixtures", Some("2025-01-06".to_string()));
+        let path = store.path_for("weather_api", "Bandar Lampung");
+        assert_eq!(path, PathBuf::from("fixtures/weather_api/bandar_lampung/2025-01-06.json"));
+    }
+}
@@ -0,0 +1,334 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use log::{info, warn};
+use reqwest::StatusCode;
+use serde::Serialize;
+use super::error::ProviderError;
+use super::quota::key_label;
+
+/// Helper untuk cek apakah API key valid (bukan kosong / placeholder contoh)
+pub fn is_valid_api_key(key: &str) -> bool {
+    !key.is_empty() && key != "your-key-here"
+}
+
+/// Cara memilih key berikutnya
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyRotation {
+    /// Gilir semua key yang sehat, satu per request
+    RoundRobin,
+    /// Pakai key pertama yang sehat, pindah hanya kalau key itu ditolak
+    Failover,
+}
+
+impl KeyRotation {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "round_robin" | "round-robin" => Some(KeyRotation::RoundRobin),
+            "" | "failover" => Some(KeyRotation::Failover),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyPoolConfig {
+    pub rotation: KeyRotation,
+    /// Lama key yang ditolak (401/403) dikarantina sebelum dicoba lagi
+    pub quarantine: Duration,
+    /// Jeda default key yang kena 429 kalau provider tidak kirim Retry-After
+    pub cool_down: Duration,
+}
+
+impl Default for KeyPoolConfig {
+    fn default() -> Self {
+        Self {
+            rotation: KeyRotation::Failover,
+            quarantine: Duration::from_secs(3600),
+            cool_down: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyState {
+    Active,
+    /// Kuota key habis (429 / hard limit), dipakai lagi setelah jeda
+    CoolingDown,
+    /// Key ditolak provider sebagai tidak valid
+    Quarantined,
+}
+
+/// Status satu key untuk endpoint admin (key ditampilkan sebagai label)
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyStatus {
+    pub provider: &'static str,
+    pub key: String,
+    pub state: KeyState,
+    pub available_in_secs: Option<u64>,
+}
+
+#[derive(Debug)]
+struct KeySlot {
+    key: String,
+    /// `None` = aktif; `Some` = tidak dipakai sampai waktu itu
+    blocked: Mutex<Option<(KeyState, Instant)>>,
+}
+
+impl KeySlot {
+    fn available(&self, now: Instant) -> bool {
+        let mut blocked = self.blocked.lock().unwrap();
+        match *blocked {
+            Some((_, until)) if until > now => false,
+            Some(_) => {
+                *blocked = None;
+                true
+            }
+            None => true,
+        }
+    }
+}
+
+/// Kumpulan API key satu provider. Key yang ditolak (401/403) dikarantina, key yang
+/// kuotanya habis (429) diistirahatkan, request berikutnya pindah ke key lain.
+#[derive(Debug)]
+pub struct ApiKeyPool {
+    provider: &'static str,
+    config: KeyPoolConfig,
+    keys: Vec<KeySlot>,
+    next: AtomicUsize,
+}
+
+impl ApiKeyPool {
+    /// Key kosong/placeholder dibuang; pool bisa jadi kosong
+    pub fn new(provider: &'static str, keys: &[String], config: KeyPoolConfig) -> Self {
+        let keys = keys
+            .iter()
+            .map(|k| k.trim())
+            .filter(|k| is_valid_api_key(k))
+            .map(|k| KeySlot {
+                key: k.to_string(),
+                blocked: Mutex::new(None),
+            })
+            .collect();
+
+        Self {
+            provider,
+            config,
+            keys,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Pool satu key, untuk test dan pemakaian langsung
+    #[allow(dead_code)]
+    pub fn single(provider: &'static str, key: &str) -> Self {
+        Self::new(provider, &[key.to_string()], KeyPoolConfig::default())
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Jalankan `request` dengan key yang sehat. Kalau key ditolak atau kuotanya habis,
+    /// key itu diblokir sementara dan request diulang dengan key berikutnya.
+    pub async fn run<T, F, Fut>(&self, request: F) -> Result<T, ProviderError>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T, ProviderError>>,
+    {
+        let mut last_error = None;
+        for _ in 0..self.keys.len() {
+            let Some(slot) = self.pick() else { break };
+            match request(slot.key.clone()).await {
+                Err(e) if self.block(slot, &e) => last_error = Some(e),
+                result => return result,
+            }
+        }
+        Err(last_error.unwrap_or_else(|| self.unavailable()))
+    }
+
+    pub fn statuses(&self) -> Vec<KeyStatus> {
+        let now = Instant::now();
+        self.keys
+            .iter()
+            .map(|slot| {
+                let blocked = *slot.blocked.lock().unwrap();
+                let (state, available_in_secs) = match blocked {
+                    Some((state, until)) if until > now => (state, Some((until - now).as_secs())),
+                    _ => (KeyState::Active, None),
+                };
+                KeyStatus {
+                    provider: self.provider,
+                    key: key_label(Some(&slot.key)),
+                    state,
+                    available_in_secs,
+                }
+            })
+            .collect()
+    }
+
+    fn pick(&self) -> Option<&KeySlot> {
+        let now = Instant::now();
+        let len = self.keys.len();
+        let start = match self.config.rotation {
+            KeyRotation::RoundRobin => self.next.fetch_add(1, Ordering::Relaxed),
+            KeyRotation::Failover => 0,
+        };
+        (0..len)
+            .map(|offset| &self.keys[(start + offset) % len])
+            .find(|slot| slot.available(now))
+    }
+
+    /// Blokir key kalau error-nya karena key itu sendiri; `false` = error lain, jangan rotasi
+    fn block(&self, slot: &KeySlot, error: &ProviderError) -> bool {
+        let (state, duration) = match error {
+            ProviderError::AuthFailure { .. } => (KeyState::Quarantined, self.config.quarantine),
+            ProviderError::QuotaExhausted { retry_after } => {
+                (KeyState::CoolingDown, retry_after.unwrap_or(self.config.cool_down))
+            }
+            ProviderError::QuotaLimitReached { resets_in, .. } => (KeyState::CoolingDown, *resets_in),
+            _ => return false,
+        };
+
+        let label = key_label(Some(&slot.key));
+        match state {
+            KeyState::Quarantined => warn!(
+                "[Keys] {} key {} rejected ({}), quarantined for {}s",
+                self.provider, label, error, duration.as_secs()
+            ),
+            _ => info!(
+                "[Keys] {} key {} out of quota ({}), resting for {}s",
+                self.provider, label, error, duration.as_secs()
+            ),
+        }
+        *slot.blocked.lock().unwrap() = Some((state, Instant::now() + duration));
+        true
+    }
+
+    /// Error kalau tidak ada key yang bisa dipakai sama sekali. Key yang sedang istirahat
+    /// adalah keadaan lokal (tidak ada request keluar), jadi dilaporkan sebagai `RateLimited`.
+    fn unavailable(&self) -> ProviderError {
+        let now = Instant::now();
+        let cooling = self
+            .keys
+            .iter()
+            .filter_map(|slot| match *slot.blocked.lock().unwrap() {
+                Some((KeyState::CoolingDown, until)) => Some(until.saturating_duration_since(now)),
+                _ => None,
+            })
+            .min();
+
+        match cooling {
+            Some(retry_in) => ProviderError::RateLimited {
+                provider: self.provider.to_string(),
+                retry_in,
+            },
+            None => ProviderError::AuthFailure {
+                status: StatusCode::UNAUTHORIZED,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+
+    fn pool(keys: &[&str], rotation: KeyRotation) -> ApiKeyPool {
+        let keys: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+        ApiKeyPool::new(
+            "weather_api",
+            &keys,
+            KeyPoolConfig {
+                rotation,
+                ..KeyPoolConfig::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_is_valid_api_key() {
+        assert!(!is_valid_api_key(""));
+        assert!(!is_valid_api_key("your-key-here"));
+        assert!(is_valid_api_key("valid-api-key-123"));
+        assert!(is_valid_api_key("abc123xyz"));
+    }
+
+    #[test]
+    fn test_placeholder_keys_are_dropped() {
+        assert_eq!(pool(&["your-key-here", "", " key-a "], KeyRotation::Failover).len(), 1);
+        assert!(pool(&["your-key-here"], KeyRotation::Failover).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_spreads_requests() {
+        let pool = pool(&["key-a", "key-b"], KeyRotation::RoundRobin);
+        let used = StdMutex::new(Vec::new());
+        for _ in 0..4 {
+            pool.run(|key| {
+                used.lock().unwrap().push(key);
+                async { Ok(()) }
+            })
+            .await
+            .unwrap();
+        }
+        assert_eq!(*used.lock().unwrap(), vec!["key-a", "key-b", "key-a", "key-b"]);
+    }
+
+    #[tokio::test]
+    async fn test_revoked_key_is_quarantined() {
+        let pool = pool(&["revoked", "good"], KeyRotation::Failover);
+        let request = |key: String| async move {
+            if key == "revoked" {
+                Err(ProviderError::AuthFailure { status: StatusCode::UNAUTHORIZED })
+            } else {
+                Ok(key)
+            }
+        };
+
+        assert_eq!(pool.run(request).await.unwrap(), "good");
+        // Key yang dicabut tidak dicoba lagi
+        assert_eq!(pool.run(|key| async move { Ok(key) }).await.unwrap(), "good");
+        assert_eq!(pool.statuses()[0].state, KeyState::Quarantined);
+    }
+
+    #[tokio::test]
+    async fn test_all_keys_exhausted() {
+        let pool = pool(&["key-a", "key-b"], KeyRotation::Failover);
+        let err = pool
+            .run(|_| async {
+                Err::<(), _>(ProviderError::QuotaExhausted { retry_after: Some(Duration::from_secs(30)) })
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProviderError::QuotaExhausted { .. }));
+
+        // Semua key sedang istirahat: gagal tanpa memanggil provider
+        let err = pool.run(|_| async { Ok(()) }).await.unwrap_err();
+        assert!(err.is_skipped());
+        assert!(matches!(err, ProviderError::RateLimited { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_other_errors_do_not_rotate() {
+        let pool = pool(&["key-a", "key-b"], KeyRotation::Failover);
+        let calls = AtomicUsize::new(0);
+        let err = pool
+            .run(|_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Err::<(), _>(ProviderError::Timeout) }
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(err, ProviderError::Timeout);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
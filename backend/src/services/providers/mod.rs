@@ -1,8 +1,102 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use chrono::{DateTime, FixedOffset};
+use crate::models::{City, CurrentWeather, DailyForecast, HourlyForecast};
+
 pub mod open_meteo;
 pub mod openweather;
 pub mod weatherapi;
+pub mod registry;
+pub mod fixtures;
+pub mod upstream;
+pub mod error;
+pub mod retry;
+pub mod circuit_breaker;
+pub mod rate_limit;
+pub mod quota;
+pub mod key_pool;
+#[cfg(test)]
+pub mod test_support;
 
 #[allow(unused_imports)]
-pub use open_meteo::{fetch_open_meteo, OpenMeteoResponse, OpenMeteoDaily};
-pub use openweather::fetch_openweather;
-pub use weatherapi::fetch_weatherapi;
+pub use open_meteo::{fetch_open_meteo, OpenMeteoProvider, OpenMeteoResponse, OpenMeteoDaily};
+#[allow(unused_imports)]
+pub use openweather::{fetch_openweather, OpenWeatherProvider};
+#[allow(unused_imports)]
+pub use weatherapi::{fetch_weatherapi, WeatherApiProvider};
+pub use registry::ProviderRegistry;
+#[allow(unused_imports)]
+pub use fixtures::{FixtureMode, FixtureStore};
+pub use upstream::UpstreamClient;
+pub use retry::RetryPolicy;
+#[allow(unused_imports)]
+pub use circuit_breaker::{BreakerConfig, BreakerSnapshot, BreakerState};
+pub use rate_limit::RateLimit;
+#[allow(unused_imports)]
+pub use key_pool::{is_valid_api_key, ApiKeyPool, KeyPoolConfig, KeyRotation, KeyState, KeyStatus};
+#[allow(unused_imports)]
+pub use quota::{KeyUsageReport, QuotaLimits, QuotaTracker};
+pub use error::ProviderError;
+
+pub type ProviderResult = Result<Vec<DailyForecast>, ProviderError>;
+pub type HourlyResult = Result<Vec<HourlyForecast>, ProviderError>;
+pub type CurrentResult = Result<CurrentWeather, ProviderError>;
+
+/// Jam lokal kota untuk timestamp UTC (detik), format `HourlyForecast.time`
+pub fn local_hour(timestamp: i64, offset: FixedOffset) -> Option<String> {
+    let time = DateTime::from_timestamp(timestamp, 0)?.with_timezone(&offset);
+    Some(time.format("%Y-%m-%dT%H:00").to_string())
+}
+
+/// Waktu observasi (RFC 3339) dalam zona lokal kota, format `CurrentWeather.observed_at`
+pub fn local_time(timestamp: i64, offset: FixedOffset) -> Option<String> {
+    Some(DateTime::from_timestamp(timestamp, 0)?.with_timezone(&offset).to_rfc3339())
+}
+
+/// Cara provider menentukan lokasi yang di-query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationQuery {
+    Coordinates,
+    #[allow(dead_code)]
+    CityName,
+}
+
+/// Deskripsi kemampuan satu provider
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderCapabilities {
+    pub description: &'static str,
+    pub forecast_days: usize,
+    pub requires_api_key: bool,
+    pub location_query: LocationQuery,
+}
+
+/// Sumber data cuaca yang bisa dipakai oleh ensemble dan daily processor
+#[async_trait]
+pub trait WeatherProvider: Send + Sync {
+    /// ID stabil, dipakai sebagai key di `per_source`
+    fn id(&self) -> &'static str;
+
+    /// Nama untuk log dan tampilan
+    fn name(&self) -> &'static str;
+
+    fn capabilities(&self) -> ProviderCapabilities;
+
+    /// Ambil forecast harian (maksimal 7 hari, mulai hari ini)
+    async fn fetch_forecast(&self, city: &City) -> ProviderResult;
+
+    /// Ambil forecast per jam pada jam lokal kota (`City::utc_offset`)
+    async fn fetch_hourly(&self, _city: &City) -> HourlyResult {
+        Err(ProviderError::Unsupported("hourly forecasts"))
+    }
+
+    /// Ambil kondisi cuaca saat ini (observasi terakhir) dari API current weather provider
+    async fn fetch_current(&self, _city: &City) -> CurrentResult {
+        Err(ProviderError::Unsupported("current conditions"))
+    }
+
+    /// Status API key provider ini (kosong untuk provider tanpa key)
+    fn api_keys(&self) -> Vec<KeyStatus> {
+        Vec::new()
+    }
+}
@@ -1,17 +1,117 @@
 use serde::{Deserialize, Serialize};
-use crate::models::DailyForecast;
-use std::error::Error;
-use reqwest::Client;
-use std::time::Duration;
-use log::info;
+use async_trait::async_trait;
+use crate::models::{City, Condition, CurrentWeather, DailyForecast, HourlyForecast, Intensity, WeatherDetails};
+use crate::models::ensemble::OPEN_METEO;
+use super::{
+    local_hour, local_time, CurrentResult, HourlyResult, LocationQuery, ProviderCapabilities, ProviderError,
+    ProviderResult, UpstreamClient, WeatherProvider,
+};
+use chrono::FixedOffset;
+use std::sync::Arc;
+use log::{debug, info};
 
+/// Nilai harian Open-Meteo; `null` dari API berarti data hari itu tidak tersedia
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct OpenMeteoDaily {
     pub time: Vec<String>,
-    pub temperature_2m_max: Vec<f32>,
-    pub temperature_2m_min: Vec<f32>,
-    pub relative_humidity_2m_mean: Vec<u32>,
-    pub weather_code: Vec<i32>,
+    pub temperature_2m_max: Vec<Option<f32>>,
+    pub temperature_2m_min: Vec<Option<f32>>,
+    pub relative_humidity_2m_mean: Vec<Option<f32>>,
+    pub weather_code: Vec<Option<i32>>,
+    /// Variabel tambahan; boleh tidak ada sama sekali di response
+    #[serde(default)]
+    pub precipitation_sum: Vec<Option<f32>>,
+    #[serde(default)]
+    pub precipitation_probability_max: Vec<Option<f32>>,
+    #[serde(default)]
+    pub wind_speed_10m_max: Vec<Option<f32>>,
+    #[serde(default)]
+    pub wind_gusts_10m_max: Vec<Option<f32>>,
+}
+
+impl OpenMeteoDaily {
+    /// Semua array harus sepanjang `time`, kalau tidak index-nya tidak bisa dipercaya
+    fn validate_lengths(&self) -> Result<(), ProviderError> {
+        check_lengths(
+            "daily",
+            "days",
+            self.time.len(),
+            &[
+                ("temperature_2m_max", self.temperature_2m_max.len()),
+                ("temperature_2m_min", self.temperature_2m_min.len()),
+                ("relative_humidity_2m_mean", self.relative_humidity_2m_mean.len()),
+                ("weather_code", self.weather_code.len()),
+            ],
+            &[
+                ("precipitation_sum", self.precipitation_sum.len()),
+                ("precipitation_probability_max", self.precipitation_probability_max.len()),
+                ("wind_speed_10m_max", self.wind_speed_10m_max.len()),
+                ("wind_gusts_10m_max", self.wind_gusts_10m_max.len()),
+            ],
+        )
+    }
+}
+
+/// Nilai per jam Open-Meteo, `time` dalam unix timestamp (`timeformat=unixtime`)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OpenMeteoHourly {
+    pub time: Vec<i64>,
+    pub temperature_2m: Vec<Option<f32>>,
+    pub weather_code: Vec<Option<i32>>,
+    #[serde(default)]
+    pub relative_humidity_2m: Vec<Option<f32>>,
+    #[serde(default)]
+    pub precipitation: Vec<Option<f32>>,
+    #[serde(default)]
+    pub precipitation_probability: Vec<Option<f32>>,
+    #[serde(default)]
+    pub wind_speed_10m: Vec<Option<f32>>,
+    #[serde(default)]
+    pub wind_gusts_10m: Vec<Option<f32>>,
+}
+
+impl OpenMeteoHourly {
+    fn validate_lengths(&self) -> Result<(), ProviderError> {
+        check_lengths(
+            "hourly",
+            "hours",
+            self.time.len(),
+            &[
+                ("temperature_2m", self.temperature_2m.len()),
+                ("weather_code", self.weather_code.len()),
+            ],
+            &[
+                ("relative_humidity_2m", self.relative_humidity_2m.len()),
+                ("precipitation", self.precipitation.len()),
+                ("precipitation_probability", self.precipitation_probability.len()),
+                ("wind_speed_10m", self.wind_speed_10m.len()),
+                ("wind_gusts_10m", self.wind_gusts_10m.len()),
+            ],
+        )
+    }
+}
+
+/// Array wajib harus sepanjang `time`; array opsional boleh kosong (tidak dikirim)
+fn check_lengths(
+    section: &str,
+    unit: &str,
+    expected: usize,
+    required: &[(&str, usize)],
+    optional: &[(&str, usize)],
+) -> Result<(), ProviderError> {
+    let present = optional.iter().filter(|(_, len)| *len > 0);
+    match required.iter().chain(present).find(|(_, len)| *len != expected) {
+        Some((field, len)) => Err(ProviderError::SchemaMismatch(format!(
+            "{}.{} has {} values for {} {}",
+            section, field, len, expected, unit
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Nilai hari ke-`i` dari array opsional (kosong kalau field tidak dikirim)
+fn optional_value(values: &[Option<f32>], i: usize) -> Option<f32> {
+    values.get(i).copied().flatten()
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -19,23 +119,49 @@ pub struct OpenMeteoResponse {
     pub daily: OpenMeteoDaily,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OpenMeteoHourlyResponse {
+    pub hourly: OpenMeteoHourly,
+}
+
+/// Nilai `current` Open-Meteo: model terbaru pada interval 15 menit, `time` = awal interval
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OpenMeteoCurrent {
+    pub time: i64,
+    pub temperature_2m: Option<f32>,
+    pub apparent_temperature: Option<f32>,
+    pub relative_humidity_2m: Option<f32>,
+    pub precipitation: Option<f32>,
+    pub weather_code: Option<i32>,
+    pub wind_speed_10m: Option<f32>,
+    pub wind_gusts_10m: Option<f32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OpenMeteoCurrentResponse {
+    pub current: OpenMeteoCurrent,
+}
+
+/// Base URL produksi, bisa dioverride lewat `OPEN_METEO_BASE_URL`
+pub const DEFAULT_BASE_URL: &str = "https://api.open-meteo.com";
+
 pub async fn fetch_open_meteo(
+    upstream: &UpstreamClient,
+    base_url: &str,
     lat: f64,
     lon: f64,
-) -> Result<Vec<DailyForecast>, Box<dyn Error + Send + Sync>> {
+) -> ProviderResult {
     info!("Fetching weather from Open-Meteo provider for lat={}, lon={}", lat, lon);
 
-    let client = Client::builder()
-        .timeout(Duration::from_secs(5))
-        .build()?;
-
     let url = format!(
-        "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&daily=temperature_2m_max,temperature_2m_min,relative_humidity_2m_mean,weather_code&timezone=Asia/Jakarta",
-        lat, lon
+        "{}/v1/forecast?latitude={}&longitude={}&daily=temperature_2m_max,temperature_2m_min,relative_humidity_2m_mean,weather_code,precipitation_sum,precipitation_probability_max,wind_speed_10m_max,wind_gusts_10m_max&timezone=auto",
+        base_url.trim_end_matches('/'), lat, lon
     );
 
-    let response = client.get(&url).send().await?;
-    let data: OpenMeteoResponse = response.json().await?;
+    let location = format!("{:.4},{:.4}", lat, lon);
+    let request = upstream.client().get(&url);
+    let body = upstream.get_text(OPEN_METEO, None, &location, request).await?;
+    let data: OpenMeteoResponse = serde_json::from_str(&body)?;
 
     info!("Successfully fetched Open-Meteo data");
 
@@ -43,52 +169,220 @@ pub async fn fetch_open_meteo(
     Ok(forecasts)
 }
 
-fn normalize_open_meteo(data: &OpenMeteoResponse) -> Result<Vec<DailyForecast>, Box<dyn Error + Send + Sync>> {
+/// 3 hari data per jam mulai 00:00 hari ini, cukup untuk 48 jam ke depan
+pub async fn fetch_open_meteo_hourly(
+    upstream: &UpstreamClient,
+    base_url: &str,
+    lat: f64,
+    lon: f64,
+    utc_offset: FixedOffset,
+) -> HourlyResult {
+    info!("Fetching hourly weather from Open-Meteo provider for lat={}, lon={}", lat, lon);
+
+    // `timezone=auto` supaya jendela 3 hari mulai dari tengah malam lokal, bukan UTC
+    let url = format!(
+        "{}/v1/forecast?latitude={}&longitude={}&hourly=temperature_2m,relative_humidity_2m,precipitation,precipitation_probability,weather_code,wind_speed_10m,wind_gusts_10m&forecast_days=3&timezone=auto&timeformat=unixtime",
+        base_url.trim_end_matches('/'), lat, lon
+    );
+
+    let location = format!("{:.4},{:.4}/hourly", lat, lon);
+    let request = upstream.client().get(&url);
+    let body = upstream.get_text(OPEN_METEO, None, &location, request).await?;
+    let data: OpenMeteoHourlyResponse = serde_json::from_str(&body)?;
+
+    normalize_open_meteo_hourly(&data, utc_offset)
+}
+
+pub async fn fetch_open_meteo_current(
+    upstream: &UpstreamClient,
+    base_url: &str,
+    lat: f64,
+    lon: f64,
+    utc_offset: FixedOffset,
+) -> CurrentResult {
+    info!("Fetching current weather from Open-Meteo provider for lat={}, lon={}", lat, lon);
+
+    let url = format!(
+        "{}/v1/forecast?latitude={}&longitude={}&current=temperature_2m,apparent_temperature,relative_humidity_2m,precipitation,weather_code,wind_speed_10m,wind_gusts_10m&timeformat=unixtime",
+        base_url.trim_end_matches('/'), lat, lon
+    );
+
+    let location = format!("{:.4},{:.4}/current", lat, lon);
+    let request = upstream.client().get(&url);
+    let body = upstream.get_text(OPEN_METEO, None, &location, request).await?;
+    let data: OpenMeteoCurrentResponse = serde_json::from_str(&body)?;
+
+    normalize_open_meteo_current(&data, utc_offset)
+}
+
+/// Open-Meteo: gratis, tanpa API key, query pakai koordinat
+pub struct OpenMeteoProvider {
+    upstream: Arc<UpstreamClient>,
+    base_url: String,
+}
+
+impl OpenMeteoProvider {
+    pub fn new(upstream: Arc<UpstreamClient>, base_url: String) -> Self {
+        Self { upstream, base_url }
+    }
+}
+
+impl Default for OpenMeteoProvider {
+    fn default() -> Self {
+        Self::new(Arc::new(UpstreamClient::default()), DEFAULT_BASE_URL.to_string())
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for OpenMeteoProvider {
+    fn id(&self) -> &'static str {
+        OPEN_METEO
+    }
+
+    fn name(&self) -> &'static str {
+        "Open-Meteo"
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            description: "Free global forecast model, daily aggregates in local time",
+            forecast_days: 7,
+            requires_api_key: false,
+            location_query: LocationQuery::Coordinates,
+        }
+    }
+
+    async fn fetch_forecast(&self, city: &City) -> ProviderResult {
+        fetch_open_meteo(&self.upstream, &self.base_url, city.latitude, city.longitude).await
+    }
+
+    async fn fetch_hourly(&self, city: &City) -> HourlyResult {
+        fetch_open_meteo_hourly(&self.upstream, &self.base_url, city.latitude, city.longitude, city.utc_offset())
+            .await
+    }
+
+    async fn fetch_current(&self, city: &City) -> CurrentResult {
+        fetch_open_meteo_current(&self.upstream, &self.base_url, city.latitude, city.longitude, city.utc_offset())
+            .await
+    }
+}
+
+fn normalize_open_meteo(data: &OpenMeteoResponse) -> ProviderResult {
     let daily = &data.daily;
-    let days_count = std::cmp::min(7, daily.time.len());
+    daily.validate_lengths()?;
 
-    // Functional approach: map iterator to create forecasts
-    let forecasts = (0..days_count)
-        .map(|i| {
-            let temp_max = daily.temperature_2m_max[i];
-            let temp_min = daily.temperature_2m_min[i];
+    // Hari tanpa suhu atau kode cuaca dilewati; humidity kosong masih bisa dipakai
+    let forecasts: Vec<DailyForecast> = daily.time.iter()
+        .enumerate()
+        .take(7)
+        .filter_map(|(i, date)| {
+            let (Some(temp_max), Some(temp_min), Some(weather_code)) = (
+                daily.temperature_2m_max[i],
+                daily.temperature_2m_min[i],
+                daily.weather_code[i],
+            ) else {
+                debug!("[OpenMeteo] Skipping {}: missing temperature or weather code", date);
+                return None;
+            };
             let temp_avg = (temp_max + temp_min) / 2.0;
-            let humidity = daily.relative_humidity_2m_mean[i];
-            let weather_code = daily.weather_code[i];
-            let (condition, icon) = map_wmo_code(weather_code);
+            let humidity = daily.relative_humidity_2m_mean[i].map(|h| h.round() as u32);
+            let condition = map_wmo_code(weather_code);
 
-            DailyForecast {
-                date: daily.time[i].clone(),
+            Some(DailyForecast {
+                date: date.clone(),
                 temp_max,
                 temp_min,
                 temp_avg,
                 condition,
                 humidity,
-                wind_speed: 0.0,
-                icon,
-            }
+                // Satuan default Open-Meteo sudah km/jam
+                wind_speed: optional_value(&daily.wind_speed_10m_max, i),
+                wind_gust: optional_value(&daily.wind_gusts_10m_max, i),
+                precipitation_mm: optional_value(&daily.precipitation_sum, i),
+                precipitation_probability: optional_value(&daily.precipitation_probability_max, i)
+                    .map(|p| p.round() as u32),
+                icon: condition.icon().to_string(),
+            })
         })
         .collect();
 
+    if forecasts.is_empty() {
+        return Err(ProviderError::EmptyData);
+    }
     Ok(forecasts)
 }
 
-fn map_wmo_code(code: i32) -> (String, String) {
-    // Normalisasi ke kategori standar untuk konsistensi ensemble
-    let (condition, icon) = match code {
-        0 => ("Clear", "sunny"),  // Clear sky → Clear
-        1 | 2 => ("Clear", "sunny"),  // Mostly clear → Clear
-        3 => ("Cloudy", "cloudy"),  // Overcast → Cloudy
-        45 | 48 => ("Foggy", "fog"),
-        51 | 53 | 55 => ("Rainy", "rainy"),  // Light drizzle → Rainy
-        61 | 63 | 65 => ("Rainy", "rainy"),  // Rain → Rainy
-        71 | 73 | 75 | 77 => ("Snow", "snowy"),  // All snow types → Snow
-        80 | 81 | 82 => ("Rainy", "rainy"),  // Rain showers → Rainy
-        85 | 86 => ("Snow", "snowy"),  // Snow showers → Snow
-        95 | 96 | 99 => ("Thunderstorm", "stormy"),
-        _ => ("Clear", "sunny"),  // Default ke Clear
+fn normalize_open_meteo_hourly(data: &OpenMeteoHourlyResponse, offset: FixedOffset) -> HourlyResult {
+    let hourly = &data.hourly;
+    hourly.validate_lengths()?;
+
+    let forecasts: Vec<HourlyForecast> = hourly.time.iter()
+        .enumerate()
+        .filter_map(|(i, timestamp)| {
+            let (Some(temperature), Some(weather_code)) = (hourly.temperature_2m[i], hourly.weather_code[i]) else {
+                debug!("[OpenMeteo] Skipping hour {}: missing temperature or weather code", timestamp);
+                return None;
+            };
+            let details = WeatherDetails {
+                humidity: optional_value(&hourly.relative_humidity_2m, i),
+                wind_speed: optional_value(&hourly.wind_speed_10m, i),
+                wind_gust: optional_value(&hourly.wind_gusts_10m, i),
+                precipitation_mm: optional_value(&hourly.precipitation, i),
+                precipitation_probability: optional_value(&hourly.precipitation_probability, i),
+            };
+            let time = local_hour(*timestamp, offset)?;
+            Some(HourlyForecast::new(time, temperature, map_wmo_code(weather_code)).with_details(details))
+        })
+        .collect();
+
+    if forecasts.is_empty() {
+        return Err(ProviderError::EmptyData);
+    }
+    Ok(forecasts)
+}
+
+/// Tanpa suhu tidak ada yang bisa dilaporkan; kode cuaca kosong jadi `Unknown`
+fn normalize_open_meteo_current(data: &OpenMeteoCurrentResponse, offset: FixedOffset) -> CurrentResult {
+    let current = &data.current;
+    let temperature = current.temperature_2m.ok_or(ProviderError::EmptyData)?;
+    let observed_at = local_time(current.time, offset)
+        .ok_or_else(|| ProviderError::SchemaMismatch(format!("current.time {} is out of range", current.time)))?;
+    let condition = current.weather_code.map(map_wmo_code).unwrap_or(Condition::Unknown);
+    let details = WeatherDetails {
+        humidity: current.relative_humidity_2m,
+        wind_speed: current.wind_speed_10m,
+        wind_gust: current.wind_gusts_10m,
+        precipitation_mm: current.precipitation,
+        precipitation_probability: None,
     };
-    (condition.to_string(), icon.to_string())
+
+    Ok(CurrentWeather::new(observed_at, temperature, condition)
+        .with_feels_like(current.apparent_temperature)
+        .with_details(details))
+}
+
+fn map_wmo_code(code: i32) -> Condition {
+    use Intensity::*;
+    match code {
+        0 | 1 => Condition::Clear,  // Clear sky / mainly clear
+        2 => Condition::PartlyCloudy,
+        3 => Condition::Cloudy,  // Overcast
+        45 | 48 => Condition::Foggy,
+        51 | 56 => Condition::Drizzle(Light),  // Termasuk freezing drizzle
+        53 => Condition::Drizzle(Moderate),
+        55 | 57 => Condition::Drizzle(Heavy),
+        61 | 66 => Condition::Rain(Light),
+        63 => Condition::Rain(Moderate),
+        65 | 67 => Condition::Rain(Heavy),
+        80 => Condition::RainShowers(Light),
+        81 => Condition::RainShowers(Moderate),
+        82 => Condition::RainShowers(Heavy),
+        71 | 77 | 85 => Condition::Snow(Light),
+        73 => Condition::Snow(Moderate),
+        75 | 86 => Condition::Snow(Heavy),
+        95 | 96 | 99 => Condition::Thunderstorm,
+        _ => Condition::Unknown,
+    }
 }
 
 #[cfg(test)]
@@ -97,16 +391,144 @@ mod tests {
 
     #[test]
     fn test_wmo_code_mapping() {
-        let (condition, icon) = map_wmo_code(0);
-        assert_eq!(condition, "Clear sky");
-        assert_eq!(icon, "sunny");
+        assert_eq!(map_wmo_code(0), Condition::Clear);
+        assert_eq!(map_wmo_code(0).icon(), "sunny");
+
+        assert_eq!(map_wmo_code(61), Condition::Rain(Intensity::Light));
+        assert_eq!(map_wmo_code(61).icon(), "rainy");
+
+        assert_eq!(map_wmo_code(2), Condition::PartlyCloudy);
+        assert_eq!(map_wmo_code(82), Condition::RainShowers(Intensity::Heavy));
+        assert_eq!(map_wmo_code(42), Condition::Unknown);
+
+        assert_eq!(map_wmo_code(95), Condition::Thunderstorm);
+        assert_eq!(map_wmo_code(95).icon(), "stormy");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_errors_are_typed() {
+        use crate::services::providers::test_support::{spawn_stub_server, StubRoute};
+
+        let base_url = spawn_stub_server(vec![
+            StubRoute::new("/v1/forecast?latitude=1", 200, r#"{"hourly": {}}"#),
+            StubRoute::new("/v1/forecast?latitude=2", 403, "{}"),
+        ]);
+        let upstream = UpstreamClient::default();
+
+        let err = fetch_open_meteo(&upstream, &base_url, 1.0, 0.0).await.unwrap_err();
+        assert!(matches!(err, ProviderError::SchemaMismatch(_)));
+
+        let err = fetch_open_meteo(&upstream, &base_url, 2.0, 0.0).await.unwrap_err();
+        assert!(matches!(err, ProviderError::AuthFailure { .. }));
+    }
+
+    fn parse(json: serde_json::Value) -> OpenMeteoResponse {
+        serde_json::from_value(serde_json::json!({ "daily": json })).unwrap()
+    }
+
+    #[test]
+    fn test_nulls_are_missing_values() {
+        let data = parse(serde_json::json!({
+            "time": ["2025-01-06", "2025-01-07", "2025-01-08"],
+            "temperature_2m_max": [31.5, null, 32.0],
+            "temperature_2m_min": [24.0, 24.5, 25.0],
+            "relative_humidity_2m_mean": [80, 78, null],
+            "weather_code": [3, 61, 95],
+        }));
+
+        let days = normalize_open_meteo(&data).unwrap();
+        let dates: Vec<&str> = days.iter().map(|d| d.date.as_str()).collect();
+        assert_eq!(dates, vec!["2025-01-06", "2025-01-08"]);
+        // Humidity kosong tidak membuang harinya
+        assert_eq!(days[1].temp_max, 32.0);
+        assert_eq!(days[1].humidity, None);
+        // Variabel tambahan yang tidak dikirim = tidak tersedia
+        assert_eq!(days[0].precipitation_mm, None);
+    }
+
+    #[test]
+    fn test_weather_details_are_parsed() {
+        let data = parse(serde_json::json!({
+            "time": ["2025-01-06", "2025-01-07"],
+            "temperature_2m_max": [31.5, 32.0],
+            "temperature_2m_min": [24.0, 24.5],
+            "relative_humidity_2m_mean": [80, 78],
+            "weather_code": [61, 95],
+            "precipitation_sum": [4.2, null],
+            "precipitation_probability_max": [70, 90],
+            "wind_speed_10m_max": [14.8, 22.3],
+            "wind_gusts_10m_max": [30.2, 41.0],
+        }));
 
-        let (condition, icon) = map_wmo_code(61);
-        assert_eq!(condition, "Rain");
-        assert_eq!(icon, "rainy");
+        let days = normalize_open_meteo(&data).unwrap();
+        assert_eq!(days[0].humidity, Some(80));
+        assert_eq!(days[0].precipitation_mm, Some(4.2));
+        assert_eq!(days[0].precipitation_probability, Some(70));
+        assert_eq!(days[1].precipitation_mm, None);
+        assert_eq!(days[1].wind_speed, Some(22.3));
+        assert_eq!(days[1].wind_gust, Some(41.0));
+    }
+
+    #[test]
+    fn test_hourly_times_use_city_offset() {
+        let data: OpenMeteoHourlyResponse = serde_json::from_value(serde_json::json!({
+            "hourly": {
+                // 2025-01-06 06:00 dan 07:00 UTC
+                "time": [1736143200, 1736146800],
+                "temperature_2m": [31.0, null],
+                "weather_code": [80, 2],
+                "precipitation": [1.2, 0.0],
+            }
+        }))
+        .unwrap();
+
+        let wita = FixedOffset::east_opt(8 * 3600).unwrap();
+        let hours = normalize_open_meteo_hourly(&data, wita).unwrap();
+        assert_eq!(hours.len(), 1);
+        assert_eq!(hours[0].time, "2025-01-06T14:00");
+        assert_eq!(hours[0].condition, Condition::RainShowers(Intensity::Light));
+        assert_eq!(hours[0].details.precipitation_mm, Some(1.2));
+        assert_eq!(hours[0].details.humidity, None);
+    }
+
+    #[test]
+    fn test_current_conditions_are_parsed() {
+        let data: OpenMeteoCurrentResponse = serde_json::from_value(serde_json::json!({
+            "current": {
+                // 2025-01-06 06:15 UTC
+                "time": 1736144100,
+                "interval": 900,
+                "temperature_2m": 31.2,
+                "apparent_temperature": 35.8,
+                "relative_humidity_2m": 71,
+                "weather_code": 2,
+                "wind_speed_10m": null,
+            }
+        }))
+        .unwrap();
+
+        let wib = FixedOffset::east_opt(7 * 3600).unwrap();
+        let current = normalize_open_meteo_current(&data, wib).unwrap();
+        assert_eq!(current.observed_at, "2025-01-06T13:15:00+07:00");
+        assert_eq!(current.feels_like, Some(35.8));
+        assert_eq!(current.condition, Condition::PartlyCloudy);
+        assert_eq!(current.details.humidity, Some(71.0));
+        assert_eq!(current.details.wind_speed, None);
+    }
+
+    #[test]
+    fn test_mismatched_lengths_are_schema_errors() {
+        let data = parse(serde_json::json!({
+            "time": ["2025-01-06", "2025-01-07"],
+            "temperature_2m_max": [31.5, 32.0],
+            "temperature_2m_min": [24.0],
+            "relative_humidity_2m_mean": [80, 78],
+            "weather_code": [3, 61],
+        }));
 
-        let (condition, icon) = map_wmo_code(95);
-        assert_eq!(condition, "Thunderstorm");
-        assert_eq!(icon, "stormy");
+        match normalize_open_meteo(&data) {
+            Err(ProviderError::SchemaMismatch(msg)) => assert!(msg.contains("temperature_2m_min")),
+            other => panic!("Expected SchemaMismatch, got {:?}", other),
+        }
     }
 }
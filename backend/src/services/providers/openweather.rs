@@ -1,10 +1,17 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use crate::models::DailyForecast;
-use std::error::Error;
-use reqwest::Client;
-use std::time::Duration;
+use async_trait::async_trait;
+use crate::models::{
+    City, Condition, CurrentWeather, DailyForecast, HourlyForecast, Intensity, VoteGranularity, WeatherDetails,
+};
+use crate::models::ensemble::OPEN_WEATHER;
+use super::{
+    local_hour, local_time, ApiKeyPool, CurrentResult, HourlyResult, KeyStatus, LocationQuery, ProviderCapabilities,
+    ProviderError, ProviderResult, UpstreamClient, WeatherProvider,
+};
+use std::sync::Arc;
 use log::info;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset};
 
 macro_rules! api_struct {
     ($name:ident { $($field:ident: $type:ty),+ $(,)? }) => {
@@ -17,68 +24,223 @@ macro_rules! api_struct {
 
 // OpenWeatherMap macro
 api_struct!(OpenWeatherMain {
+    temp: Option<f32>,
+    feels_like: Option<f32>,
     temp_max: f32,
     temp_min: f32,
     humidity: u32,
 });
 
 api_struct!(OpenWeatherWeather {
+    id: Option<u32>,
     main: String,
     description: String,
 });
 
 api_struct!(OpenWeatherWind {
     speed: f32,
+    gust: Option<f32>,
 });
 
+/// Curah hujan (mm): `3h` di forecast, `1h` di current weather. Nama field JSON-nya
+/// diawali angka jadi tidak bisa lewat macro.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct OpenWeatherRain {
+    #[serde(rename = "1h", default)]
+    pub one_hour: Option<f32>,
+    #[serde(rename = "3h", default)]
+    pub three_hours: Option<f32>,
+}
+
 api_struct!(OpenWeatherListItem {
     dt: i64,
     main: OpenWeatherMain,
     weather: Vec<OpenWeatherWeather>,
     wind: Option<OpenWeatherWind>,
+    pop: Option<f32>,
+    rain: Option<OpenWeatherRain>,
+});
+
+/// Response `/data/2.5/weather` punya bentuk yang sama dengan satu item forecast
+/// (`pop` tidak ada, `rain` berisi `1h`)
+pub type OpenWeatherCurrentResponse = OpenWeatherListItem;
+
+api_struct!(OpenWeatherCity {
+    timezone: Option<i32>,
 });
 
 api_struct!(OpenWeatherResponse {
     list: Vec<OpenWeatherListItem>,
+    city: Option<OpenWeatherCity>,
 });
 
+/// Base URL produksi, bisa dioverride lewat `OPENWEATHER_BASE_URL`
+pub const DEFAULT_BASE_URL: &str = "https://api.openweathermap.org";
+
+/// `units=metric` memberi angin dalam m/s; ensemble memakai km/jam
+const MS_TO_KMH: f32 = 3.6;
+
 pub async fn fetch_openweather(
+    upstream: &UpstreamClient,
+    base_url: &str,
     lat: f64,
     lon: f64,
     api_key: &str,
-) -> Result<Vec<DailyForecast>, Box<dyn Error + Send + Sync>> {
+    utc_offset: FixedOffset,
+) -> ProviderResult {
     info!("Fetching weather from OpenWeatherMap provider for lat={}, lon={}", lat, lon);
 
-    let client = Client::builder()
-        .timeout(Duration::from_secs(5))
-        .build()?;
+    let data: OpenWeatherResponse = request(upstream, base_url, "forecast", lat, lon, api_key).await?;
+    info!("Successfully fetched OpenWeatherMap data");
+
+    let forecasts = normalize_openweather(&data, response_offset(&data, utc_offset))?;
+    Ok(forecasts)
+}
 
+/// Data 3-jam yang sama dengan forecast harian, dipecah per jam pada jam lokal kota.
+/// Label jam selalu memakai offset kota supaya cocok dengan grid ensemble per jam.
+pub async fn fetch_openweather_hourly(
+    upstream: &UpstreamClient,
+    base_url: &str,
+    lat: f64,
+    lon: f64,
+    api_key: &str,
+    utc_offset: FixedOffset,
+) -> HourlyResult {
+    info!("Fetching hourly weather from OpenWeatherMap provider for lat={}, lon={}", lat, lon);
+
+    let data: OpenWeatherResponse = request(upstream, base_url, "forecast", lat, lon, api_key).await?;
+    normalize_openweather_hourly(&data, utc_offset)
+}
+
+/// Offset dari OWM (`city.timezone`, detik) lebih akurat; zona kota jadi cadangan
+fn response_offset(data: &OpenWeatherResponse, fallback: FixedOffset) -> FixedOffset {
+    data.city
+        .as_ref()
+        .and_then(|c| c.timezone)
+        .and_then(FixedOffset::east_opt)
+        .unwrap_or(fallback)
+}
+
+pub async fn fetch_openweather_current(
+    upstream: &UpstreamClient,
+    base_url: &str,
+    lat: f64,
+    lon: f64,
+    api_key: &str,
+    utc_offset: FixedOffset,
+) -> CurrentResult {
+    info!("Fetching current weather from OpenWeatherMap provider for lat={}, lon={}", lat, lon);
+
+    let data: OpenWeatherCurrentResponse = request(upstream, base_url, "weather", lat, lon, api_key).await?;
+    normalize_openweather_current(&data, utc_offset)
+}
+
+/// GET `/data/2.5/{endpoint}`; fixture forecast memakai key lokasi saja, endpoint lain diberi akhiran
+async fn request<T: DeserializeOwned>(
+    upstream: &UpstreamClient,
+    base_url: &str,
+    endpoint: &str,
+    lat: f64,
+    lon: f64,
+    api_key: &str,
+) -> Result<T, ProviderError> {
     let url = format!(
-        "https://api.openweathermap.org/data/2.5/forecast?lat={}&lon={}&appid={}&units=metric",
-        lat, lon, api_key
+        "{}/data/2.5/{}?lat={}&lon={}&appid={}&units=metric",
+        base_url.trim_end_matches('/'), endpoint, lat, lon, api_key
     );
 
-    let response = client.get(&url).send().await?;
-    let data: OpenWeatherResponse = response.json().await?;
+    let location = format!("{:.4},{:.4}", lat, lon);
+    let fixture_key = if endpoint == "forecast" { location } else { format!("{}/{}", location, endpoint) };
+    let request = upstream.client().get(&url);
+    let body = upstream.get_text(OPEN_WEATHER, Some(api_key), &fixture_key, request).await?;
+    Ok(serde_json::from_str(&body)?)
+}
 
-    info!("Successfully fetched OpenWeatherMap data");
+/// OpenWeatherMap: 5 hari data 3-jam, butuh API key
+pub struct OpenWeatherProvider {
+    upstream: Arc<UpstreamClient>,
+    base_url: String,
+    keys: ApiKeyPool,
+}
 
-    let forecasts = normalize_openweather(&data)?;
-    Ok(forecasts)
+impl OpenWeatherProvider {
+    pub fn new(upstream: Arc<UpstreamClient>, base_url: String, keys: ApiKeyPool) -> Self {
+        Self {
+            upstream,
+            base_url,
+            keys,
+        }
+    }
 }
 
-fn normalize_openweather(data: &OpenWeatherResponse) -> Result<Vec<DailyForecast>, Box<dyn Error + Send + Sync>> {
+#[async_trait]
+impl WeatherProvider for OpenWeatherProvider {
+    fn id(&self) -> &'static str {
+        OPEN_WEATHER
+    }
+
+    fn name(&self) -> &'static str {
+        "OpenWeatherMap"
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            description: "5-day / 3-hour forecast aggregated into daily values",
+            forecast_days: 5,
+            requires_api_key: true,
+            location_query: LocationQuery::Coordinates,
+        }
+    }
+
+    async fn fetch_forecast(&self, city: &City) -> ProviderResult {
+        let (upstream, base_url) = (&self.upstream, &self.base_url);
+        self.keys
+            .run(|key| async move {
+                fetch_openweather(upstream, base_url, city.latitude, city.longitude, &key, city.utc_offset())
+                    .await
+            })
+            .await
+    }
+
+    async fn fetch_hourly(&self, city: &City) -> HourlyResult {
+        let (upstream, base_url) = (&self.upstream, &self.base_url);
+        self.keys
+            .run(|key| async move {
+                fetch_openweather_hourly(upstream, base_url, city.latitude, city.longitude, &key, city.utc_offset())
+                    .await
+            })
+            .await
+    }
+
+    async fn fetch_current(&self, city: &City) -> CurrentResult {
+        let (upstream, base_url) = (&self.upstream, &self.base_url);
+        self.keys
+            .run(|key| async move {
+                fetch_openweather_current(upstream, base_url, city.latitude, city.longitude, &key, city.utc_offset())
+                    .await
+            })
+            .await
+    }
+
+    fn api_keys(&self) -> Vec<KeyStatus> {
+        self.keys.statuses()
+    }
+}
+
+/// Kelompokkan data 3-jam per tanggal lokal kota (`offset`), bukan tanggal UTC
+fn normalize_openweather(data: &OpenWeatherResponse, offset: FixedOffset) -> ProviderResult {
     use std::collections::HashMap;
     
     if data.list.is_empty() {
-        return Err("No weather data available".into());
+        return Err(ProviderError::EmptyData);
     }
 
-    // Group items by date using fold 
+    // Group items by local date using fold 
     let grouped_by_date: HashMap<String, Vec<&OpenWeatherListItem>> = data.list.iter()
         .fold(HashMap::new(), |mut acc, item| {
-            if let Some(dt) = DateTime::<Utc>::from_timestamp(item.dt, 0) {
-                let date = dt.format("%Y-%m-%d").to_string();
+            if let Some(dt) = DateTime::from_timestamp(item.dt, 0) {
+                let date = dt.with_timezone(&offset).format("%Y-%m-%d").to_string();
                 acc.entry(date).or_insert_with(Vec::new).push(item);
             }
             acc
@@ -88,98 +250,163 @@ fn normalize_openweather(data: &OpenWeatherResponse) -> Result<Vec<DailyForecast
     let daily_forecasts: Vec<DailyForecast> = grouped_by_date
         .into_iter()
         .map(|(date, items)| {
-            // Agregasi data untuk sehari menggunakan fold (immutable accumulation)
-            let (max_temp, min_temp, last_humidity, last_wind, last_condition, last_icon) = items.iter()
-                .fold(
-                    (f32::MIN, f32::MAX, 0u32, 0.0f32, String::new(), String::new()),
-                    |(max_t, min_t, _, _, _, _), item| {
-                        let humidity = item.main.humidity;
-                        let wind = item.wind.as_ref().map(|w| w.speed).unwrap_or(0.0);
-                        let (condition, icon) = if !item.weather.is_empty() {
-                            let (c, i) = map_openweather_condition(&item.weather[0].main);
-                            (c.to_string(), i.to_string())
-                        } else {
-                            (String::new(), String::new())
-                        };
-                        
-                        (
-                            max_t.max(item.main.temp_max),
-                            min_t.min(item.main.temp_min),
-                            humidity,
-                            wind,
-                            condition,
-                            icon,
-                        )
-                    }
-                );
-
+            let max_temp = items.iter().map(|i| i.main.temp_max).fold(f32::MIN, f32::max);
+            let min_temp = items.iter().map(|i| i.main.temp_min).fold(f32::MAX, f32::min);
             let temp_avg = (max_temp + min_temp) / 2.0;
 
+            let humidity_sum: u32 = items.iter().map(|i| i.main.humidity).sum();
+            let humidity = (humidity_sum as f32 / items.len() as f32).round() as u32;
+
+            let winds = items.iter().filter_map(|i| i.wind.as_ref());
+            let wind_speed = max_of(winds.clone().map(|w| w.speed)).map(|v| v * MS_TO_KMH);
+            let wind_gust = max_of(winds.filter_map(|w| w.gust)).map(|v| v * MS_TO_KMH);
+            // Item tanpa `rain` berarti tidak hujan di slot itu
+            let precipitation_mm = items
+                .iter()
+                .map(|i| i.rain.as_ref().and_then(|r| r.three_hours).unwrap_or(0.0))
+                .sum();
+            let precipitation_probability = max_of(items.iter().filter_map(|i| i.pop))
+                .map(|p| (p * 100.0).round() as u32);
+            let condition = dominant_condition(&items);
+
             DailyForecast {
                 date,
                 temp_max: max_temp,
                 temp_min: min_temp,
                 temp_avg,
-                condition: last_condition,
-                humidity: last_humidity,
-                wind_speed: last_wind,
-                icon: last_icon,
+                condition,
+                humidity: Some(humidity),
+                wind_speed,
+                wind_gust,
+                precipitation_mm: Some(precipitation_mm),
+                precipitation_probability,
+                icon: condition.icon().to_string(),
             }
         })
         .collect();
 
-    // Urutkan berdasarkan tanggal (immutable sort dengan sorted iterator)
+    // Urutkan berdasarkan tanggal. Feed 5 hari tidak diperpanjang: hari yang tidak ada
+    // memang tidak ikut voting ensemble untuk provider ini.
     let mut daily_forecasts = daily_forecasts;
     daily_forecasts.sort_by(|a, b| a.date.cmp(&b.date));
+    daily_forecasts.truncate(7);
+
+    Ok(daily_forecasts)
+}
+
+/// Panjang satu slot forecast OWM
+const SLOT_HOURS: i64 = 3;
 
-    // Perpanjang sampai 7 hari jika diperlukan (functional approach)
-    let daily_forecasts = if daily_forecasts.len() < 7 {
-        let additional_days: Vec<DailyForecast> = (daily_forecasts.len()..7)
-            .filter_map(|i| {
-                daily_forecasts.last().and_then(|last_day| {
-                    let days_to_add = (i as i64) - 4;
-                    chrono::NaiveDate::parse_from_str(&last_day.date, "%Y-%m-%d")
-                        .ok()
-                        .and_then(|base_date| base_date.checked_add_signed(chrono::Duration::days(days_to_add)))
-                        .map(|new_date| {
-                            // Buat forecast baru dengan tanggal yang diperbarui (immutable update)
-                            DailyForecast {
-                                date: new_date.format("%Y-%m-%d").to_string(),
-                                temp_max: last_day.temp_max,
-                                temp_min: last_day.temp_min,
-                                temp_avg: last_day.temp_avg,
-                                condition: last_day.condition.clone(),
-                                humidity: last_day.humidity,
-                                wind_speed: last_day.wind_speed,
-                                icon: last_day.icon.clone(),
-                            }
-                        })
-                })
+/// Pecah tiap slot 3-jam jadi data per jam. Suhu, humidity, angin dan peluang hujan
+/// diinterpolasi linear ke slot berikutnya; hujan slot dibagi rata; kondisi ikut slot-nya.
+fn normalize_openweather_hourly(data: &OpenWeatherResponse, offset: FixedOffset) -> HourlyResult {
+    let mut items: Vec<&OpenWeatherListItem> = data.list.iter().collect();
+    items.sort_by_key(|i| i.dt);
+
+    let forecasts: Vec<HourlyForecast> = items
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, item)| {
+            // Slot terakhir (atau sebelum celah data) dipakai apa adanya untuk 3 jamnya
+            let next = items.get(idx + 1).filter(|n| n.dt - item.dt <= SLOT_HOURS * 3600);
+            let steps = next.map(|n| ((n.dt - item.dt) / 3600).max(1)).unwrap_or(SLOT_HOURS);
+            (0..steps).filter_map(move |step| {
+                let fraction = step as f32 / steps as f32;
+                let lerp = |value: fn(&OpenWeatherListItem) -> Option<f32>| {
+                    match (value(item), next.and_then(|n| value(n))) {
+                        (Some(a), Some(b)) => Some(a + (b - a) * fraction),
+                        (a, _) => a,
+                    }
+                };
+
+                let temperature = lerp(|i| Some(i.main.temp.unwrap_or((i.main.temp_max + i.main.temp_min) / 2.0)))?;
+                let rain = item.rain.as_ref().and_then(|r| r.three_hours).unwrap_or(0.0);
+                let details = WeatherDetails {
+                    humidity: lerp(|i| Some(i.main.humidity as f32)),
+                    wind_speed: lerp(|i| i.wind.as_ref().map(|w| w.speed)).map(|v| v * MS_TO_KMH),
+                    wind_gust: lerp(|i| i.wind.as_ref().and_then(|w| w.gust)).map(|v| v * MS_TO_KMH),
+                    precipitation_mm: Some(rain / SLOT_HOURS as f32),
+                    precipitation_probability: lerp(|i| i.pop).map(|p| p * 100.0),
+                };
+                let condition = item.weather.first().map(map_openweather_condition).unwrap_or(Condition::Unknown);
+
+                let forecast = HourlyForecast::new(local_hour(item.dt + step * 3600, offset)?, temperature, condition)
+                    .with_details(details);
+                Some(HourlyForecast { interpolated: step > 0, ..forecast })
             })
-            .collect();
-
-        // Gabungkan forecast asli dengan hari tambahan
-        daily_forecasts.into_iter()
-            .chain(additional_days)
-            .take(7)
-            .collect()
-    } else {
-        daily_forecasts.into_iter().take(7).collect()
+        })
+        .collect();
+
+    if forecasts.is_empty() {
+        return Err(ProviderError::EmptyData);
+    }
+    Ok(forecasts)
+}
+
+/// Observasi terakhir OWM; `rain` tidak ada berarti tidak hujan dalam satu jam terakhir
+fn normalize_openweather_current(data: &OpenWeatherCurrentResponse, offset: FixedOffset) -> CurrentResult {
+    let temperature = data.main.temp.ok_or(ProviderError::EmptyData)?;
+    let observed_at = local_time(data.dt, offset)
+        .ok_or_else(|| ProviderError::SchemaMismatch(format!("dt {} is out of range", data.dt)))?;
+    let condition = data.weather.first().map(map_openweather_condition).unwrap_or(Condition::Unknown);
+    let details = WeatherDetails {
+        humidity: Some(data.main.humidity as f32),
+        wind_speed: data.wind.as_ref().map(|w| w.speed * MS_TO_KMH),
+        wind_gust: data.wind.as_ref().and_then(|w| w.gust).map(|g| g * MS_TO_KMH),
+        precipitation_mm: Some(data.rain.as_ref().and_then(|r| r.one_hour).unwrap_or(0.0)),
+        precipitation_probability: None,
     };
 
-    Ok(daily_forecasts)
+    Ok(CurrentWeather::new(observed_at, temperature, condition)
+        .with_feels_like(data.main.feels_like)
+        .with_details(details))
+}
+
+fn max_of(values: impl Iterator<Item = f32>) -> Option<f32> {
+    values.reduce(f32::max)
 }
 
-fn map_openweather_condition(condition: &str) -> (&'static str, &'static str) {
-    match condition.to_lowercase().as_str() {
-        "clear" => ("Clear", "sunny"),
-        "clouds" => ("Cloudy", "cloudy"),
-        "rain" => ("Rainy", "rainy"),
-        "snow" => ("Snow", "snowy"),
-        "drizzle" => ("Rainy", "rainy"),  // Drizzle = Rainy untuk konsistensi
-        "mist" | "smoke" | "haze" | "dust" | "fog" | "sand" | "ash" | "squall" | "tornado" => ("Foggy", "fog"),
-        "thunderstorm" => ("Thunderstorm", "stormy"),
-        _ => ("Clear", "sunny"),  // Default ke Clear (bukan Unknown)
+/// Kondisi harian: kategori yang paling sering muncul (seri → yang paling parah)
+fn dominant_condition(items: &[&OpenWeatherListItem]) -> Condition {
+    let conditions = items
+        .iter()
+        .filter_map(|item| item.weather.first())
+        .map(map_openweather_condition);
+    Condition::vote(conditions, VoteGranularity::Category).unwrap_or(Condition::Unknown)
+}
+
+/// Pakai condition id OWM (punya intensitas); `main` hanya cadangan kalau id tidak ada
+fn map_openweather_condition(weather: &OpenWeatherWeather) -> Condition {
+    use Intensity::*;
+    match weather.id {
+        Some(200..=232) => Condition::Thunderstorm,
+        Some(300 | 310) => Condition::Drizzle(Light),
+        Some(301 | 311 | 313 | 321) => Condition::Drizzle(Moderate),
+        Some(302 | 312 | 314) => Condition::Drizzle(Heavy),
+        Some(500) => Condition::Rain(Light),
+        Some(501 | 511) => Condition::Rain(Moderate),
+        Some(502..=504) => Condition::Rain(Heavy),
+        Some(520) => Condition::RainShowers(Light),
+        Some(521 | 531) => Condition::RainShowers(Moderate),
+        Some(522) => Condition::RainShowers(Heavy),
+        Some(600 | 612 | 615 | 620) => Condition::Snow(Light),
+        Some(601 | 611 | 613 | 616 | 621) => Condition::Snow(Moderate),
+        Some(602 | 622) => Condition::Snow(Heavy),
+        Some(701..=781) => Condition::Foggy,  // Mist, haze, asap, debu
+        Some(800) => Condition::Clear,
+        Some(801 | 802) => Condition::PartlyCloudy,
+        Some(803 | 804) => Condition::Cloudy,
+        Some(_) => Condition::Unknown,
+        None => match weather.main.to_lowercase().as_str() {
+            "clear" => Condition::Clear,
+            "clouds" => Condition::Cloudy,
+            "rain" => Condition::Rain(Moderate),
+            "drizzle" => Condition::Drizzle(Moderate),
+            "snow" => Condition::Snow(Moderate),
+            "mist" | "smoke" | "haze" | "dust" | "fog" | "sand" | "ash" | "squall" | "tornado" => Condition::Foggy,
+            "thunderstorm" => Condition::Thunderstorm,
+            _ => Condition::Unknown,
+        },
     }
 }
 
@@ -189,12 +416,173 @@ mod tests {
 
     #[test]
     fn test_openweather_condition_mapping() {
-        let (condition, icon) = map_openweather_condition("clear");
-        assert_eq!(condition, "Clear");
-        assert_eq!(icon, "sunny");
+        let weather = |id: Option<u32>, main: &str| OpenWeatherWeather {
+            id,
+            main: main.to_string(),
+            description: String::new(),
+        };
+        assert_eq!(map_openweather_condition(&weather(None, "clear")), Condition::Clear);
+        assert_eq!(map_openweather_condition(&weather(None, "clear")).icon(), "sunny");
+
+        assert_eq!(map_openweather_condition(&weather(None, "rain")), Condition::Rain(Intensity::Moderate));
+        assert_eq!(map_openweather_condition(&weather(None, "rain")).icon(), "rainy");
+
+        assert_eq!(map_openweather_condition(&weather(Some(520), "Rain")), Condition::RainShowers(Intensity::Light));
+        assert_eq!(map_openweather_condition(&weather(Some(801), "Clouds")), Condition::PartlyCloudy);
+        assert_eq!(map_openweather_condition(&weather(Some(999), "Clear")), Condition::Unknown);
+    }
+
+    fn item(dt: &str, temp: f32, humidity: u32, main: &str) -> OpenWeatherListItem {
+        OpenWeatherListItem {
+            dt: DateTime::parse_from_rfc3339(dt).unwrap().timestamp(),
+            main: OpenWeatherMain {
+                temp: Some(temp),
+                feels_like: None,
+                temp_max: temp,
+                temp_min: temp,
+                humidity,
+            },
+            weather: vec![OpenWeatherWeather {
+                id: None,
+                main: main.to_string(),
+                description: String::new(),
+            }],
+            wind: None,
+            pop: None,
+            rain: None,
+        }
+    }
+
+    #[test]
+    fn test_days_are_bucketed_in_local_time() {
+        let data = OpenWeatherResponse {
+            list: vec![
+                // 23:00 UTC tanggal 5 = 06:00 WIB tanggal 6
+                item("2025-01-05T23:00:00Z", 24.0, 90, "Rain"),
+                item("2025-01-06T08:00:00Z", 33.0, 60, "Clouds"),
+                item("2025-01-06T17:00:00Z", 26.0, 81, "Clouds"),
+            ],
+            city: None,
+        };
+        let wib = FixedOffset::east_opt(7 * 3600).unwrap();
+        let days = normalize_openweather(&data, wib).unwrap();
+
+        let first = &days[0];
+        assert_eq!(first.date, "2025-01-06");
+        assert_eq!(first.temp_min, 24.0);
+        assert_eq!(first.temp_max, 33.0);
+        assert_eq!(first.humidity, Some(75));
+        assert_eq!(first.condition, Condition::Rain(Intensity::Moderate));
+        // 17:00 UTC = 00:00 WIB hari berikutnya
+        assert_eq!(days[1].date, "2025-01-07");
+    }
+
+    #[test]
+    fn test_weather_details_are_aggregated_per_day() {
+        let wet = |dt: &str, speed: f32, rain: Option<f32>, pop: f32| OpenWeatherListItem {
+            wind: Some(OpenWeatherWind { speed, gust: Some(speed * 2.0) }),
+            pop: Some(pop),
+            rain: rain.map(|mm| OpenWeatherRain { three_hours: Some(mm), ..Default::default() }),
+            ..item(dt, 30.0, 80, "Rain")
+        };
+        let data = OpenWeatherResponse {
+            list: vec![
+                wet("2025-01-06T00:00:00Z", 5.0, Some(1.5), 0.4),
+                wet("2025-01-06T03:00:00Z", 10.0, None, 0.85),
+                wet("2025-01-06T06:00:00Z", 2.0, Some(2.5), 0.6),
+            ],
+            city: None,
+        };
+        let day = &normalize_openweather(&data, FixedOffset::east_opt(7 * 3600).unwrap()).unwrap()[0];
+
+        assert_eq!(day.precipitation_mm, Some(4.0));
+        assert_eq!(day.precipitation_probability, Some(85));
+        // m/s → km/jam, ambil maksimum hari itu
+        assert_eq!(day.wind_speed, Some(36.0));
+        assert_eq!(day.wind_gust, Some(72.0));
+    }
+
+    #[test]
+    fn test_hourly_slots_are_interpolated() {
+        let mut first = item("2025-01-06T06:00:00Z", 30.0, 60, "Rain");
+        first.rain = Some(OpenWeatherRain { three_hours: Some(3.0), ..Default::default() });
+        let data = OpenWeatherResponse {
+            list: vec![first, item("2025-01-06T09:00:00Z", 27.0, 90, "Clouds")],
+            city: None,
+        };
+        let wib = FixedOffset::east_opt(7 * 3600).unwrap();
+        let hours = normalize_openweather_hourly(&data, wib).unwrap();
+
+        let times: Vec<&str> = hours.iter().map(|h| h.time.as_str()).collect();
+        assert_eq!(times[..4], ["2025-01-06T13:00", "2025-01-06T14:00", "2025-01-06T15:00", "2025-01-06T16:00"]);
+        assert!(!hours[0].interpolated);
+        assert!(hours[1].interpolated);
+        // 30 → 27 dalam 3 jam, humidity 60 → 90
+        assert_eq!(hours[1].temperature, 29.0);
+        assert_eq!(hours[2].details.humidity, Some(80.0));
+        // Kondisi dan hujan mengikuti slot-nya
+        assert_eq!(hours[2].condition, Condition::Rain(Intensity::Moderate));
+        assert_eq!(hours[2].details.precipitation_mm, Some(1.0));
+        assert_eq!(hours[3].condition, Condition::Cloudy);
+        // Slot terakhir tetap mencakup 3 jam
+        assert_eq!(hours.len(), 6);
+    }
+
+    #[test]
+    fn test_response_offset_prefers_city_timezone() {
+        let wib = FixedOffset::east_opt(7 * 3600).unwrap();
+        let mut data = OpenWeatherResponse {
+            list: vec![item("2025-01-06T06:00:00Z", 30.0, 60, "Clouds")],
+            city: Some(OpenWeatherCity { timezone: Some(8 * 3600) }),
+        };
+        assert_eq!(response_offset(&data, wib), FixedOffset::east_opt(8 * 3600).unwrap());
+
+        data.city = None;
+        assert_eq!(response_offset(&data, wib), wib);
+    }
+
+    #[test]
+    fn test_current_conditions_are_parsed() {
+        let data: OpenWeatherCurrentResponse = serde_json::from_value(serde_json::json!({
+            "dt": DateTime::parse_from_rfc3339("2025-01-06T06:10:00Z").unwrap().timestamp(),
+            "main": { "temp": 30.4, "feels_like": 36.1, "temp_max": 31.0, "temp_min": 29.8, "humidity": 74 },
+            "weather": [{ "id": 500, "main": "Rain", "description": "light rain" }],
+            "wind": { "speed": 5.0 },
+            "rain": { "1h": 0.6 },
+        }))
+        .unwrap();
+
+        let current = normalize_openweather_current(&data, FixedOffset::east_opt(8 * 3600).unwrap()).unwrap();
+        assert_eq!(current.observed_at, "2025-01-06T14:10:00+08:00");
+        assert_eq!(current.temperature, 30.4);
+        assert_eq!(current.feels_like, Some(36.1));
+        assert_eq!(current.condition, Condition::Rain(Intensity::Light));
+        assert_eq!(current.details.wind_speed, Some(18.0));
+        assert_eq!(current.details.wind_gust, None);
+        assert_eq!(current.details.precipitation_mm, Some(0.6));
+    }
+
+    #[test]
+    fn test_short_feed_is_not_padded() {
+        let data = OpenWeatherResponse {
+            list: (0..5)
+                .map(|d| item(&format!("2025-01-{:02}T05:00:00Z", d + 6), 30.0, 80, "Clouds"))
+                .collect(),
+            city: None,
+        };
+        let days = normalize_openweather(&data, FixedOffset::east_opt(7 * 3600).unwrap()).unwrap();
+        assert_eq!(days.len(), 5);
+        assert_eq!(days.last().unwrap().date, "2025-01-10");
+    }
+
+    #[test]
+    fn test_condition_tie_goes_to_most_severe() {
+        let a = item("2025-01-06T00:00:00Z", 30.0, 70, "Clear");
+        let b = item("2025-01-06T03:00:00Z", 30.0, 70, "Thunderstorm");
+        let c = item("2025-01-06T06:00:00Z", 30.0, 70, "Clouds");
+        assert_eq!(dominant_condition(&[&a, &b, &c]), Condition::Thunderstorm);
 
-        let (condition, icon) = map_openweather_condition("rain");
-        assert_eq!(condition, "Rainy");
-        assert_eq!(icon, "rainy");
+        let d = item("2025-01-06T09:00:00Z", 30.0, 70, "Clear");
+        assert_eq!(dominant_condition(&[&a, &b, &c, &d]), Condition::Clear);
     }
 }
@@ -0,0 +1,453 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use super::error::ProviderError;
+
+/// Batas pemakaian per API key. Soft limit hanya warning, hard limit mematikan provider
+/// sampai window (hari/bulan UTC) berganti. `None` = tidak dibatasi.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct QuotaLimits {
+    pub daily_soft: Option<u64>,
+    pub daily_hard: Option<u64>,
+    pub monthly_soft: Option<u64>,
+    pub monthly_hard: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct UsageCounter {
+    pub success: u64,
+    pub failure: u64,
+}
+
+impl UsageCounter {
+    /// Provider menghitung semua request, termasuk yang gagal
+    pub fn total(&self) -> u64 {
+        self.success + self.failure
+    }
+
+    fn add(&mut self, success: bool) {
+        if success {
+            self.success += 1;
+        } else {
+            self.failure += 1;
+        }
+    }
+}
+
+/// Pemakaian satu API key pada hari dan bulan berjalan (UTC)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyUsage {
+    pub provider: String,
+    /// Label key yang aman ditampilkan, lihat `key_label`
+    pub key: String,
+    /// Hash key lengkap (`key_id`); dua key dengan 4 karakter terakhir sama tetap terpisah
+    #[serde(default)]
+    pub key_id: String,
+    pub day: String,
+    pub today: UsageCounter,
+    pub month: String,
+    pub this_month: UsageCounter,
+}
+
+impl KeyUsage {
+    fn new(provider: &str, api_key: Option<&str>, now: DateTime<Utc>) -> Self {
+        Self {
+            provider: provider.to_string(),
+            key: key_label(api_key),
+            key_id: key_id(api_key),
+            day: day_key(now),
+            today: UsageCounter::default(),
+            month: month_key(now),
+            this_month: UsageCounter::default(),
+        }
+    }
+
+    /// Reset counter yang window-nya sudah lewat
+    fn roll(&mut self, now: DateTime<Utc>) {
+        if self.day != day_key(now) {
+            self.day = day_key(now);
+            self.today = UsageCounter::default();
+        }
+        if self.month != month_key(now) {
+            self.month = month_key(now);
+            self.this_month = UsageCounter::default();
+        }
+    }
+}
+
+/// Satu baris laporan pemakaian untuk endpoint admin
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyUsageReport {
+    #[serde(flatten)]
+    pub usage: KeyUsage,
+    pub limits: QuotaLimits,
+    /// "ok", "soft_limit" atau "hard_limit"
+    pub status: &'static str,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct QuotaState {
+    keys: Vec<KeyUsage>,
+}
+
+type UsageMap = HashMap<(String, String), KeyUsage>;
+
+/// Penghitung request upstream per provider per API key, disimpan ke file JSON
+/// supaya tetap benar setelah restart
+#[derive(Debug)]
+pub struct QuotaTracker {
+    path: Option<PathBuf>,
+    limits: HashMap<String, QuotaLimits>,
+    usage: Arc<Mutex<UsageMap>>,
+    /// Satu-satunya jalur tulis ke `path`; serialisasi file tidak terjadi di task async
+    writer: Option<Sender<()>>,
+    save_lock: Arc<Mutex<()>>,
+}
+
+impl QuotaTracker {
+    /// Tanpa `path` counter hanya ada di memori
+    pub fn new(path: Option<PathBuf>, limits: HashMap<String, QuotaLimits>) -> Self {
+        let usage: UsageMap = path
+            .as_deref()
+            .map(load_state)
+            .unwrap_or_default()
+            .keys
+            .into_iter()
+            // File lama belum punya `key_id`, counter-nya tidak bisa dicocokkan ke key mana pun
+            .filter(|u| !u.key_id.is_empty())
+            .map(|u| ((u.provider.clone(), u.key_id.clone()), u))
+            .collect();
+        let usage = Arc::new(Mutex::new(usage));
+        let save_lock = Arc::new(Mutex::new(()));
+        let writer = path
+            .clone()
+            .map(|path| spawn_writer(path, usage.clone(), save_lock.clone()));
+
+        Self {
+            path,
+            limits,
+            usage,
+            writer,
+            save_lock,
+        }
+    }
+
+    pub fn limits(&self, provider_id: &str) -> QuotaLimits {
+        self.limits.get(provider_id).copied().unwrap_or_default()
+    }
+
+    /// Tolak request kalau hard limit key ini sudah tercapai di window berjalan
+    pub fn check(&self, provider_id: &str, api_key: Option<&str>) -> Result<(), ProviderError> {
+        self.check_at(provider_id, api_key, Utc::now())
+    }
+
+    fn check_at(&self, provider_id: &str, api_key: Option<&str>, now: DateTime<Utc>) -> Result<(), ProviderError> {
+        let limits = self.limits(provider_id);
+        let mut usage = self.usage.lock().unwrap();
+        let Some(entry) = usage.get_mut(&(provider_id.to_string(), key_id(api_key))) else {
+            return Ok(());
+        };
+        entry.roll(now);
+
+        let window = if limits.monthly_hard.is_some_and(|hard| entry.this_month.total() >= hard) {
+            Some(("monthly", next_month(now)))
+        } else if limits.daily_hard.is_some_and(|hard| entry.today.total() >= hard) {
+            Some(("daily", next_day(now)))
+        } else {
+            None
+        };
+
+        match window {
+            Some((window, resets_at)) => Err(ProviderError::QuotaLimitReached {
+                provider: provider_id.to_string(),
+                window,
+                resets_in: (resets_at - now).to_std().unwrap_or(Duration::ZERO),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Catat satu request upstream (sukses atau gagal); penyimpanan ke disk diserahkan ke writer
+    pub fn record(&self, provider_id: &str, api_key: Option<&str>, success: bool) {
+        self.record_at(provider_id, api_key, success, Utc::now());
+    }
+
+    fn record_at(&self, provider_id: &str, api_key: Option<&str>, success: bool, now: DateTime<Utc>) {
+        let limits = self.limits(provider_id);
+        {
+            let mut usage = self.usage.lock().unwrap();
+            let entry = usage
+                .entry((provider_id.to_string(), key_id(api_key)))
+                .or_insert_with(|| KeyUsage::new(provider_id, api_key, now));
+            entry.roll(now);
+            entry.today.add(success);
+            entry.this_month.add(success);
+
+            warn_on_crossing(entry, "daily", entry.today.total(), limits.daily_soft, limits.daily_hard);
+            warn_on_crossing(entry, "monthly", entry.this_month.total(), limits.monthly_soft, limits.monthly_hard);
+        }
+
+        if let Some(writer) = &self.writer {
+            let _ = writer.send(());
+        }
+    }
+
+    /// Simpan state sekarang juga (blocking), misal sebelum proses berhenti
+    pub fn flush(&self) {
+        if let Some(path) = &self.path {
+            save_snapshot(path, &self.usage, &self.save_lock);
+        }
+    }
+
+    /// Pemakaian semua key, diurutkan per provider lalu key
+    pub fn report(&self) -> Vec<KeyUsageReport> {
+        let now = Utc::now();
+        let mut usage = self.usage.lock().unwrap();
+        let mut rows: Vec<KeyUsageReport> = usage
+            .values_mut()
+            .map(|entry| {
+                entry.roll(now);
+                let limits = self.limits(&entry.provider);
+                let reached = |count: u64, limit: Option<u64>| limit.is_some_and(|l| count >= l);
+                let status = if reached(entry.today.total(), limits.daily_hard)
+                    || reached(entry.this_month.total(), limits.monthly_hard)
+                {
+                    "hard_limit"
+                } else if reached(entry.today.total(), limits.daily_soft)
+                    || reached(entry.this_month.total(), limits.monthly_soft)
+                {
+                    "soft_limit"
+                } else {
+                    "ok"
+                };
+                KeyUsageReport {
+                    usage: entry.clone(),
+                    limits,
+                    status,
+                }
+            })
+            .collect();
+        rows.sort_by(|a, b| (&a.usage.provider, &a.usage.key).cmp(&(&b.usage.provider, &b.usage.key)));
+        rows
+    }
+}
+
+/// Key tidak pernah ditampilkan utuh: 4 karakter terakhir cukup untuk dikenali admin
+pub fn key_label(api_key: Option<&str>) -> String {
+    match api_key.map(str::trim).filter(|k| !k.is_empty()) {
+        None => "-".to_string(),
+        Some(key) if key.chars().count() <= 4 => "****".to_string(),
+        Some(key) => {
+            let tail: String = key.chars().rev().take(4).collect::<Vec<_>>().into_iter().rev().collect();
+            format!("****{}", tail)
+        }
+    }
+}
+
+/// Identitas key untuk counter: hash FNV-1a 64-bit dari key lengkap. Stabil antar build
+/// (beda dengan `DefaultHasher`), jadi tetap cocok dengan file yang disimpan.
+pub fn key_id(api_key: Option<&str>) -> String {
+    match api_key.map(str::trim).filter(|k| !k.is_empty()) {
+        None => "-".to_string(),
+        Some(key) => {
+            let hash = key
+                .bytes()
+                .fold(0xcbf2_9ce4_8422_2325_u64, |hash, b| (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3));
+            format!("{:016x}", hash)
+        }
+    }
+}
+
+/// Thread penulis tunggal: tiap sinyal dari `record` memicu satu simpan, sinyal yang
+/// menumpuk selama menulis digabung jadi satu. Berhenti saat tracker di-drop.
+fn spawn_writer(path: PathBuf, usage: Arc<Mutex<UsageMap>>, save_lock: Arc<Mutex<()>>) -> Sender<()> {
+    let (tx, rx) = mpsc::channel::<()>();
+    thread::spawn(move || {
+        while rx.recv().is_ok() {
+            while rx.try_recv().is_ok() {}
+            save_snapshot(&path, &usage, &save_lock);
+        }
+    });
+    tx
+}
+
+/// Snapshot diambil di dalam `save_lock`, jadi snapshot lama tidak pernah menimpa yang baru
+fn save_snapshot(path: &Path, usage: &Mutex<UsageMap>, save_lock: &Mutex<()>) {
+    let _guard = save_lock.lock().unwrap();
+    let state = QuotaState {
+        keys: usage.lock().unwrap().values().cloned().collect(),
+    };
+    save_state(path, &state);
+}
+
+/// Log sekali saat counter tepat melewati soft/hard limit
+fn warn_on_crossing(entry: &KeyUsage, window: &str, count: u64, soft: Option<u64>, hard: Option<u64>) {
+    if soft == Some(count) {
+        warn!(
+            "[Quota] {} key {} reached {} soft limit ({} requests)",
+            entry.provider, entry.key, window, count
+        );
+    }
+    if hard == Some(count) {
+        warn!(
+            "[Quota] {} key {} reached {} hard limit ({} requests), provider disabled until the window resets",
+            entry.provider, entry.key, window, count
+        );
+    }
+}
+
+fn day_key(now: DateTime<Utc>) -> String {
+    now.format("%Y-%m-%d").to_string()
+}
+
+fn month_key(now: DateTime<Utc>) -> String {
+    now.format("%Y-%m").to_string()
+}
+
+fn next_day(now: DateTime<Utc>) -> DateTime<Utc> {
+    let tomorrow = now.date_naive().succ_opt().unwrap_or(now.date_naive());
+    tomorrow.and_hms_opt(0, 0, 0).unwrap().and_utc()
+}
+
+fn next_month(now: DateTime<Utc>) -> DateTime<Utc> {
+    let (year, month) = if now.month() == 12 {
+        (now.year() + 1, 1)
+    } else {
+        (now.year(), now.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|d| d.and_utc())
+        .unwrap_or(now)
+}
+
+fn load_state(path: &Path) -> QuotaState {
+    match fs::read_to_string(path) {
+        Ok(json) => match serde_json::from_str::<QuotaState>(&json) {
+            Ok(state) => {
+                info!("[Quota] Loaded usage for {} key(s) from {}", state.keys.len(), path.display());
+                state
+            }
+            Err(e) => {
+                warn!("[Quota] Ignoring unreadable usage file {}: {}", path.display(), e);
+                QuotaState::default()
+            }
+        },
+        Err(_) => QuotaState::default(),
+    }
+}
+
+/// Tulis ke file sementara lalu rename, supaya file tidak setengah jadi kalau proses mati
+fn save_state(path: &Path, state: &QuotaState) {
+    // Nama unik per proses, supaya dua instance dengan file yang sama tidak berbagi file sementara
+    let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    let result = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .map(fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| serde_json::to_vec_pretty(state).map_err(std::io::Error::other))
+        .and_then(|json| fs::write(&tmp, json))
+        .and_then(|_| fs::rename(&tmp, path));
+
+    if let Err(e) = result {
+        warn!("[Quota] Failed to persist usage to {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ensemble::{OPEN_METEO, WEATHER_API};
+    use chrono::TimeZone;
+
+    fn at(y: i32, m: u32, d: u32, h: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap()
+    }
+
+    fn tracker(path: Option<PathBuf>, limits: QuotaLimits) -> QuotaTracker {
+        QuotaTracker::new(path, HashMap::from([(WEATHER_API.to_string(), limits)]))
+    }
+
+    #[test]
+    fn test_key_label_hides_key() {
+        assert_eq!(key_label(Some("abcdef123456")), "****3456");
+        assert_eq!(key_label(Some("abc")), "****");
+        assert_eq!(key_label(None), "-");
+    }
+
+    #[test]
+    fn test_keys_with_same_suffix_are_counted_separately() {
+        let quota = tracker(None, QuotaLimits { daily_hard: Some(1), ..QuotaLimits::default() });
+        let now = at(2025, 3, 10, 8);
+        quota.record_at(WEATHER_API, Some("first-key-abcd"), true, now);
+
+        assert!(quota.check_at(WEATHER_API, Some("first-key-abcd"), now).is_err());
+        assert!(quota.check_at(WEATHER_API, Some("other-key-abcd"), now).is_ok());
+        assert_eq!(key_label(Some("first-key-abcd")), key_label(Some("other-key-abcd")));
+    }
+
+    #[test]
+    fn test_hard_limit_disables_until_day_resets() {
+        let quota = tracker(None, QuotaLimits { daily_hard: Some(2), ..QuotaLimits::default() });
+        let key = Some("secret-key-1");
+        let morning = at(2025, 3, 10, 8);
+
+        quota.record_at(WEATHER_API, key, true, morning);
+        assert!(quota.check_at(WEATHER_API, key, morning).is_ok());
+        quota.record_at(WEATHER_API, key, false, morning);
+
+        match quota.check_at(WEATHER_API, key, morning) {
+            Err(ProviderError::QuotaLimitReached { window, resets_in, .. }) => {
+                assert_eq!(window, "daily");
+                assert_eq!(resets_in, Duration::from_secs(16 * 3600));
+            }
+            other => panic!("Expected QuotaLimitReached, got {:?}", other),
+        }
+        // Key lain tidak terpengaruh
+        assert!(quota.check_at(WEATHER_API, Some("secret-key-2"), morning).is_ok());
+        // Hari berikutnya counter harian mulai dari nol lagi
+        assert!(quota.check_at(WEATHER_API, key, at(2025, 3, 11, 0)).is_ok());
+    }
+
+    #[test]
+    fn test_monthly_counter_survives_day_change() {
+        let quota = tracker(None, QuotaLimits { monthly_hard: Some(3), ..QuotaLimits::default() });
+        for day in 1..=3 {
+            quota.record_at(WEATHER_API, Some("secret-key-1"), true, at(2025, 12, day, 12));
+        }
+        let err = quota.check_at(WEATHER_API, Some("secret-key-1"), at(2025, 12, 31, 12)).unwrap_err();
+        assert!(matches!(err, ProviderError::QuotaLimitReached { window: "monthly", .. }));
+        assert!(quota.check_at(WEATHER_API, Some("secret-key-1"), at(2026, 1, 1, 0)).is_ok());
+    }
+
+    #[test]
+    fn test_usage_is_persisted() {
+        let dir = std::env::temp_dir().join(format!("quota-test-{}", fastrand::u64(..)));
+        let path = dir.join("usage.json");
+        let quota = tracker(Some(path.clone()), QuotaLimits::default());
+        quota.record(OPEN_METEO, None, true);
+        quota.record(WEATHER_API, Some("secret-key-1"), false);
+        quota.flush();
+
+        let saved = fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("secret-key-1"));
+
+        let reloaded = tracker(Some(path), QuotaLimits { daily_hard: Some(1), ..QuotaLimits::default() });
+        let report = reloaded.report();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[1].usage.key, "****ey-1");
+        assert_eq!(report[1].usage.today.failure, 1);
+        assert_eq!(report[1].usage.this_month.total(), 1);
+        // Counter yang dimuat tetap terhubung ke key-nya
+        assert!(reloaded.check(WEATHER_API, Some("secret-key-1")).is_err());
+        fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,175 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use log::info;
+use super::error::ProviderError;
+
+/// Batas request ke satu provider sesuai plan-nya. `None` = tidak dibatasi di window itu.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RateLimit {
+    pub per_second: Option<u32>,
+    pub per_minute: Option<u32>,
+    pub per_day: Option<u32>,
+    /// Lama maksimal request boleh antre menunggu token sebelum provider dilewati
+    pub max_wait: Duration,
+}
+
+impl RateLimit {
+    pub fn is_unlimited(&self) -> bool {
+        self.per_second.is_none() && self.per_minute.is_none() && self.per_day.is_none()
+    }
+}
+
+/// Token bucket satu window: kapasitas = budget, terisi ulang merata sepanjang window
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    /// Boleh negatif: token yang sudah dipesan request yang sedang antre
+    tokens: f64,
+    last: Instant,
+}
+
+impl Bucket {
+    fn new(budget: u32, window: Duration) -> Self {
+        let capacity = budget.max(1) as f64;
+        Self {
+            capacity,
+            refill_per_sec: capacity / window.as_secs_f64(),
+            tokens: capacity,
+            last: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last = now;
+    }
+
+    /// Waktu tunggu sampai satu token tersedia
+    fn wait(&self) -> Duration {
+        if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.refill_per_sec)
+        }
+    }
+}
+
+/// Rate limiter satu provider, di-share semua jalur yang memanggil provider tersebut
+#[derive(Debug)]
+pub struct RateLimiter {
+    provider: String,
+    max_wait: Duration,
+    buckets: Mutex<Vec<Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(provider: &str, limit: RateLimit) -> Self {
+        let buckets = [
+            (limit.per_second, Duration::from_secs(1)),
+            (limit.per_minute, Duration::from_secs(60)),
+            (limit.per_day, Duration::from_secs(86_400)),
+        ]
+        .into_iter()
+        .filter_map(|(budget, window)| budget.map(|b| Bucket::new(b, window)))
+        .collect();
+
+        Self {
+            provider: provider.to_string(),
+            max_wait: limit.max_wait,
+            buckets: Mutex::new(buckets),
+        }
+    }
+
+    /// Pesan satu token di semua window. Kalau harus menunggu lebih lama dari `max_wait`,
+    /// request ditolak (`RateLimited`) tanpa memakai token; kalau tidak, antre sampai giliran.
+    pub async fn acquire(&self) -> Result<(), ProviderError> {
+        let wait = self.reserve()?;
+        if !wait.is_zero() {
+            info!("[RateLimit] {} budget busy, queueing request for {:?}", self.provider, wait);
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
+    }
+
+    fn reserve(&self) -> Result<Duration, ProviderError> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let now = Instant::now();
+        for bucket in buckets.iter_mut() {
+            bucket.refill(now);
+        }
+
+        let wait = buckets.iter().map(Bucket::wait).max().unwrap_or(Duration::ZERO);
+        if wait > self.max_wait {
+            return Err(ProviderError::RateLimited {
+                provider: self.provider.clone(),
+                retry_in: wait,
+            });
+        }
+        for bucket in buckets.iter_mut() {
+            bucket.tokens -= 1.0;
+        }
+        Ok(wait)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_when_budget_exhausted() {
+        let limiter = RateLimiter::new(
+            "open_weather",
+            RateLimit {
+                per_minute: Some(2),
+                ..RateLimit::default()
+            },
+        );
+        assert_eq!(limiter.reserve().unwrap(), Duration::ZERO);
+        assert_eq!(limiter.reserve().unwrap(), Duration::ZERO);
+
+        match limiter.reserve() {
+            Err(ProviderError::RateLimited { provider, retry_in }) => {
+                assert_eq!(provider, "open_weather");
+                assert!(retry_in > Duration::from_secs(25) && retry_in <= Duration::from_secs(30));
+            }
+            other => panic!("Expected RateLimited, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_tightest_window_wins() {
+        let limiter = RateLimiter::new(
+            "weather_api",
+            RateLimit {
+                per_second: Some(100),
+                per_day: Some(1),
+                ..RateLimit::default()
+            },
+        );
+        assert!(limiter.reserve().is_ok());
+        assert!(limiter.reserve().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_short_waits_are_queued() {
+        let limiter = RateLimiter::new(
+            "open_meteo",
+            RateLimit {
+                per_second: Some(20),
+                max_wait: Duration::from_millis(500),
+                ..RateLimit::default()
+            },
+        );
+        for _ in 0..20 {
+            limiter.acquire().await.unwrap();
+        }
+
+        // Bucket kosong: request berikutnya antre ~50ms, bukan ditolak
+        let start = Instant::now();
+        limiter.acquire().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
+}
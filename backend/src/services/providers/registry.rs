@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use log::{info, warn};
+use crate::models::City;
+use crate::models::ensemble::{OPEN_METEO, OPEN_WEATHER, WEATHER_API};
+use crate::utils::Config;
+use super::circuit_breaker::{BreakerConfig, BreakerSnapshot, CircuitBreaker};
+use super::{
+    ApiKeyPool, CurrentResult, HourlyResult, KeyStatus, OpenMeteoProvider, OpenWeatherProvider, ProviderError,
+    ProviderResult, UpstreamClient, WeatherApiProvider, WeatherProvider,
+};
+
+/// Daftar provider aktif, urutannya dipakai sebagai urutan fallback.
+/// Tiap provider punya circuit breaker sendiri, di-share oleh semua clone registry.
+#[derive(Clone)]
+pub struct ProviderRegistry {
+    providers: Vec<Arc<dyn WeatherProvider>>,
+    breakers: HashMap<&'static str, Arc<CircuitBreaker>>,
+}
+
+impl ProviderRegistry {
+    #[allow(dead_code)]
+    pub fn new(providers: Vec<Arc<dyn WeatherProvider>>) -> Self {
+        Self::with_breaker_config(providers, BreakerConfig::default())
+    }
+
+    pub fn with_breaker_config(providers: Vec<Arc<dyn WeatherProvider>>, config: BreakerConfig) -> Self {
+        let breakers = providers
+            .iter()
+            .map(|p| (p.id(), Arc::new(CircuitBreaker::new(p.id(), config))))
+            .collect();
+        Self { providers, breakers }
+    }
+
+    /// Bangun registry dari `Config.enabled_providers`, semua provider berbagi `upstream`.
+    /// Provider yang butuh API key dilewati kalau tidak ada satu pun key yang terisi.
+    pub fn from_config(config: &Config, upstream: Arc<UpstreamClient>) -> Self {
+        let providers: Vec<Arc<dyn WeatherProvider>> = config
+            .enabled_providers
+            .iter()
+            .filter_map(|id| -> Option<Arc<dyn WeatherProvider>> {
+                match id.as_str() {
+                    OPEN_METEO => Some(Arc::new(OpenMeteoProvider::new(
+                        upstream.clone(),
+                        config.open_meteo_base_url.clone(),
+                    ))),
+                    OPEN_WEATHER => usable_keys(OPEN_WEATHER, &config.openweather_keys, config).map(
+                        |keys| -> Arc<dyn WeatherProvider> {
+                            Arc::new(OpenWeatherProvider::new(
+                                upstream.clone(),
+                                config.openweather_base_url.clone(),
+                                keys,
+                            ))
+                        },
+                    ),
+                    WEATHER_API => usable_keys(WEATHER_API, &config.weatherapi_keys, config).map(
+                        |keys| -> Arc<dyn WeatherProvider> {
+                            Arc::new(
+                                WeatherApiProvider::new(
+                                    upstream.clone(),
+                                    config.weatherapi_base_url.clone(),
+                                    keys,
+                                )
+                                .with_max_distance_km(config.weatherapi_max_distance_km),
+                            )
+                        },
+                    ),
+                    other => {
+                        warn!("[Providers] Unknown provider '{}' in WEATHER_PROVIDERS, ignoring", other);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        let registry = Self::with_breaker_config(providers, config.breaker);
+        if registry.is_empty() {
+            warn!("[Providers] No weather provider enabled, every forecast request will fail");
+        }
+        info!("[Providers] Registry initialized (fallback order): {:?}", registry.ids());
+        for provider in registry.providers() {
+            let caps = provider.capabilities();
+            info!(
+                "[Providers] {} ({}): {} [{} days, query by {:?}, api key: {}]",
+                provider.name(),
+                provider.id(),
+                caps.description,
+                caps.forecast_days,
+                caps.location_query,
+                caps.requires_api_key
+            );
+        }
+        registry
+    }
+
+    pub fn providers(&self) -> &[Arc<dyn WeatherProvider>] {
+        &self.providers
+    }
+
+    pub fn ids(&self) -> Vec<&'static str> {
+        self.providers.iter().map(|p| p.id()).collect()
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, id: &str) -> Option<Arc<dyn WeatherProvider>> {
+        self.providers.iter().find(|p| p.id() == id).cloned()
+    }
+
+    /// Fetch lewat circuit breaker provider: kalau breaker terbuka langsung gagal tanpa request.
+    /// Penolakan rate limiter/kuota lokal tidak dihitung sebagai kegagalan provider.
+    pub async fn fetch(&self, provider: &dyn WeatherProvider, city: &City) -> ProviderResult {
+        self.guarded(provider, provider.fetch_forecast(city)).await
+    }
+
+    /// Forecast per jam, lewat circuit breaker yang sama dengan forecast harian
+    pub async fn fetch_hourly(&self, provider: &dyn WeatherProvider, city: &City) -> HourlyResult {
+        self.guarded(provider, provider.fetch_hourly(city)).await
+    }
+
+    /// Kondisi saat ini; kegagalannya juga ikut menggerakkan breaker provider
+    pub async fn fetch_current(&self, provider: &dyn WeatherProvider, city: &City) -> CurrentResult {
+        self.guarded(provider, provider.fetch_current(city)).await
+    }
+
+    async fn guarded<T>(
+        &self,
+        provider: &dyn WeatherProvider,
+        request: impl Future<Output = Result<T, ProviderError>>,
+    ) -> Result<T, ProviderError> {
+        let Some(breaker) = self.breakers.get(provider.id()) else {
+            return request.await;
+        };
+        breaker.try_acquire()?;
+
+        let result = request.await;
+        match &result {
+            Ok(_) => breaker.record_success(),
+            // Bukan tanda provider bermasalah: budget lokal habis, lokasi satu kota salah resolve,
+            // atau provider memang tidak punya data jenis ini. Percobaan half-open tetap dilepas.
+            Err(
+                ProviderError::RateLimited { .. }
+                | ProviderError::QuotaLimitReached { .. }
+                | ProviderError::LocationMismatch { .. }
+                | ProviderError::Unsupported(_),
+            ) => breaker.release_trial(),
+            Err(_) => breaker.record_failure(),
+        }
+        result
+    }
+
+    /// Status breaker semua provider, sesuai urutan registry
+    pub fn breaker_states(&self) -> Vec<BreakerSnapshot> {
+        self.providers
+            .iter()
+            .filter_map(|p| self.breakers.get(p.id()))
+            .map(|b| b.snapshot())
+            .collect()
+    }
+
+    /// Status semua API key, sesuai urutan registry
+    pub fn api_keys(&self) -> Vec<KeyStatus> {
+        self.providers.iter().flat_map(|p| p.api_keys()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
+
+/// Pool key provider, `None` (dengan warning) kalau tidak ada key yang bisa dipakai
+fn usable_keys(id: &'static str, keys: &[String], config: &Config) -> Option<ApiKeyPool> {
+    let pool = ApiKeyPool::new(id, keys, config.key_pool);
+    if pool.is_empty() {
+        warn!("[Providers] {} API key not configured, provider disabled", id);
+        return None;
+    }
+    if pool.len() > 1 {
+        info!("[Providers] {} using {} API keys ({:?})", id, pool.len(), config.key_pool.rotation);
+    }
+    Some(pool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::test_support::{spawn_stub_server, StubRoute};
+    use super::super::{BreakerState, LocationQuery, ProviderCapabilities};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Provider palsu yang selalu gagal dan menghitung berapa kali dipanggil
+    struct FailingProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl WeatherProvider for FailingProvider {
+        fn id(&self) -> &'static str {
+            WEATHER_API
+        }
+
+        fn name(&self) -> &'static str {
+            "Failing"
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                description: "test",
+                forecast_days: 7,
+                requires_api_key: false,
+                location_query: LocationQuery::CityName,
+            }
+        }
+
+        async fn fetch_forecast(&self, _city: &City) -> ProviderResult {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(ProviderError::Timeout)
+        }
+    }
+
+    fn config_with(providers: &str, openweather_key: &str, weatherapi_key: &str) -> Config {
+        let mut config = Config::from_env();
+        config.enabled_providers = providers.split(',').map(|s| s.to_string()).collect();
+        config.openweather_keys = vec![openweather_key.to_string()];
+        config.weatherapi_keys = vec![weatherapi_key.to_string()];
+        config
+    }
+
+    #[test]
+    fn test_registry_keeps_configured_order() {
+        let config = config_with("weather_api,open_meteo,open_weather", "ow-key", "wa-key");
+        let registry = ProviderRegistry::from_config(&config, Arc::default());
+        assert_eq!(registry.ids(), vec![WEATHER_API, OPEN_METEO, OPEN_WEATHER]);
+    }
+
+    #[test]
+    fn test_registry_skips_providers_without_key() {
+        let config = config_with("open_meteo,open_weather,weather_api", "your-key-here", "");
+        let registry = ProviderRegistry::from_config(&config, Arc::default());
+        assert_eq!(registry.ids(), vec![OPEN_METEO]);
+        assert!(registry.get(OPEN_WEATHER).is_none());
+    }
+
+    #[test]
+    fn test_registry_ignores_unknown_provider() {
+        let config = config_with("open_meteo,bmkg", "", "");
+        let registry = ProviderRegistry::from_config(&config, Arc::default());
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_open_breaker_skips_provider() {
+        let provider = Arc::new(FailingProvider { calls: AtomicUsize::new(0) });
+        let registry = ProviderRegistry::with_breaker_config(
+            vec![provider.clone()],
+            BreakerConfig {
+                failure_threshold: 2,
+                cool_down: Duration::from_secs(60),
+            },
+        );
+        let city = City {
+            id: 1,
+            name: "Jakarta",
+            province: "DKI Jakarta",
+            latitude: -6.2088,
+            longitude: 106.8456,
+        };
+
+        for _ in 0..2 {
+            assert!(registry.fetch(provider.as_ref(), &city).await.is_err());
+        }
+        assert_eq!(registry.breaker_states()[0].state, BreakerState::Open);
+
+        let err = registry.fetch(provider.as_ref(), &city).await.unwrap_err();
+        assert!(matches!(err, ProviderError::CircuitOpen { .. }));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_skipped_trial_releases_half_open() {
+        let provider = Arc::new(FailingProvider { calls: AtomicUsize::new(0) });
+        let registry = ProviderRegistry::with_breaker_config(
+            vec![provider.clone()],
+            BreakerConfig {
+                failure_threshold: 2,
+                cool_down: Duration::ZERO,
+            },
+        );
+        let city = City {
+            id: 1,
+            name: "Jakarta",
+            province: "DKI Jakarta",
+            latitude: -6.2088,
+            longitude: 106.8456,
+        };
+
+        for _ in 0..2 {
+            assert!(registry.fetch(provider.as_ref(), &city).await.is_err());
+        }
+        // Percobaan half-open ke API yang tidak didukung tidak menahan breaker di half-open
+        let err = registry.fetch_hourly(provider.as_ref(), &city).await.unwrap_err();
+        assert!(matches!(err, ProviderError::Unsupported(_)));
+        assert_eq!(registry.breaker_states()[0].state, BreakerState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_cooling_keys_do_not_trip_breaker() {
+        let base_url = spawn_stub_server(vec![StubRoute::new("/data/2.5/forecast", 429, "{}")]);
+        let provider = Arc::new(OpenWeatherProvider::new(
+            Arc::new(UpstreamClient::default()),
+            base_url,
+            ApiKeyPool::single(OPEN_WEATHER, "test-key"),
+        ));
+        let registry = ProviderRegistry::with_breaker_config(
+            vec![provider.clone()],
+            BreakerConfig {
+                failure_threshold: 2,
+                cool_down: Duration::from_secs(60),
+            },
+        );
+        let city = City {
+            id: 1,
+            name: "Jakarta",
+            province: "DKI Jakarta",
+            latitude: -6.2088,
+            longitude: 106.8456,
+        };
+
+        // 429 sungguhan dari provider dihitung sekali, lalu key istirahat
+        let err = registry.fetch(provider.as_ref(), &city).await.unwrap_err();
+        assert!(matches!(err, ProviderError::QuotaExhausted { .. }));
+        for _ in 0..3 {
+            let err = registry.fetch(provider.as_ref(), &city).await.unwrap_err();
+            assert!(matches!(err, ProviderError::RateLimited { .. }));
+        }
+        let state = &registry.breaker_states()[0];
+        assert_eq!(state.state, BreakerState::Closed);
+        assert_eq!(state.consecutive_failures, 1);
+    }
+}
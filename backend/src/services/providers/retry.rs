@@ -0,0 +1,96 @@
+use std::time::Duration;
+use super::error::ProviderError;
+
+/// Kebijakan retry per provider: exponential backoff + jitter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Total percobaan, termasuk request pertama (1 = tanpa retry)
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Porsi delay yang diacak (0.0 = tanpa jitter, 1.0 = full jitter)
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(5),
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay sebelum percobaan berikutnya, `None` kalau tidak perlu/boleh retry lagi.
+    /// `attempt` adalah nomor percobaan yang barusan gagal (mulai dari 1).
+    pub fn next_delay(&self, attempt: u32, error: &ProviderError) -> Option<Duration> {
+        if attempt >= self.max_attempts || !error.is_retryable() {
+            return None;
+        }
+
+        // Retry-After dari server dihormati, tapi kalau lebih lama dari max_delay menyerah saja
+        if let Some(retry_after) = error.retry_after() {
+            return (retry_after <= self.max_delay).then_some(retry_after);
+        }
+
+        Some(self.backoff(attempt))
+    }
+
+    /// base_delay * 2^(attempt-1), dibatasi max_delay, lalu dikurangi jitter acak
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        exp.mul_f64(1.0 - jitter * fastrand::f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    fn status_error(code: u16, retry_after: Option<Duration>) -> ProviderError {
+        ProviderError::from_status(StatusCode::from_u16(code).unwrap(), retry_after)
+    }
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+            jitter: 0.0,
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(8), Duration::from_millis(1000));
+
+        let jittered = RetryPolicy { jitter: 0.5, ..policy };
+        for _ in 0..20 {
+            let delay = jittered.backoff(2);
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn test_next_delay_respects_attempts_and_retry_after() {
+        let policy = RetryPolicy { jitter: 0.0, ..RetryPolicy::default() };
+        let error = status_error(503, None);
+        assert_eq!(policy.next_delay(1, &error), Some(Duration::from_millis(500)));
+        assert_eq!(policy.next_delay(3, &error), None);
+
+        let throttled = status_error(429, Some(Duration::from_secs(2)));
+        assert_eq!(policy.next_delay(1, &throttled), Some(Duration::from_secs(2)));
+
+        let too_long = status_error(429, Some(Duration::from_secs(60)));
+        assert_eq!(policy.next_delay(1, &too_long), None);
+
+        assert_eq!(policy.next_delay(1, &status_error(401, None)), None);
+    }
+}
@@ -0,0 +1,250 @@
+//! Stub HTTP server lokal untuk test provider tanpa internet
+use std::cell::Cell;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+thread_local! {
+    static FIXTURE_START: Cell<chrono::NaiveDate> =
+        const { Cell::new(chrono::NaiveDate::from_ymd_opt(2025, 1, 6).unwrap()) };
+}
+
+/// Buat fixture di dalam `f` dengan hari pertama `start`, bukan 2025-01-06.
+/// Dipakai test yang butuh data mulai hari ini (integration test API end-to-end).
+#[allow(dead_code)]
+pub fn with_fixture_start<T>(start: chrono::NaiveDate, f: impl FnOnce() -> T) -> T {
+    let previous = FIXTURE_START.with(|s| s.replace(start));
+    let result = f();
+    FIXTURE_START.with(|s| s.set(previous));
+    result
+}
+
+/// Satu route stub: request yang path-nya diawali `path_prefix` dibalas `status` + `body`
+#[derive(Clone)]
+pub struct StubRoute {
+    pub path_prefix: &'static str,
+    pub status: u16,
+    pub body: String,
+    pub headers: Vec<(&'static str, String)>,
+    /// Batas berapa kali route ini dipakai, setelah itu route berikutnya yang dicocokkan
+    pub times: Option<usize>,
+}
+
+impl StubRoute {
+    pub fn new(path_prefix: &'static str, status: u16, body: &str) -> Self {
+        Self {
+            path_prefix,
+            status,
+            body: body.to_string(),
+            headers: Vec::new(),
+            times: None,
+        }
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: &str) -> Self {
+        self.headers.push((name, value.to_string()));
+        self
+    }
+
+    pub fn times(mut self, times: usize) -> Self {
+        self.times = Some(times);
+        self
+    }
+}
+
+/// Jalankan stub server di port random, return base URL-nya (contoh: `http://127.0.0.1:41234`).
+/// Route dicocokkan berurutan, yang pertama cocok yang dipakai.
+pub fn spawn_stub_server(mut routes: Vec<StubRoute>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind stub server");
+    let addr = listener.local_addr().expect("stub server address");
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut buf = [0u8; 8192];
+            let n = stream.read(&mut buf).unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let path = request.split_whitespace().nth(1).unwrap_or("/");
+
+            let (status, body, headers) = routes
+                .iter_mut()
+                .find(|r| path.starts_with(r.path_prefix) && r.times != Some(0))
+                .map(|r| {
+                    r.times = r.times.map(|t| t - 1);
+                    (r.status, r.body.clone(), r.headers.clone())
+                })
+                .unwrap_or((404, "{}".to_string(), Vec::new()));
+
+            let extra_headers: String = headers
+                .iter()
+                .map(|(name, value)| format!("{}: {}\r\n", name, value))
+                .collect();
+            let response = format!(
+                "HTTP/1.1 {} Stub\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                extra_headers,
+                body
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    format!("http://{}", addr)
+}
+
+/// Payload Open-Meteo 7 hari: temp_max = `base_max + hari`, temp_min = `base_max - 8 + hari`.
+/// Juga berisi `hourly` 3 hari (suhu `base_max - 4`, hujan lokal jam 14-16 WIB) dan
+/// `current` jam 13:15 WIB hari pertama (suhu `base_max - 2`).
+pub fn open_meteo_fixture(base_max: f32) -> String {
+    let days: Vec<usize> = (0..7).collect();
+    let hours: Vec<usize> = (0..72).collect();
+    let afternoon = |h: &usize| (14..=16).contains(&(h % 24));
+    serde_json::json!({
+        "current": {
+            "time": fixture_hour(0, 13) + 15 * 60,
+            "interval": 900,
+            "temperature_2m": base_max - 2.0,
+            "apparent_temperature": base_max + 2.0,
+            "relative_humidity_2m": 70,
+            "precipitation": 0.0,
+            "weather_code": 2,
+            "wind_speed_10m": 12.0,
+            "wind_gusts_10m": 24.0,
+        },
+        "hourly": {
+            "time": hours.iter().map(|h| fixture_hour(h / 24, h % 24)).collect::<Vec<_>>(),
+            "temperature_2m": hours.iter().map(|_| base_max - 4.0).collect::<Vec<_>>(),
+            "weather_code": hours.iter().map(|h| if afternoon(h) { 80 } else { 3 }).collect::<Vec<_>>(),
+            "relative_humidity_2m": hours.iter().map(|_| 80).collect::<Vec<_>>(),
+            "precipitation": hours.iter().map(|h| if afternoon(h) { 1.5 } else { 0.0 }).collect::<Vec<_>>(),
+        },
+        "daily": {
+            "time": days.iter().map(|d| fixture_date(*d)).collect::<Vec<_>>(),
+            "temperature_2m_max": days.iter().map(|d| base_max + *d as f32).collect::<Vec<_>>(),
+            "temperature_2m_min": days.iter().map(|d| base_max - 8.0 + *d as f32).collect::<Vec<_>>(),
+            "relative_humidity_2m_mean": days.iter().map(|_| 80).collect::<Vec<_>>(),
+            "weather_code": days.iter().map(|_| 3).collect::<Vec<_>>(),
+            "precipitation_sum": days.iter().map(|_| 2.4).collect::<Vec<_>>(),
+            "precipitation_probability_max": days.iter().map(|_| 65).collect::<Vec<_>>(),
+            "wind_speed_10m_max": days.iter().map(|_| 18.0).collect::<Vec<_>>(),
+            "wind_gusts_10m_max": days.iter().map(|_| 31.0).collect::<Vec<_>>(),
+        }
+    })
+    .to_string()
+}
+
+/// Payload OpenWeatherMap: satu item per hari jam 05:00 UTC, 7 hari
+pub fn openweather_fixture(base_max: f32) -> String {
+    let list: Vec<serde_json::Value> = (0..7)
+        .map(|d| {
+            let dt = chrono::NaiveDate::parse_from_str(&fixture_date(d), "%Y-%m-%d")
+                .unwrap()
+                .and_hms_opt(5, 0, 0)
+                .unwrap()
+                .and_utc()
+                .timestamp();
+            serde_json::json!({
+                "dt": dt,
+                "main": {
+                    "temp": base_max - 2.0 + d as f32,
+                    "temp_max": base_max + d as f32,
+                    "temp_min": base_max - 8.0 + d as f32,
+                    "humidity": 80,
+                },
+                "weather": [{ "id": 804, "main": "Clouds", "description": "overcast clouds" }],
+                "wind": { "speed": 5.0, "gust": 8.6 },
+                "pop": 0.65,
+                "rain": { "3h": 2.4 },
+            })
+        })
+        .collect();
+    serde_json::json!({ "list": list, "city": { "timezone": 25200 } }).to_string()
+}
+
+/// Payload OpenWeatherMap `/data/2.5/weather`: observasi 13:00 WIB hari pertama, mendung
+pub fn openweather_current_fixture(temp: f32) -> String {
+    serde_json::json!({
+        "dt": fixture_hour(0, 13),
+        "main": { "temp": temp, "feels_like": temp + 5.0, "temp_max": temp, "temp_min": temp, "humidity": 76 },
+        "weather": [{ "id": 804, "main": "Clouds", "description": "overcast clouds" }],
+        "wind": { "speed": 5.0 },
+    })
+    .to_string()
+}
+
+/// Payload WeatherAPI `current.json`: observasi 13:15 WIB hari pertama, berawan sebagian
+pub fn weatherapi_current_fixture(temp: f32) -> String {
+    serde_json::json!({
+        "location": { "name": "Jakarta", "region": "Jakarta Raya", "lat": -6.21, "lon": 106.85 },
+        "current": {
+            "last_updated_epoch": fixture_hour(0, 13) + 15 * 60,
+            "temp_c": temp,
+            "feelslike_c": temp + 4.5,
+            "humidity": 70,
+            "wind_kph": 13.0,
+            "gust_kph": 22.0,
+            "precip_mm": 0.0,
+            "condition": { "text": "Partly cloudy", "icon": "//cdn.weatherapi.com/weather/64x64/day/116.png" },
+        },
+    })
+    .to_string()
+}
+
+/// Payload WeatherAPI 7 hari
+pub fn weatherapi_fixture(base_max: f32) -> String {
+    let forecastday: Vec<serde_json::Value> = (0..7)
+        .map(|d| {
+            serde_json::json!({
+                "date": fixture_date(d),
+                "day": {
+                    "maxtemp_c": base_max + d as f32,
+                    "mintemp_c": base_max - 8.0 + d as f32,
+                    "avgtemp_c": base_max - 4.0 + d as f32,
+                    "avghumidity": 80,
+                    "maxwind_kph": 18.0,
+                    "totalprecip_mm": 2.4,
+                    "daily_chance_of_rain": 65,
+                    "condition": {
+                        "text": "Overcast",
+                        "icon": "//cdn.weatherapi.com/weather/64x64/day/122.png",
+                    },
+                },
+                "hour": (0..24)
+                    .map(|h| {
+                        serde_json::json!({
+                            "time_epoch": fixture_hour(d, h),
+                            "temp_c": base_max - 4.0,
+                            "humidity": 80,
+                            "wind_kph": 15.0,
+                            "gust_kph": if h == 14 { 31.0 } else { 20.0 },
+                            "precip_mm": if h == 14 { 2.0 } else { 0.0 },
+                            "chance_of_rain": if h == 14 { 70 } else { 10 },
+                            "condition": {
+                                "text": if h == 14 { "Patchy rain nearby" } else { "Overcast" },
+                                "icon": "//cdn.weatherapi.com/weather/64x64/day/122.png",
+                            },
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "location": { "name": "Jakarta", "region": "Jakarta Raya", "lat": -6.21, "lon": 106.85 },
+        "forecast": { "forecastday": forecastday },
+    })
+    .to_string()
+}
+
+/// Unix timestamp jam `hour` WIB pada hari fixture ke-`day`
+pub fn fixture_hour(day: usize, hour: usize) -> i64 {
+    let start = FIXTURE_START.with(Cell::get).and_hms_opt(0, 0, 0).unwrap();
+    let local = start + chrono::Duration::hours((day * 24 + hour) as i64);
+    local.and_utc().timestamp() - 7 * 3600
+}
+
+fn fixture_date(day: usize) -> String {
+    let start = FIXTURE_START.with(Cell::get);
+    (start + chrono::Duration::days(day as i64)).format("%Y-%m-%d").to_string()
+}
@@ -0,0 +1,486 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Certificate, Client, ClientBuilder, Identity, NoProxy, Proxy, RequestBuilder};
+use log::{info, warn};
+use crate::utils::Config;
+use super::error::ProviderError;
+use super::fixtures::{FixtureMode, FixtureStore};
+use super::quota::QuotaTracker;
+use super::rate_limit::{RateLimit, RateLimiter};
+use super::retry::RetryPolicy;
+
+/// Jalur keluar bersama untuk semua request ke provider.
+/// Satu `reqwest::Client` (dan connection pool-nya) dipakai semua provider.
+#[derive(Debug, Clone)]
+pub struct UpstreamClient {
+    client: Client,
+    fixtures: FixtureStore,
+    /// Kebijakan retry per provider id, provider lain pakai `RetryPolicy::default()`
+    retry_policies: HashMap<String, RetryPolicy>,
+    /// Rate limiter per provider id, di-share semua clone (provider tanpa entry tidak dibatasi)
+    rate_limiters: HashMap<String, Arc<RateLimiter>>,
+    /// Pemakaian per API key; tanpa tracker request tidak dihitung
+    quota: Option<Arc<QuotaTracker>>,
+}
+
+impl UpstreamClient {
+    pub fn new(client: Client, fixtures: FixtureStore) -> Self {
+        Self {
+            client,
+            fixtures,
+            retry_policies: HashMap::new(),
+            rate_limiters: HashMap::new(),
+            quota: None,
+        }
+    }
+
+    pub fn with_retry_policy(mut self, provider_id: &str, policy: RetryPolicy) -> Self {
+        self.retry_policies.insert(provider_id.to_string(), policy);
+        self
+    }
+
+    pub fn with_rate_limit(mut self, provider_id: &str, limit: RateLimit) -> Self {
+        if !limit.is_unlimited() {
+            self.rate_limiters
+                .insert(provider_id.to_string(), Arc::new(RateLimiter::new(provider_id, limit)));
+        }
+        self
+    }
+
+    pub fn with_quota(mut self, quota: Arc<QuotaTracker>) -> Self {
+        self.quota = Some(quota);
+        self
+    }
+
+    pub fn quota(&self) -> Option<&Arc<QuotaTracker>> {
+        self.quota.as_ref()
+    }
+
+    pub fn retry_policy(&self, provider_id: &str) -> RetryPolicy {
+        self.retry_policies.get(provider_id).copied().unwrap_or_default()
+    }
+
+    /// Bangun client dari pengaturan `HTTP_*` di Config (pool, timeout, proxy, TLS)
+    pub fn from_config(config: &Config) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let builder = Client::builder()
+            .user_agent(config.http_user_agent.as_str())
+            .timeout(Duration::from_secs(config.http_timeout_secs))
+            .connect_timeout(Duration::from_secs(config.http_connect_timeout_secs))
+            .pool_idle_timeout(Duration::from_secs(config.http_pool_idle_timeout_secs))
+            .pool_max_idle_per_host(config.http_pool_max_idle_per_host);
+
+        let builder = apply_proxies(builder, config)?;
+        let builder = apply_tls(builder, config)?;
+
+        info!(
+            "[Upstream] HTTP client ready: pool {} idle/host, timeout {}s, connect {}s, proxy: {}, extra CA: {}, client cert: {}",
+            config.http_pool_max_idle_per_host,
+            config.http_timeout_secs,
+            config.http_connect_timeout_secs,
+            config.http_proxy.is_some() || config.https_proxy.is_some(),
+            config.http_ca_bundles.len(),
+            config.http_client_cert.is_some()
+        );
+
+        let upstream = config
+            .retry_policies
+            .iter()
+            .fold(Self::new(builder.build()?, FixtureStore::from_config(config)), |upstream, (id, policy)| {
+                upstream.with_retry_policy(id, *policy)
+            });
+        let upstream = config
+            .rate_limits
+            .iter()
+            .fold(upstream, |upstream, (id, limit)| upstream.with_rate_limit(id, *limit));
+
+        let quota_path = config.quota_state_file.as_ref().map(PathBuf::from);
+        let quota = QuotaTracker::new(quota_path, config.quota_limits.clone());
+        Ok(upstream.with_quota(Arc::new(quota)))
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Kirim GET dan ambil body sebagai text, dengan retry sesuai kebijakan provider.
+    /// Setiap percobaan (termasuk retry) memakai satu token rate limiter provider
+    /// dan dihitung ke kuota `api_key`. `location` dipakai sebagai key fixture.
+    /// 429 untuk request ber-key langsung dikembalikan supaya key pool bisa rotasi.
+    pub async fn get_text(
+        &self,
+        provider_id: &str,
+        api_key: Option<&str>,
+        location: &str,
+        request: RequestBuilder,
+    ) -> Result<String, ProviderError> {
+        if self.fixtures.mode() == FixtureMode::Replay {
+            return self.fixtures.load(provider_id, location);
+        }
+
+        let policy = self.retry_policy(provider_id);
+        let mut attempt = 1;
+        let body = loop {
+            // GET tanpa body selalu bisa di-clone
+            let attempt_request = request
+                .try_clone()
+                .ok_or_else(|| ProviderError::Connection("request cannot be cloned for retry".to_string()))?;
+
+            if let Some(quota) = &self.quota {
+                quota.check(provider_id, api_key)?;
+            }
+            if let Some(limiter) = self.rate_limiters.get(provider_id) {
+                limiter.acquire().await?;
+            }
+
+            let result = send_once(attempt_request).await;
+            if let Some(quota) = &self.quota {
+                quota.record(provider_id, api_key, result.is_ok());
+            }
+
+            match result {
+                Ok(body) => break body,
+                // Key yang sama akan tetap kena 429; biarkan pool mendinginkannya
+                Err(e @ ProviderError::QuotaExhausted { .. }) if api_key.is_some() => return Err(e),
+                Err(e) => match policy.next_delay(attempt, &e) {
+                    Some(delay) => {
+                        warn!(
+                            "[Upstream] {} request for {} failed (attempt {}/{}): {}, retrying in {:?}",
+                            provider_id, location, attempt, policy.max_attempts, e, delay
+                        );
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    None => return Err(e),
+                },
+            }
+        };
+
+        if attempt > 1 {
+            info!("[Upstream] {} request for {} succeeded after {} attempts", provider_id, location, attempt);
+        }
+        if self.fixtures.mode() == FixtureMode::Record {
+            self.fixtures.save(provider_id, location, &body);
+        }
+        Ok(body)
+    }
+}
+
+async fn send_once(request: RequestBuilder) -> Result<String, ProviderError> {
+    let response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(ProviderError::from_status(status, parse_retry_after(response.headers())));
+    }
+    Ok(response.text().await?)
+}
+
+/// `Retry-After` bisa berupa detik atau HTTP-date
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().ok()
+}
+
+/// `HTTPS_PROXY_URL` khusus https, `HTTP_PROXY_URL` untuk sisanya.
+/// Host di `HTTP_NO_PROXY` selalu dihubungi langsung.
+fn apply_proxies(
+    mut builder: ClientBuilder,
+    config: &Config,
+) -> Result<ClientBuilder, Box<dyn Error + Send + Sync>> {
+    let no_proxy = || config.http_no_proxy.as_deref().and_then(NoProxy::from_string);
+
+    // reqwest memakai proxy pertama yang cocok, jadi https harus didaftarkan duluan
+    if let Some(url) = &config.https_proxy {
+        let proxy = Proxy::https(url.as_str())
+            .map_err(|e| format!("Invalid HTTPS_PROXY_URL '{}': {}", url, e))?;
+        builder = builder.proxy(proxy.no_proxy(no_proxy()));
+    }
+    if let Some(url) = &config.http_proxy {
+        let proxy = Proxy::all(url.as_str())
+            .map_err(|e| format!("Invalid HTTP_PROXY_URL '{}': {}", url, e))?;
+        builder = builder.proxy(proxy.no_proxy(no_proxy()));
+    }
+    Ok(builder)
+}
+
+/// Tambah root CA dari file PEM (boleh berisi banyak sertifikat) dan client certificate (mTLS)
+fn apply_tls(
+    mut builder: ClientBuilder,
+    config: &Config,
+) -> Result<ClientBuilder, Box<dyn Error + Send + Sync>> {
+    for path in &config.http_ca_bundles {
+        let pem = fs::read(path).map_err(|e| format!("Failed to read CA bundle {}: {}", path, e))?;
+        let certs = Certificate::from_pem_bundle(&pem)
+            .map_err(|e| format!("Invalid CA bundle {}: {}", path, e))?;
+        info!("[Upstream] Trusting {} extra CA certificate(s) from {}", certs.len(), path);
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    match (&config.http_client_cert, &config.http_client_key) {
+        (Some(cert_path), Some(key_path)) => {
+            let cert = fs::read(cert_path)
+                .map_err(|e| format!("Failed to read client certificate {}: {}", cert_path, e))?;
+            let key = fs::read(key_path)
+                .map_err(|e| format!("Failed to read client key {}: {}", key_path, e))?;
+            let identity = Identity::from_pkcs8_pem(&cert, &key)
+                .map_err(|e| format!("Invalid client certificate {}: {}", cert_path, e))?;
+            builder = builder.identity(identity);
+        }
+        (None, None) => {}
+        _ => return Err("HTTP_CLIENT_CERT and HTTP_CLIENT_KEY must be set together".into()),
+    }
+    Ok(builder)
+}
+
+impl Default for UpstreamClient {
+    fn default() -> Self {
+        Self::new(Client::new(), FixtureStore::disabled())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ensemble::{OPEN_METEO, WEATHER_API};
+    use crate::services::providers::test_support::{open_meteo_fixture, spawn_stub_server, StubRoute};
+    use crate::services::providers::fetch_open_meteo;
+    use crate::services::providers::quota::{QuotaLimits, UsageCounter};
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_from_config_rejects_invalid_proxy() {
+        let mut config = Config::from_env();
+        config.http_proxy = Some("not a url".to_string());
+        assert!(UpstreamClient::from_config(&config).is_err());
+
+        config.http_proxy = Some("http://127.0.0.1:3128".to_string());
+        assert!(UpstreamClient::from_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_from_config_reports_missing_tls_files() {
+        let mut config = Config::from_env();
+        config.http_ca_bundles = vec!["/nonexistent/corp-root.pem".to_string()];
+        let err = UpstreamClient::from_config(&config).unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/corp-root.pem"));
+
+        config.http_ca_bundles.clear();
+        config.http_client_cert = Some("/nonexistent/client.pem".to_string());
+        config.http_client_key = None;
+        assert!(UpstreamClient::from_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_provider_requests_go_through_proxy() {
+        // Stub berperan sebagai proxy: request datang dengan absolute URI
+        let proxy = spawn_stub_server(vec![StubRoute::new(
+            "http://provider.invalid/v1/forecast",
+            200,
+            &open_meteo_fixture(30.0),
+        )]);
+        let mut config = Config::from_env();
+        config.http_proxy = Some(proxy);
+        config.https_proxy = None;
+        config.http_no_proxy = None;
+        config.quota_state_file = None;
+        let upstream = UpstreamClient::from_config(&config).unwrap();
+
+        let forecast = fetch_open_meteo(&upstream, "http://provider.invalid", -6.2, 106.8)
+            .await
+            .unwrap();
+        assert_eq!(forecast.len(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_no_proxy_hosts_bypass_proxy() {
+        let base_url = spawn_stub_server(vec![StubRoute::new(
+            "/v1/forecast",
+            200,
+            &open_meteo_fixture(30.0),
+        )]);
+        let mut config = Config::from_env();
+        // Proxy ini tidak bisa dihubungi, jadi request hanya sukses kalau di-bypass
+        config.http_proxy = Some("http://127.0.0.1:9".to_string());
+        config.http_no_proxy = Some("127.0.0.1".to_string());
+        config.quota_state_file = None;
+        let upstream = UpstreamClient::from_config(&config).unwrap();
+
+        let result = upstream
+            .get_text(OPEN_METEO, None, "test", upstream.client().get(format!("{}/v1/forecast", base_url)))
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_configured_timeout_applies_to_provider_requests() {
+        // Server menerima koneksi tapi tidak pernah menjawab
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let _open: Vec<_> = listener.incoming().collect();
+        });
+
+        let mut config = Config::from_env();
+        config.http_timeout_secs = 1;
+        config.http_proxy = None;
+        config.https_proxy = None;
+        config.quota_state_file = None;
+        config.retry_policies.insert(OPEN_METEO.to_string(), RetryPolicy { max_attempts: 1, ..fast_retry() });
+        let upstream = UpstreamClient::from_config(&config).unwrap();
+
+        let started = std::time::Instant::now();
+        let err = fetch_open_meteo(&upstream, &base_url, -6.2, 106.8).await.unwrap_err();
+        assert_eq!(err, ProviderError::Timeout);
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
+
+    fn fast_retry() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_secs(2),
+            jitter: 0.0,
+        }
+    }
+
+    async fn get_forecast(upstream: &UpstreamClient, base_url: &str) -> Result<String, ProviderError> {
+        let request = upstream.client().get(format!("{}/v1/forecast", base_url));
+        upstream.get_text(OPEN_METEO, None, "test", request).await
+    }
+
+    #[tokio::test]
+    async fn test_transient_errors_are_retried() {
+        let base_url = spawn_stub_server(vec![
+            StubRoute::new("/v1/forecast", 503, "{}").times(1),
+            StubRoute::new("/v1/forecast", 429, "{}").with_header("Retry-After", "0").times(1),
+            StubRoute::new("/v1/forecast", 200, "ok"),
+        ]);
+        let upstream = UpstreamClient::default().with_retry_policy(OPEN_METEO, fast_retry());
+
+        assert_eq!(get_forecast(&upstream, &base_url).await.unwrap(), "ok");
+    }
+
+    #[tokio::test]
+    async fn test_keyed_quota_errors_are_not_retried() {
+        let base_url = spawn_stub_server(vec![
+            StubRoute::new("/v1/forecast.json", 429, "{}").times(1),
+            StubRoute::new("/v1/forecast.json", 200, "ok"),
+        ]);
+        let upstream = UpstreamClient::default().with_retry_policy(WEATHER_API, fast_retry());
+
+        let request = upstream.client().get(format!("{}/v1/forecast.json", base_url));
+        let err = upstream.get_text(WEATHER_API, Some("test-key"), "test", request).await.unwrap_err();
+        assert!(matches!(err, ProviderError::QuotaExhausted { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let base_url = spawn_stub_server(vec![
+            StubRoute::new("/v1/forecast", 401, "{}").times(1),
+            StubRoute::new("/v1/forecast", 200, "ok"),
+        ]);
+        let upstream = UpstreamClient::default().with_retry_policy(OPEN_METEO, fast_retry());
+
+        let err = get_forecast(&upstream, &base_url).await.unwrap_err();
+        assert_eq!(err, ProviderError::AuthFailure { status: StatusCode::UNAUTHORIZED });
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let base_url = spawn_stub_server(vec![
+            StubRoute::new("/v1/forecast", 502, "{}").times(3),
+            StubRoute::new("/v1/forecast", 200, "ok"),
+        ]);
+        let upstream = UpstreamClient::default().with_retry_policy(OPEN_METEO, fast_retry());
+
+        let err = get_forecast(&upstream, &base_url).await.unwrap_err();
+        assert!(matches!(err, ProviderError::HttpStatus { status: StatusCode::BAD_GATEWAY, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_is_shared_and_counts_retries() {
+        let base_url = spawn_stub_server(vec![
+            StubRoute::new("/v1/forecast", 200, "ok").times(1),
+            StubRoute::new("/v1/forecast", 503, "{}").times(1),
+            StubRoute::new("/v1/forecast", 200, "ok"),
+        ]);
+        let upstream = UpstreamClient::default()
+            .with_retry_policy(OPEN_METEO, fast_retry())
+            .with_rate_limit(
+                OPEN_METEO,
+                RateLimit {
+                    per_minute: Some(2),
+                    ..RateLimit::default()
+                },
+            );
+        let other_path = upstream.clone();
+
+        assert_eq!(get_forecast(&upstream, &base_url).await.unwrap(), "ok");
+        // 503 memakai token terakhir, retry-nya ditolak limiter tanpa request ke server
+        let err = get_forecast(&other_path, &base_url).await.unwrap_err();
+        assert!(matches!(err, ProviderError::RateLimited { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_quota_hard_limit_stops_requests() {
+        let base_url = spawn_stub_server(vec![
+            StubRoute::new("/v1/forecast", 500, "{}").times(1),
+            StubRoute::new("/v1/forecast", 200, "ok"),
+        ]);
+        let limits = QuotaLimits {
+            daily_hard: Some(2),
+            ..QuotaLimits::default()
+        };
+        let quota = Arc::new(QuotaTracker::new(None, HashMap::from([(OPEN_METEO.to_string(), limits)])));
+        let upstream = UpstreamClient::default()
+            .with_retry_policy(OPEN_METEO, fast_retry())
+            .with_quota(quota.clone());
+
+        // Percobaan gagal tetap dihitung: 500 lalu 200 = 2 request
+        assert_eq!(get_forecast(&upstream, &base_url).await.unwrap(), "ok");
+        let report = quota.report();
+        assert_eq!(report[0].usage.today, UsageCounter { success: 1, failure: 1 });
+        assert_eq!(report[0].status, "hard_limit");
+
+        let err = get_forecast(&upstream, &base_url).await.unwrap_err();
+        assert!(matches!(err, ProviderError::QuotaLimitReached { window: "daily", .. }));
+    }
+
+    #[tokio::test]
+    async fn test_transport_errors_do_not_leak_url_keys() {
+        let upstream = UpstreamClient::default().with_retry_policy(
+            WEATHER_API,
+            RetryPolicy { max_attempts: 1, ..fast_retry() },
+        );
+        // Port 9 (discard) tidak listen, jadi request gagal di level koneksi
+        let request = upstream
+            .client()
+            .get("http://127.0.0.1:9/v1/forecast.json?key=leaky-key-12345&q=Jakarta");
+        let err = upstream.get_text(WEATHER_API, Some("leaky-key-12345"), "Jakarta", request).await.unwrap_err();
+
+        assert!(matches!(err, ProviderError::Connection(_)));
+        assert!(!err.to_string().contains("leaky-key-12345"));
+        assert!(!format!("{:?}", err).contains("leaky-key-12345"));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(7)));
+
+        // HTTP-date yang sudah lewat tidak dipakai, jatuh ke backoff biasa
+        headers.insert(RETRY_AFTER, "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap());
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+}
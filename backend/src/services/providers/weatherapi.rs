@@ -1,8 +1,13 @@
 use serde::{Deserialize, Serialize};
-use crate::models::DailyForecast;
-use std::error::Error;
-use reqwest::Client;
-use std::time::Duration;
+use async_trait::async_trait;
+use crate::models::{City, Condition, CurrentWeather, DailyForecast, HourlyForecast, Intensity, WeatherDetails};
+use crate::models::ensemble::WEATHER_API;
+use super::{
+    local_hour, local_time, ApiKeyPool, CurrentResult, HourlyResult, KeyStatus, LocationQuery, ProviderCapabilities,
+    ProviderError, ProviderResult, UpstreamClient, WeatherProvider,
+};
+use chrono::FixedOffset;
+use std::sync::Arc;
 use log::info;
 
 // Macro untuk struktur API response
@@ -26,103 +31,289 @@ api_struct!(WeatherAPIDay {
     mintemp_c: f32,
     avgtemp_c: f32,
     avghumidity: u32,
+    maxwind_kph: Option<f32>,
+    totalprecip_mm: Option<f32>,
+    daily_chance_of_rain: Option<u32>,
     condition: WeatherAPICondition,
 });
 
+api_struct!(WeatherAPIHour {
+    time_epoch: Option<i64>,
+    temp_c: Option<f32>,
+    humidity: Option<f32>,
+    wind_kph: Option<f32>,
+    gust_kph: Option<f32>,
+    precip_mm: Option<f32>,
+    chance_of_rain: Option<f32>,
+    condition: Option<WeatherAPICondition>,
+});
+
 api_struct!(WeatherAPIForecastDay {
     date: String,
     day: WeatherAPIDay,
+    hour: Option<Vec<WeatherAPIHour>>,
 });
 
 api_struct!(WeatherAPIForecast {
     forecastday: Vec<WeatherAPIForecastDay>,
 });
 
+api_struct!(WeatherAPILocation {
+    name: String,
+    region: String,
+    lat: f64,
+    lon: f64,
+});
+
 api_struct!(WeatherAPIResponse {
+    location: Option<WeatherAPILocation>,
     forecast: WeatherAPIForecast,
 });
 
+api_struct!(WeatherAPICurrent {
+    last_updated_epoch: Option<i64>,
+    temp_c: Option<f32>,
+    feelslike_c: Option<f32>,
+    humidity: Option<f32>,
+    wind_kph: Option<f32>,
+    gust_kph: Option<f32>,
+    precip_mm: Option<f32>,
+    condition: Option<WeatherAPICondition>,
+});
+
+api_struct!(WeatherAPICurrentResponse {
+    location: Option<WeatherAPILocation>,
+    current: WeatherAPICurrent,
+});
+
+/// Base URL produksi, bisa dioverride lewat `WEATHERAPI_BASE_URL`
+pub const DEFAULT_BASE_URL: &str = "https://api.weatherapi.com";
+
+/// Jarak maksimal lokasi hasil resolve WeatherAPI dari koordinat kota, bisa dioverride
+/// lewat `WEATHERAPI_MAX_DISTANCE_KM`
+pub const DEFAULT_MAX_DISTANCE_KM: f64 = 25.0;
+
 pub async fn fetch_weatherapi(
-    city: &str,
+    upstream: &UpstreamClient,
+    base_url: &str,
+    lat: f64,
+    lon: f64,
+    api_key: &str,
+    max_distance_km: f64,
+) -> ProviderResult {
+    info!("Fetching weather from WeatherAPI provider for lat={}, lon={}", lat, lon);
+
+    let data = request_forecast(upstream, base_url, lat, lon, api_key, 7, max_distance_km).await?;
+    info!("Successfully fetched WeatherAPI data for {:.4},{:.4}", lat, lon);
+
+    let forecasts = normalize_weatherapi(&data)?;
+    Ok(forecasts)
+}
+
+/// Array `hour` dari 3 hari forecast (mulai 00:00 hari ini), cukup untuk 48 jam ke depan
+pub async fn fetch_weatherapi_hourly(
+    upstream: &UpstreamClient,
+    base_url: &str,
+    lat: f64,
+    lon: f64,
     api_key: &str,
-) -> Result<Vec<DailyForecast>, Box<dyn Error + Send + Sync>> {
-    info!("Fetching weather from WeatherAPI provider for city={}", city);
+    max_distance_km: f64,
+    utc_offset: FixedOffset,
+) -> HourlyResult {
+    info!("Fetching hourly weather from WeatherAPI provider for lat={}, lon={}", lat, lon);
 
-    // Increased timeout untuk koneksi yang lebih stabil (15s total, 8s connect)
-    let client = Client::builder()
-        .timeout(Duration::from_secs(15))        // Total request timeout
-        .connect_timeout(Duration::from_secs(8)) // Connection timeout
-        .pool_idle_timeout(Duration::from_secs(90)) // Keep connections alive
-        .pool_max_idle_per_host(2)               // Connection pooling
-        .build()?;
+    let data = request_forecast(upstream, base_url, lat, lon, api_key, 3, max_distance_km).await?;
+    normalize_weatherapi_hourly(&data, utc_offset)
+}
 
+/// Request `forecast.json` untuk `days` hari dan pastikan lokasinya cocok dengan koordinat
+async fn request_forecast(
+    upstream: &UpstreamClient,
+    base_url: &str,
+    lat: f64,
+    lon: f64,
+    api_key: &str,
+    days: u32,
+    max_distance_km: f64,
+) -> Result<WeatherAPIResponse, ProviderError> {
+    let location = format!("{:.4},{:.4}", lat, lon);
     let url = format!(
-        "https://api.weatherapi.com/v1/forecast.json?key={}&q={}&days=7&aqi=no",
-        api_key, city
+        "{}/v1/forecast.json?key={}&q={}&days={}&aqi=no",
+        base_url.trim_end_matches('/'), api_key, location, days
     );
 
-    // Retry logic: coba 3x dengan exponential backoff
-    let mut last_error = None;
-    for attempt in 1..=3 {
-        match client.get(&url).send().await {
-            Ok(response) => {
-                // Check HTTP status
-                if !response.status().is_success() {
-                    let status = response.status();
-                    last_error = Some(format!("HTTP error: {}", status));
-                    if attempt < 3 {
-                        let delay = Duration::from_millis(500 * attempt as u64); // Exponential backoff
-                        tokio::time::sleep(delay).await;
-                    }
-                    continue;
-                }
-
-                match response.json::<WeatherAPIResponse>().await {
-                    Ok(data) => {
-                        info!("Successfully fetched WeatherAPI data for {} (attempt {})", city, attempt);
-                        let forecasts = normalize_weatherapi(&data)?;
-                        return Ok(forecasts);
-                    }
-                    Err(e) => {
-                        last_error = Some(format!("JSON parse error: {}", e));
-                        if attempt < 3 {
-                            let delay = Duration::from_millis(500 * attempt as u64);
-                            tokio::time::sleep(delay).await;
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                let error_msg = format!("{}", e);
-                last_error = Some(error_msg.clone());
-                
-                // Log error type untuk debugging
-                if error_msg.contains("deadline has elapsed") || error_msg.contains("timed out") {
-                    info!("WeatherAPI timeout for {} (attempt {}), retrying...", city, attempt);
-                } else if error_msg.contains("connect") {
-                    info!("WeatherAPI connection error for {} (attempt {}), retrying...", city, attempt);
-                } else {
-                    info!("WeatherAPI error for {} (attempt {}): {}", city, attempt, error_msg);
-                }
-                
-                if attempt < 3 {
-                    let delay = Duration::from_millis(500 * attempt as u64); // Exponential backoff: 500ms, 1s, skip
-                    tokio::time::sleep(delay).await;
-                }
-            }
+    // Retry ditangani UpstreamClient sesuai RetryPolicy provider. Fixture dibedakan per
+    // jumlah hari supaya forecast harian dan per jam tidak saling menimpa.
+    let fixture_key = if days == 7 { location.clone() } else { format!("{}/{}d", location, days) };
+    let request = upstream.client().get(&url);
+    let body = upstream.get_text(WEATHER_API, Some(api_key), &fixture_key, request).await?;
+    let data: WeatherAPIResponse = serde_json::from_str(&body)?;
+
+    check_resolved_location(data.location.as_ref(), lat, lon, max_distance_km)?;
+    Ok(data)
+}
+
+/// Observasi terakhir dari `current.json`, dengan cek lokasi yang sama seperti forecast
+pub async fn fetch_weatherapi_current(
+    upstream: &UpstreamClient,
+    base_url: &str,
+    lat: f64,
+    lon: f64,
+    api_key: &str,
+    max_distance_km: f64,
+    utc_offset: FixedOffset,
+) -> CurrentResult {
+    info!("Fetching current weather from WeatherAPI provider for lat={}, lon={}", lat, lon);
+
+    let location = format!("{:.4},{:.4}", lat, lon);
+    let url = format!(
+        "{}/v1/current.json?key={}&q={}&aqi=no",
+        base_url.trim_end_matches('/'), api_key, location
+    );
+
+    let fixture_key = format!("{}/current", location);
+    let request = upstream.client().get(&url);
+    let body = upstream.get_text(WEATHER_API, Some(api_key), &fixture_key, request).await?;
+    let data: WeatherAPICurrentResponse = serde_json::from_str(&body)?;
+
+    check_resolved_location(data.location.as_ref(), lat, lon, max_distance_km)?;
+    normalize_weatherapi_current(&data, utc_offset)
+}
+
+/// Tolak data kalau WeatherAPI me-resolve koordinat ke tempat yang terlalu jauh
+fn check_resolved_location(
+    resolved: Option<&WeatherAPILocation>,
+    lat: f64,
+    lon: f64,
+    max_distance_km: f64,
+) -> Result<(), ProviderError> {
+    let resolved = resolved.ok_or_else(|| ProviderError::SchemaMismatch("missing field `location`".to_string()))?;
+
+    let distance_km = haversine_km(lat, lon, resolved.lat, resolved.lon);
+    if distance_km > max_distance_km {
+        return Err(ProviderError::LocationMismatch {
+            resolved: format!("{}, {}", resolved.name, resolved.region),
+            distance_km,
+        });
+    }
+    Ok(())
+}
+
+/// Jarak lingkaran besar antara dua koordinat (km)
+fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    const EARTH_RADIUS_KM: f64 = 6371.0;
+    let (dlat, dlon) = ((lat2 - lat1).to_radians(), (lon2 - lon1).to_radians());
+    let a = (dlat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// WeatherAPI.com: forecast harian 7 hari, butuh API key, query pakai koordinat
+pub struct WeatherApiProvider {
+    upstream: Arc<UpstreamClient>,
+    base_url: String,
+    keys: ApiKeyPool,
+    max_distance_km: f64,
+}
+
+impl WeatherApiProvider {
+    pub fn new(upstream: Arc<UpstreamClient>, base_url: String, keys: ApiKeyPool) -> Self {
+        Self {
+            upstream,
+            base_url,
+            keys,
+            max_distance_km: DEFAULT_MAX_DISTANCE_KM,
+        }
+    }
+
+    pub fn with_max_distance_km(mut self, max_distance_km: f64) -> Self {
+        self.max_distance_km = max_distance_km;
+        self
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for WeatherApiProvider {
+    fn id(&self) -> &'static str {
+        WEATHER_API
+    }
+
+    fn name(&self) -> &'static str {
+        "WeatherAPI"
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            description: "Daily forecast queried by coordinates, resolved location checked against the city",
+            forecast_days: 7,
+            requires_api_key: true,
+            location_query: LocationQuery::Coordinates,
         }
     }
 
-    Err(last_error.unwrap_or_else(|| "Unknown error after 3 attempts".to_string()).into())
+    async fn fetch_forecast(&self, city: &City) -> ProviderResult {
+        let (upstream, base_url, max_distance_km) = (&self.upstream, &self.base_url, self.max_distance_km);
+        self.keys
+            .run(|key| async move {
+                fetch_weatherapi(upstream, base_url, city.latitude, city.longitude, &key, max_distance_km).await
+            })
+            .await
+    }
+
+    async fn fetch_hourly(&self, city: &City) -> HourlyResult {
+        let (upstream, base_url, max_distance_km) = (&self.upstream, &self.base_url, self.max_distance_km);
+        self.keys
+            .run(|key| async move {
+                fetch_weatherapi_hourly(
+                    upstream,
+                    base_url,
+                    city.latitude,
+                    city.longitude,
+                    &key,
+                    max_distance_km,
+                    city.utc_offset(),
+                )
+                .await
+            })
+            .await
+    }
+
+    async fn fetch_current(&self, city: &City) -> CurrentResult {
+        let (upstream, base_url, max_distance_km) = (&self.upstream, &self.base_url, self.max_distance_km);
+        self.keys
+            .run(|key| async move {
+                fetch_weatherapi_current(
+                    upstream,
+                    base_url,
+                    city.latitude,
+                    city.longitude,
+                    &key,
+                    max_distance_km,
+                    city.utc_offset(),
+                )
+                .await
+            })
+            .await
+    }
+
+    fn api_keys(&self) -> Vec<KeyStatus> {
+        self.keys.statuses()
+    }
 }
 
-fn normalize_weatherapi(data: &WeatherAPIResponse) -> Result<Vec<DailyForecast>, Box<dyn Error + Send + Sync>> {
+fn normalize_weatherapi(data: &WeatherAPIResponse) -> ProviderResult {
     // Functional approach: map iterator tanpa mutable variable
-    let forecasts = data.forecast.forecastday
+    let forecasts: Vec<DailyForecast> = data.forecast.forecastday
         .iter()
         .map(|forecast_day| {
-            let condition = normalize_weatherapi_condition(&forecast_day.day.condition.text);
-            let icon = map_weatherapi_icon(&forecast_day.day.condition.icon);
+            let condition = map_weatherapi_condition(&forecast_day.day.condition.text);
+            // Ringkasan harian tidak punya gust, ambil maksimum dari data per jam
+            let wind_gust = forecast_day
+                .hour
+                .iter()
+                .flatten()
+                .filter_map(|h| h.gust_kph)
+                .reduce(f32::max);
 
             DailyForecast {
                 date: forecast_day.date.clone(),
@@ -130,53 +321,100 @@ fn normalize_weatherapi(data: &WeatherAPIResponse) -> Result<Vec<DailyForecast>,
                 temp_min: forecast_day.day.mintemp_c,
                 temp_avg: forecast_day.day.avgtemp_c,
                 condition,
-                humidity: forecast_day.day.avghumidity,
-                wind_speed: 0.0,
-                icon,
+                humidity: Some(forecast_day.day.avghumidity),
+                wind_speed: forecast_day.day.maxwind_kph,
+                wind_gust,
+                precipitation_mm: forecast_day.day.totalprecip_mm,
+                precipitation_probability: forecast_day.day.daily_chance_of_rain,
+                icon: condition.icon().to_string(),
             }
         })
         .collect();
 
+    if forecasts.is_empty() {
+        return Err(ProviderError::EmptyData);
+    }
     Ok(forecasts)
 }
 
-fn normalize_weatherapi_condition(condition: &str) -> String {
-    // Normalisasi ke kategori standar untuk konsistensi ensemble
-    match condition {
-        c if c.contains("Thunder") || c.contains("Storm") => "Thunderstorm".to_string(),
-        c if c.contains("Snow") || c.contains("Blizzard") => "Snow".to_string(),
-        c if c.contains("Rain") || c.contains("Drizzle") || c.contains("Shower") => "Rainy".to_string(),
-        c if c.contains("Fog") || c.contains("Mist") => "Foggy".to_string(),
-        c if c.contains("Cloud") || c.contains("Overcast") => "Cloudy".to_string(),
-        c if c.contains("Clear") || c.contains("Sunny") => "Clear".to_string(),
-        c if c.contains("Partly") => "Cloudy".to_string(),  // Partly cloudy → Cloudy
-        _ => "Clear".to_string(),  // Default ke Clear
+/// Jam tanpa waktu atau suhu dilewati
+fn normalize_weatherapi_hourly(data: &WeatherAPIResponse, offset: FixedOffset) -> HourlyResult {
+    let forecasts: Vec<HourlyForecast> = data.forecast.forecastday
+        .iter()
+        .flat_map(|day| day.hour.iter().flatten())
+        .filter_map(|hour| {
+            let time = local_hour(hour.time_epoch?, offset)?;
+            let condition = hour
+                .condition
+                .as_ref()
+                .map(|c| map_weatherapi_condition(&c.text))
+                .unwrap_or(Condition::Unknown);
+            let details = WeatherDetails {
+                humidity: hour.humidity,
+                wind_speed: hour.wind_kph,
+                wind_gust: hour.gust_kph,
+                precipitation_mm: hour.precip_mm,
+                precipitation_probability: hour.chance_of_rain,
+            };
+            Some(HourlyForecast::new(time, hour.temp_c?, condition).with_details(details))
+        })
+        .collect();
+
+    if forecasts.is_empty() {
+        return Err(ProviderError::EmptyData);
     }
+    Ok(forecasts)
 }
 
-fn map_weatherapi_icon(icon_url: &str) -> String {
-    // WeatherAPI return URL kayak https://cdn.weatherapi.com/weather/128x128/day/113.png
-    // Extract kode icon dan map ke icon kita
-    if icon_url.contains("day") || icon_url.contains("night") {
-        if icon_url.contains("113") || icon_url.contains("122") {
-            "sunny".to_string()
-        } else if icon_url.contains("116") || icon_url.contains("119") || icon_url.contains("122") {
-            "cloudy".to_string()
-        } else if icon_url.contains("143") || icon_url.contains("248") || icon_url.contains("260") {
-            "fog".to_string()
-        } else if icon_url.contains("176") || icon_url.contains("179") || icon_url.contains("182") || icon_url.contains("185") || icon_url.contains("200") || icon_url.contains("227") || icon_url.contains("230") || icon_url.contains("233") || icon_url.contains("266") || icon_url.contains("281") || icon_url.contains("284") || icon_url.contains("293") || icon_url.contains("296") || icon_url.contains("299") || icon_url.contains("302") || icon_url.contains("305") || icon_url.contains("308") || icon_url.contains("311") || icon_url.contains("314") || icon_url.contains("317") || icon_url.contains("320") || icon_url.contains("323") || icon_url.contains("326") || icon_url.contains("329") || icon_url.contains("332") || icon_url.contains("335") || icon_url.contains("338") || icon_url.contains("350") || icon_url.contains("353") || icon_url.contains("356") || icon_url.contains("359") || icon_url.contains("362") || icon_url.contains("365") || icon_url.contains("368") || icon_url.contains("371") || icon_url.contains("374") || icon_url.contains("377") || icon_url.contains("380") || icon_url.contains("386") || icon_url.contains("389") || icon_url.contains("392") || icon_url.contains("395") {
-            if icon_url.contains("snow") || icon_url.contains("sleet") || icon_url.contains("179") || icon_url.contains("182") || icon_url.contains("185") || icon_url.contains("227") || icon_url.contains("230") || icon_url.contains("233") || icon_url.contains("320") || icon_url.contains("323") || icon_url.contains("326") || icon_url.contains("329") || icon_url.contains("332") || icon_url.contains("335") || icon_url.contains("368") || icon_url.contains("371") || icon_url.contains("374") || icon_url.contains("377") {
-                "snowy".to_string()
-            } else {
-                "rainy".to_string()
-            }
-        } else if icon_url.contains("386") || icon_url.contains("389") || icon_url.contains("392") || icon_url.contains("395") {
-            "stormy".to_string()
-        } else {
-            "cloudy".to_string()
-        }
+fn normalize_weatherapi_current(data: &WeatherAPICurrentResponse, offset: FixedOffset) -> CurrentResult {
+    let current = &data.current;
+    let (Some(temperature), Some(updated)) = (current.temp_c, current.last_updated_epoch) else {
+        return Err(ProviderError::EmptyData);
+    };
+    let observed_at = local_time(updated, offset).ok_or_else(|| {
+        ProviderError::SchemaMismatch(format!("current.last_updated_epoch {} is out of range", updated))
+    })?;
+    let condition = current
+        .condition
+        .as_ref()
+        .map(|c| map_weatherapi_condition(&c.text))
+        .unwrap_or(Condition::Unknown);
+    let details = WeatherDetails {
+        humidity: current.humidity,
+        wind_speed: current.wind_kph,
+        wind_gust: current.gust_kph,
+        precipitation_mm: current.precip_mm,
+        precipitation_probability: None,
+    };
+
+    Ok(CurrentWeather::new(observed_at, temperature, condition)
+        .with_feels_like(current.feelslike_c)
+        .with_details(details))
+}
+
+fn map_weatherapi_condition(text: &str) -> Condition {
+    use Intensity::*;
+    // Teks WeatherAPI campur huruf besar/kecil ("Patchy light rain", "Moderate or heavy rain shower")
+    let text = text.to_lowercase();
+    let intensity = if text.contains("heavy") || text.contains("torrential") {
+        Heavy
+    } else if text.contains("light") || text.contains("patchy") {
+        Light
     } else {
-        "cloudy".to_string()
+        Moderate
+    };
+
+    match text.as_str() {
+        c if c.contains("thunder") || c.contains("storm") => Condition::Thunderstorm,
+        c if c.contains("snow") || c.contains("blizzard") || c.contains("sleet") || c.contains("ice pellets") => Condition::Snow(intensity),
+        c if c.contains("shower") => Condition::RainShowers(intensity),
+        c if c.contains("drizzle") => Condition::Drizzle(intensity),
+        c if c.contains("rain") => Condition::Rain(intensity),
+        c if c.contains("fog") || c.contains("mist") => Condition::Foggy,
+        c if c.contains("partly") => Condition::PartlyCloudy,
+        c if c.contains("cloud") || c.contains("overcast") => Condition::Cloudy,
+        c if c.contains("clear") || c.contains("sunny") => Condition::Clear,
+        _ => Condition::Unknown,
     }
 }
 
@@ -186,8 +424,108 @@ mod tests {
 
     #[test]
     fn test_weatherapi_condition_mapping() {
-        assert_eq!(normalize_weatherapi_condition("Sunny"), "Clear");
-        assert_eq!(normalize_weatherapi_condition("Rainy"), "Rainy");
-        assert_eq!(normalize_weatherapi_condition("Snowy"), "Snowy");
+        assert_eq!(map_weatherapi_condition("Sunny"), Condition::Clear);
+        assert_eq!(map_weatherapi_condition("Rainy"), Condition::Rain(Intensity::Moderate));
+        assert_eq!(map_weatherapi_condition("Snowy"), Condition::Snow(Intensity::Moderate));
+        assert_eq!(map_weatherapi_condition("Patchy light drizzle"), Condition::Drizzle(Intensity::Light));
+        assert_eq!(map_weatherapi_condition("Partly cloudy"), Condition::PartlyCloudy);
+        assert_eq!(
+            map_weatherapi_condition("Moderate or heavy rain shower"),
+            Condition::RainShowers(Intensity::Heavy)
+        );
+        assert_eq!(map_weatherapi_condition("Volcanic ash"), Condition::Unknown);
+    }
+
+    #[test]
+    fn test_weather_details_are_parsed() {
+        let data: WeatherAPIResponse =
+            serde_json::from_str(&super::super::test_support::weatherapi_fixture(30.0)).unwrap();
+        let day = &normalize_weatherapi(&data).unwrap()[0];
+
+        assert_eq!(day.humidity, Some(80));
+        assert_eq!(day.wind_speed, Some(18.0));
+        assert_eq!(day.wind_gust, Some(31.0));
+        assert_eq!(day.precipitation_mm, Some(2.4));
+        assert_eq!(day.precipitation_probability, Some(65));
+    }
+
+    #[test]
+    fn test_hourly_values_are_parsed() {
+        let data: WeatherAPIResponse =
+            serde_json::from_str(&super::super::test_support::weatherapi_fixture(30.0)).unwrap();
+        let hours = normalize_weatherapi_hourly(&data, FixedOffset::east_opt(7 * 3600).unwrap()).unwrap();
+
+        assert_eq!(hours.len(), 7 * 24);
+        assert_eq!(hours[0].time, "2025-01-06T00:00");
+        assert_eq!(hours[13].condition, Condition::Cloudy);
+        assert_eq!(hours[14].condition, Condition::Rain(Intensity::Light));
+        assert_eq!(hours[14].details.wind_gust, Some(31.0));
+        assert_eq!(hours[14].details.precipitation_probability, Some(70.0));
+    }
+
+    #[test]
+    fn test_current_conditions_are_parsed() {
+        let data: WeatherAPICurrentResponse =
+            serde_json::from_str(&super::super::test_support::weatherapi_current_fixture(30.0)).unwrap();
+        let current = normalize_weatherapi_current(&data, FixedOffset::east_opt(7 * 3600).unwrap()).unwrap();
+
+        assert_eq!(current.observed_at, "2025-01-06T13:15:00+07:00");
+        assert_eq!(current.temperature, 30.0);
+        assert_eq!(current.feels_like, Some(34.5));
+        assert_eq!(current.condition, Condition::PartlyCloudy);
+        assert_eq!(current.details.wind_gust, Some(22.0));
+    }
+
+    #[test]
+    fn test_haversine_km() {
+        // Jakarta - Bandung sekitar 120 km
+        let distance = haversine_km(-6.2088, 106.8456, -6.9175, 107.6191);
+        assert!((distance - 116.0).abs() < 5.0, "got {}", distance);
+        assert_eq!(haversine_km(-6.2, 106.8, -6.2, 106.8), 0.0);
+    }
+
+    #[test]
+    fn test_far_resolved_location_is_rejected() {
+        let data: WeatherAPIResponse =
+            serde_json::from_str(&super::super::test_support::weatherapi_fixture(30.0)).unwrap();
+
+        // Fixture me-resolve ke Jakarta: cocok untuk Jakarta, terlalu jauh untuk Bandar Lampung
+        let resolved = data.location.as_ref();
+        assert!(check_resolved_location(resolved, -6.2088, 106.8456, DEFAULT_MAX_DISTANCE_KM).is_ok());
+        match check_resolved_location(resolved, -5.4292, 105.2610, DEFAULT_MAX_DISTANCE_KM) {
+            Err(ProviderError::LocationMismatch { resolved, distance_km }) => {
+                assert_eq!(resolved, "Jakarta, Jakarta Raya");
+                assert!(distance_km > 150.0);
+            }
+            other => panic!("Expected LocationMismatch, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_revoked_key_rotates_to_next_key() {
+        use super::super::test_support::{spawn_stub_server, weatherapi_fixture, StubRoute};
+        use super::super::{KeyPoolConfig, KeyState};
+
+        let base_url = spawn_stub_server(vec![
+            StubRoute::new("/v1/forecast.json?key=revoked-key", 401, r#"{"error":{"code":2008}}"#),
+            StubRoute::new("/v1/forecast.json?key=spare-key", 200, &weatherapi_fixture(32.0)),
+        ]);
+        let keys = ApiKeyPool::new(
+            WEATHER_API,
+            &["revoked-key".to_string(), "spare-key".to_string()],
+            KeyPoolConfig::default(),
+        );
+        let provider = WeatherApiProvider::new(Arc::new(UpstreamClient::default()), base_url, keys);
+        let city = City {
+            id: 1,
+            name: "Jakarta",
+            province: "DKI Jakarta",
+            latitude: -6.2088,
+            longitude: 106.8456,
+        };
+
+        assert_eq!(provider.fetch_forecast(&city).await.unwrap().len(), 7);
+        let states: Vec<KeyState> = provider.api_keys().iter().map(|k| k.state).collect();
+        assert_eq!(states, vec![KeyState::Quarantined, KeyState::Active]);
     }
 }
@@ -1,4 +1,6 @@
+use crate::errors::ApiError;
 use crate::models::{WeatherForecast, City};
+use crate::services::providers::ProviderRegistry;
 use log::info;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
@@ -6,27 +8,23 @@ use tokio::sync::Semaphore;
 use super::parallel_forecast::{fetch_forecast_parallel, fetch_forecast_with_rate_limit};
 
 pub struct WeatherService {
-    pub openweather_key: String,
-    pub weatherapi_key: String,
+    pub registry: Arc<ProviderRegistry>,
 }
 
 impl WeatherService {
-    pub fn new(openweather_key: String, weatherapi_key: String) -> Self {
-        Self {
-            openweather_key,
-            weatherapi_key,
-        }
+    pub fn new(registry: Arc<ProviderRegistry>) -> Self {
+        Self { registry }
     }
 
     /// Pemrosesan paralel: 7 task sekaligus untuk 7 hari
     pub async fn get_forecast_parallel(
         &self,
         city: &City,
-    ) -> Result<WeatherForecast, String> {
+    ) -> Result<WeatherForecast, ApiError> {
         info!("Getting parallel weather forecast for city={}, lat={}, lon={}", 
               city.name, city.latitude, city.longitude);
 
-        fetch_forecast_parallel(city, &self.openweather_key, &self.weatherapi_key).await
+        fetch_forecast_parallel(city, self.registry.clone()).await
     }
 
     /// Rate limiting pakai semaphore: batasi concurrent API calls
@@ -34,25 +32,49 @@ impl WeatherService {
         &self,
         city: &City,
         semaphore: Arc<Semaphore>,
-    ) -> Result<WeatherForecast, String> {
+    ) -> Result<WeatherForecast, ApiError> {
         info!("Getting rate-limited weather forecast for city={}, lat={}, lon={}", 
               city.name, city.latitude, city.longitude);
 
-        fetch_forecast_with_rate_limit(city, semaphore, &self.openweather_key, &self.weatherapi_key).await
+        fetch_forecast_with_rate_limit(city, semaphore, self.registry.clone()).await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::providers::test_support::{open_meteo_fixture, spawn_stub_server, StubRoute};
+    use crate::services::providers::{OpenMeteoProvider, UpstreamClient};
+
+    fn create_test_city() -> City {
+        City {
+            id: 1,
+            name: "Jakarta",
+            province: "DKI Jakarta",
+            latitude: -6.2088,
+            longitude: 106.8456,
+        }
+    }
 
     #[tokio::test]
-    async fn test_weather_service_creation() {
-        let service = WeatherService::new(
-            "test-key".to_string(),
-            "test-key".to_string(),
-        );
-        assert!(!service.openweather_key.is_empty());
-        assert!(!service.weatherapi_key.is_empty());
+    async fn test_weather_service_forecast() {
+        let city = create_test_city();
+        // Forecast paralel dimulai dari hari ini (waktu lokal kota), jadi tanggal fixture digeser
+        let today = chrono::Utc::now().with_timezone(&city.utc_offset()).date_naive();
+        let mut fixture: serde_json::Value = serde_json::from_str(&open_meteo_fixture(30.0)).unwrap();
+        fixture["daily"]["time"] = (0..7)
+            .map(|d| (today + chrono::Duration::days(d)).format("%Y-%m-%d").to_string())
+            .collect();
+        let base_url = spawn_stub_server(vec![StubRoute::new("/v1/forecast", 200, &fixture.to_string())]);
+
+        let provider = OpenMeteoProvider::new(Arc::new(UpstreamClient::default()), base_url);
+        let service = WeatherService::new(Arc::new(ProviderRegistry::new(vec![Arc::new(provider)])));
+        let forecast = service.get_forecast_parallel(&city).await.unwrap();
+
+        assert_eq!(forecast.city, "Jakarta");
+        assert_eq!(forecast.forecast.len(), 7);
+        assert_eq!(forecast.forecast[0].date, today.format("%Y-%m-%d").to_string());
+        assert_eq!(forecast.forecast[0].temp_max, 30.0);
+        assert_eq!(forecast.forecast[6].temp_max, 36.0);
     }
 }
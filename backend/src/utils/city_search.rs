@@ -4,14 +4,17 @@ use crate::cities::CITIES;
 #[allow(dead_code)]
 pub fn find_city_by_name(name: &str) -> Option<&'static City> {
     let search_term = name.to_lowercase();
-    
-    CITIES.iter().find(|city| {
-        let city_name = city.name.to_lowercase();
-        
-        city_name == search_term 
-            || city_name.contains(&search_term)
-            || search_term.chars().all(|c| city_name.contains(c))
-    })
+    if search_term.is_empty() {
+        return None;
+    }
+
+    // Nama persis menang dulu, baru substring, baru cocok per huruf
+    let matches = |pred: &dyn Fn(&str) -> bool| {
+        CITIES.iter().find(|city| pred(&city.name.to_lowercase()))
+    };
+    matches(&|city_name| city_name == search_term)
+        .or_else(|| matches(&|city_name| city_name.contains(&search_term)))
+        .or_else(|| matches(&|city_name| search_term.chars().all(|c| city_name.contains(c))))
 }
 
 #[cfg(test)]
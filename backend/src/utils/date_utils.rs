@@ -3,6 +3,7 @@ use chrono::{Local, Datelike, Duration};
 /// CurrentWeek: hari ini + 6 hari | NextWeek: satu hari spesifik minggu depan
 #[derive(Debug, Clone)]
 pub enum ForecastPeriod {
+    #[allow(dead_code)]
     CurrentWeek,
     NextWeek { 
         base_day: u32,  // 0=Senin, 6=Minggu
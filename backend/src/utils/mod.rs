@@ -1,7 +1,13 @@
+use std::collections::HashMap;
 use std::env;
+use std::time::Duration;
+use crate::models::ensemble::{OPEN_METEO, OPEN_WEATHER, WEATHER_API};
+use crate::models::VoteGranularity;
+use crate::services::providers::{open_meteo, openweather, weatherapi, BreakerConfig, FixtureMode, KeyPoolConfig, KeyRotation, QuotaLimits, RateLimit, RetryPolicy};
 
 pub mod city_search;
 pub mod date_utils;
+pub mod redact;
 
 
 
@@ -10,11 +16,49 @@ pub struct Config {
     pub server_port: u16,
     #[allow(dead_code)]
     pub api_base_url: String,
-    #[allow(dead_code)]
+    /// Key untuk endpoint admin (header `X-API-Key`); kalau kosong endpoint admin ditolak
     pub api_key: Option<String>,
     pub cors_origins: Vec<String>,
-    pub openweather_key: String,
-    pub weatherapi_key: String,
+    /// API key per provider; lebih dari satu key dirotasi (lihat `providers::key_pool`)
+    pub openweather_keys: Vec<String>,
+    pub weatherapi_keys: Vec<String>,
+    pub key_pool: KeyPoolConfig,
+    /// Urutan provider = urutan fallback di daily processor
+    pub enabled_providers: Vec<String>,
+    /// Base URL provider, bisa diarahkan ke mock server lokal untuk staging/CI
+    pub open_meteo_base_url: String,
+    pub openweather_base_url: String,
+    pub weatherapi_base_url: String,
+    /// Data WeatherAPI ditolak kalau lokasi hasil resolve-nya lebih jauh dari ini
+    pub weatherapi_max_distance_km: f64,
+    /// Record/replay response provider ke disk (lihat `providers::fixtures`)
+    pub fixture_mode: FixtureMode,
+    pub fixture_dir: String,
+    pub fixture_date: Option<String>,
+    /// Pengaturan HTTP client bersama untuk semua provider (lihat `providers::upstream`)
+    pub http_pool_max_idle_per_host: usize,
+    pub http_pool_idle_timeout_secs: u64,
+    pub http_connect_timeout_secs: u64,
+    pub http_timeout_secs: u64,
+    pub http_user_agent: String,
+    pub http_proxy: Option<String>,
+    pub https_proxy: Option<String>,
+    pub http_no_proxy: Option<String>,
+    /// File PEM berisi root CA tambahan (misal CA internal perusahaan)
+    pub http_ca_bundles: Vec<String>,
+    pub http_client_cert: Option<String>,
+    pub http_client_key: Option<String>,
+    /// Kebijakan retry per provider id (`RETRY_*`, bisa dioverride `<PROVIDER>_RETRY_*`)
+    pub retry_policies: HashMap<String, RetryPolicy>,
+    pub breaker: BreakerConfig,
+    /// Budget request per provider id (`<PROVIDER>_RATE_LIMIT_PER_SEC/_PER_MIN/_PER_DAY`)
+    pub rate_limits: HashMap<String, RateLimit>,
+    /// File JSON penyimpan counter kuota; `None` = hanya di memori
+    pub quota_state_file: Option<String>,
+    /// Soft/hard limit per API key (`<PROVIDER>_QUOTA_DAILY_SOFT`, `_DAILY_HARD`, `_MONTHLY_*`)
+    pub quota_limits: HashMap<String, QuotaLimits>,
+    /// Kekasaran kondisi saat voting ensemble (`exact`, `type`, `category`)
+    pub condition_vote_granularity: VoteGranularity,
 }
 
 impl Config {
@@ -27,7 +71,7 @@ impl Config {
         let api_base_url = env::var("API_BASE_URL")
             .unwrap_or_else(|_| "https://api.example.com".to_string());
 
-        let api_key = env::var("API_KEY").ok();
+        let api_key = env_non_empty("API_KEY");
 
         let cors_origins = env::var("CORS_ORIGINS")
             .unwrap_or_else(|_| "http://localhost:5173,http://localhost:3000".to_string())
@@ -35,24 +79,287 @@ impl Config {
             .map(|s| s.trim().to_string())
             .collect();
 
-        let openweather_key = env::var("OPENWEATHER_API_KEY")
-            .unwrap_or_else(|_| "your-key-here".to_string());
+        let openweather_keys = env_key_list(&["OPENWEATHER_API_KEYS", "OPENWEATHER_API_KEY"]);
+        let weatherapi_keys = env_key_list(&["WEATHERAPI_KEYS", "WEATHERAPI_KEY"]);
+
+        let key_pool_defaults = KeyPoolConfig::default();
+        let key_pool = KeyPoolConfig {
+            rotation: env::var("API_KEY_ROTATION")
+                .ok()
+                .and_then(|r| {
+                    let rotation = KeyRotation::parse(&r);
+                    if rotation.is_none() {
+                        log::warn!("Invalid API_KEY_ROTATION '{}', using failover", r);
+                    }
+                    rotation
+                })
+                .unwrap_or(key_pool_defaults.rotation),
+            quarantine: Duration::from_secs(env_parse(
+                "API_KEY_QUARANTINE_SECS",
+                key_pool_defaults.quarantine.as_secs(),
+            )),
+            cool_down: Duration::from_secs(env_parse(
+                "API_KEY_COOL_DOWN_SECS",
+                key_pool_defaults.cool_down.as_secs(),
+            )),
+        };
+
+        let enabled_providers = env::var("WEATHER_PROVIDERS")
+            .unwrap_or_else(|_| "open_meteo,open_weather,weather_api".to_string())
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let open_meteo_base_url = env::var("OPEN_METEO_BASE_URL")
+            .unwrap_or_else(|_| open_meteo::DEFAULT_BASE_URL.to_string());
+
+        let openweather_base_url = env::var("OPENWEATHER_BASE_URL")
+            .unwrap_or_else(|_| openweather::DEFAULT_BASE_URL.to_string());
+
+        let weatherapi_base_url = env::var("WEATHERAPI_BASE_URL")
+            .unwrap_or_else(|_| weatherapi::DEFAULT_BASE_URL.to_string());
+
+        let weatherapi_max_distance_km =
+            env_parse("WEATHERAPI_MAX_DISTANCE_KM", weatherapi::DEFAULT_MAX_DISTANCE_KM);
+
+        let fixture_mode = env::var("PROVIDER_FIXTURE_MODE")
+            .ok()
+            .and_then(|m| {
+                let mode = FixtureMode::parse(&m);
+                if mode.is_none() {
+                    log::warn!("Invalid PROVIDER_FIXTURE_MODE '{}', fixtures disabled", m);
+                }
+                mode
+            })
+            .unwrap_or(FixtureMode::Off);
+
+        let fixture_dir = env::var("PROVIDER_FIXTURE_DIR")
+            .unwrap_or_else(|_| "fixtures/providers".to_string());
+
+        let fixture_date = env::var("PROVIDER_FIXTURE_DATE").ok();
+
+        let http_pool_max_idle_per_host = env_parse("HTTP_POOL_MAX_IDLE_PER_HOST", 8);
+        let http_pool_idle_timeout_secs = env_parse("HTTP_POOL_IDLE_TIMEOUT_SECS", 90);
+        let http_connect_timeout_secs = env_parse("HTTP_CONNECT_TIMEOUT_SECS", 8);
+        let http_timeout_secs = env_parse("HTTP_TIMEOUT_SECS", 15);
+
+        let http_user_agent = env::var("HTTP_USER_AGENT")
+            .unwrap_or_else(|_| format!("IndoPrint-Weather/{}", env!("CARGO_PKG_VERSION")));
+
+        let http_proxy = env_non_empty("HTTP_PROXY_URL");
+        let https_proxy = env_non_empty("HTTPS_PROXY_URL");
+        let http_no_proxy = env_non_empty("HTTP_NO_PROXY");
+
+        let http_ca_bundles = env::var("HTTP_CA_BUNDLE")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let http_client_cert = env_non_empty("HTTP_CLIENT_CERT");
+        let http_client_key = env_non_empty("HTTP_CLIENT_KEY");
+
+        let default_retry = retry_policy_from_env("RETRY", RetryPolicy::default());
+        let retry_policies = [
+            (OPEN_METEO, "OPEN_METEO_RETRY"),
+            (OPEN_WEATHER, "OPENWEATHER_RETRY"),
+            (WEATHER_API, "WEATHERAPI_RETRY"),
+        ]
+        .iter()
+        .map(|(id, prefix)| (id.to_string(), retry_policy_from_env(prefix, default_retry)))
+        .collect();
+
+        let breaker_defaults = BreakerConfig::default();
+        let breaker = BreakerConfig {
+            failure_threshold: env_parse("CIRCUIT_BREAKER_FAILURE_THRESHOLD", breaker_defaults.failure_threshold)
+                .max(1),
+            cool_down: Duration::from_secs(env_parse(
+                "CIRCUIT_BREAKER_COOL_DOWN_SECS",
+                breaker_defaults.cool_down.as_secs(),
+            )),
+        };
+
+        // Default mengikuti plan gratis: Open-Meteo 600/menit & 10k/hari,
+        // OpenWeatherMap 60/menit, WeatherAPI ~100k/bulan
+        let rate_limit_max_wait = Duration::from_millis(env_parse("RATE_LIMIT_MAX_WAIT_MS", 2000));
+        let rate_limits = [
+            (OPEN_METEO, "OPEN_METEO_RATE_LIMIT", (None, Some(600), Some(10_000))),
+            (OPEN_WEATHER, "OPENWEATHER_RATE_LIMIT", (None, Some(60), None)),
+            (WEATHER_API, "WEATHERAPI_RATE_LIMIT", (None, None, Some(3_000))),
+        ]
+        .iter()
+        .map(|(id, prefix, defaults)| {
+            (id.to_string(), rate_limit_from_env(prefix, *defaults, rate_limit_max_wait))
+        })
+        .collect();
 
-        let weatherapi_key = env::var("WEATHERAPI_KEY")
-            .unwrap_or_else(|_| "your-key-here".to_string());
+        let quota_state_file = env::var("QUOTA_STATE_FILE")
+            .map(|v| Some(v).filter(|v| !v.trim().is_empty()))
+            .unwrap_or_else(|_| Some("state/quota_usage.json".to_string()));
+
+        // WeatherAPI berbayar kalau lewat kuota bulanan, jadi default-nya dibatasi
+        let quota_limits = [
+            (OPEN_METEO, "OPEN_METEO_QUOTA", QuotaLimits::default()),
+            (OPEN_WEATHER, "OPENWEATHER_QUOTA", QuotaLimits::default()),
+            (
+                WEATHER_API,
+                "WEATHERAPI_QUOTA",
+                QuotaLimits {
+                    monthly_soft: Some(80_000),
+                    monthly_hard: Some(100_000),
+                    ..QuotaLimits::default()
+                },
+            ),
+        ]
+        .iter()
+        .map(|(id, prefix, defaults)| (id.to_string(), quota_limits_from_env(prefix, *defaults)))
+        .collect();
+
+        let condition_vote_granularity = env::var("CONDITION_VOTE_GRANULARITY")
+            .ok()
+            .and_then(|g| {
+                let granularity = VoteGranularity::parse(&g);
+                if granularity.is_none() {
+                    log::warn!("Invalid CONDITION_VOTE_GRANULARITY '{}', using category", g);
+                }
+                granularity
+            })
+            .unwrap_or_default();
 
         Self {
             server_port,
             api_base_url,
             api_key,
             cors_origins,
-            openweather_key,
-            weatherapi_key,
+            openweather_keys,
+            weatherapi_keys,
+            key_pool,
+            enabled_providers,
+            open_meteo_base_url,
+            openweather_base_url,
+            weatherapi_base_url,
+            weatherapi_max_distance_km,
+            fixture_mode,
+            fixture_dir,
+            fixture_date,
+            http_pool_max_idle_per_host,
+            http_pool_idle_timeout_secs,
+            http_connect_timeout_secs,
+            http_timeout_secs,
+            http_user_agent,
+            http_proxy,
+            https_proxy,
+            http_no_proxy,
+            http_ca_bundles,
+            http_client_cert,
+            http_client_key,
+            retry_policies,
+            breaker,
+            rate_limits,
+            quota_state_file,
+            quota_limits,
+            condition_vote_granularity,
         }
     }
 }
 
+/// Daftar key dipisah koma dari env var pertama yang terisi
+fn env_key_list(vars: &[&str]) -> Vec<String> {
+    vars.iter()
+        .find_map(|var| env_non_empty(var))
+        .unwrap_or_default()
+        .split(',')
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty())
+        .collect()
+}
+
+fn env_non_empty(key: &str) -> Option<String> {
+    env::var(key).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// Baca `<prefix>_MAX_ATTEMPTS`, `_BASE_DELAY_MS`, `_MAX_DELAY_MS`, `_JITTER`
+fn retry_policy_from_env(prefix: &str, fallback: RetryPolicy) -> RetryPolicy {
+    let millis = |d: Duration| d.as_millis() as u64;
+    RetryPolicy {
+        max_attempts: env_parse(&format!("{}_MAX_ATTEMPTS", prefix), fallback.max_attempts).max(1),
+        base_delay: Duration::from_millis(env_parse(
+            &format!("{}_BASE_DELAY_MS", prefix),
+            millis(fallback.base_delay),
+        )),
+        max_delay: Duration::from_millis(env_parse(
+            &format!("{}_MAX_DELAY_MS", prefix),
+            millis(fallback.max_delay),
+        )),
+        jitter: env_parse(&format!("{}_JITTER", prefix), fallback.jitter).clamp(0.0, 1.0),
+    }
+}
+
+/// Baca `<prefix>_PER_SEC`, `_PER_MIN`, `_PER_DAY`; nilai 0 mematikan batas window tersebut
+fn rate_limit_from_env(
+    prefix: &str,
+    (per_second, per_minute, per_day): (Option<u32>, Option<u32>, Option<u32>),
+    max_wait: Duration,
+) -> RateLimit {
+    let budget = |suffix: &str, default: Option<u32>| {
+        Some(env_parse(&format!("{}_{}", prefix, suffix), default.unwrap_or(0))).filter(|n| *n > 0)
+    };
+    RateLimit {
+        per_second: budget("PER_SEC", per_second),
+        per_minute: budget("PER_MIN", per_minute),
+        per_day: budget("PER_DAY", per_day),
+        max_wait,
+    }
+}
+
+/// Baca `<prefix>_DAILY_SOFT`, `_DAILY_HARD`, `_MONTHLY_SOFT`, `_MONTHLY_HARD`; 0 = tanpa batas
+fn quota_limits_from_env(prefix: &str, defaults: QuotaLimits) -> QuotaLimits {
+    let limit = |suffix: &str, default: Option<u64>| {
+        Some(env_parse(&format!("{}_{}", prefix, suffix), default.unwrap_or(0))).filter(|n| *n > 0)
+    };
+    QuotaLimits {
+        daily_soft: limit("DAILY_SOFT", defaults.daily_soft),
+        daily_hard: limit("DAILY_HARD", defaults.daily_hard),
+        monthly_soft: limit("MONTHLY_SOFT", defaults.monthly_soft),
+        monthly_hard: limit("MONTHLY_HARD", defaults.monthly_hard),
+    }
+}
+
+/// Parse env var numerik, pakai default kalau kosong atau tidak valid
+fn env_parse<T: std::str::FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(default)
+}
+
+/// Load file konfigurasi (format dotenv) dari `CONFIG_FILE` kalau di-set.
+/// Variabel yang sudah ada di environment tidak ditimpa.
+pub fn load_config_file() {
+    if let Ok(path) = env::var("CONFIG_FILE") {
+        match dotenvy::from_path(&path) {
+            Ok(()) => log::info!("Loaded configuration file: {}", path),
+            Err(e) => log::warn!("Failed to load configuration file {}: {}", path, e),
+        }
+    }
+}
+
+/// Semua baris log lewat `redact::redact`, jadi API key tidak pernah tertulis ke log
 pub fn init_logger() {
+    use std::io::Write;
+
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+        .format(|buf, record| {
+            writeln!(
+                buf,
+                "[{} {} {}] {}",
+                buf.timestamp(),
+                buf.default_styled_level(record.level()),
+                record.target(),
+                redact::redact(&record.args().to_string())
+            )
+        })
         .init();
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
-use crate::test_utils::{TestClient, assertions::*};
-use backend::models::EnsembleForecast;
+use crate::test_utils::{TestClient, TEST_API_KEY, assertions::*};
+use backend::models::{Condition, EnsembleForecast};
 use rocket::http::Status;
 use serde_json::Value;
 use std::time::Instant;
@@ -12,7 +12,7 @@ use chrono::NaiveDate;
 #[test]
 fn test_current_week_valid_city_jakarta() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta");
+    let response = client.get("/api/weather/ensemble?city=Jakarta");
     
     // Verify 200 OK
     assert_ok(response.status());
@@ -48,7 +48,7 @@ fn test_current_week_valid_city_jakarta() {
 #[test]
 fn test_current_week_valid_city_bandung() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Bandung");
+    let response = client.get("/api/weather/ensemble?city=Bandung");
     
     assert_ok(response.status());
     
@@ -70,7 +70,7 @@ fn test_current_week_valid_city_bandung() {
 #[test]
 fn test_current_week_valid_city_surabaya() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Surabaya");
+    let response = client.get("/api/weather/ensemble?city=Surabaya");
     
     assert_ok(response.status());
     
@@ -85,7 +85,7 @@ fn test_current_week_valid_city_surabaya() {
 #[test]
 fn test_next_week_valid_city_and_day_monday() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta&period=next_week&day=0");
+    let response = client.get("/api/weather/ensemble?city=Jakarta&period=next_week&day=0");
     
     // Verify 200 OK
     assert_ok(response.status());
@@ -111,7 +111,7 @@ fn test_next_week_valid_city_and_day_monday() {
 #[test]
 fn test_next_week_valid_city_and_day_friday() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Bandung&period=next_week&day=4");
+    let response = client.get("/api/weather/ensemble?city=Bandung&period=next_week&day=4");
     
     assert_ok(response.status());
     
@@ -129,9 +129,9 @@ fn test_next_week_valid_city_and_day_friday() {
 #[test]
 fn test_case_insensitive_city_lowercase() {
     let client = TestClient::new();
-    let mut response_lower = client.get("/api/weather/ensemble?city=jakarta");
-    let mut response_upper = client.get("/api/weather/ensemble?city=JAKARTA");
-    let mut response_mixed = client.get("/api/weather/ensemble?city=JaKaRtA");
+    let response_lower = client.get("/api/weather/ensemble?city=jakarta");
+    let response_upper = client.get("/api/weather/ensemble?city=JAKARTA");
+    let response_mixed = client.get("/api/weather/ensemble?city=JaKaRtA");
     
     assert_ok(response_lower.status());
     assert_ok(response_upper.status());
@@ -153,7 +153,7 @@ fn test_all_valid_days_0_through_6() {
     
     for day in 0..=6 {
         let uri = format!("/api/weather/ensemble?city=Jakarta&period=next_week&day={}", day);
-        let mut response = client.get(&uri);
+        let response = client.get(&uri);
         
         assert_ok(response.status());
         
@@ -174,8 +174,8 @@ fn test_default_period_is_current_week() {
     let client = TestClient::new();
     
     // Without period parameter should default to current_week
-    let mut response_default = client.get("/api/weather/ensemble?city=Jakarta");
-    let mut response_explicit = client.get("/api/weather/ensemble?city=Jakarta&period=current_week");
+    let response_default = client.get("/api/weather/ensemble?city=Jakarta");
+    let response_explicit = client.get("/api/weather/ensemble?city=Jakarta&period=current_week");
     
     assert_ok(response_default.status());
     assert_ok(response_explicit.status());
@@ -195,7 +195,7 @@ fn test_default_period_is_current_week() {
 #[test]
 fn test_city_not_found_404() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=InvalidCityName");
+    let response = client.get("/api/weather/ensemble?city=InvalidCityName");
     
     assert_not_found(response.status());
     
@@ -210,7 +210,7 @@ fn test_city_not_found_404() {
 #[test]
 fn test_empty_city_400() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=");
+    let response = client.get("/api/weather/ensemble?city=");
     
     assert_bad_request(response.status());
     
@@ -227,7 +227,7 @@ fn test_empty_city_400() {
 #[test]
 fn test_missing_city_parameter_400() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble");
+    let response = client.get("/api/weather/ensemble");
     
     assert_bad_request(response.status());
     
@@ -244,7 +244,7 @@ fn test_missing_city_parameter_400() {
 #[test]
 fn test_next_week_missing_day_parameter_400() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta&period=next_week");
+    let response = client.get("/api/weather/ensemble?city=Jakarta&period=next_week");
     
     assert_bad_request(response.status());
     
@@ -259,7 +259,7 @@ fn test_next_week_missing_day_parameter_400() {
 #[test]
 fn test_invalid_day_greater_than_6() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta&period=next_week&day=10");
+    let response = client.get("/api/weather/ensemble?city=Jakarta&period=next_week&day=10");
     
     assert_bad_request(response.status());
     
@@ -274,7 +274,7 @@ fn test_invalid_day_greater_than_6() {
 #[test]
 fn test_invalid_day_value_7() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta&period=next_week&day=7");
+    let response = client.get("/api/weather/ensemble?city=Jakarta&period=next_week&day=7");
     
     assert_bad_request(response.status());
     
@@ -287,7 +287,7 @@ fn test_invalid_day_value_7() {
 #[test]
 fn test_invalid_period_parameter() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta&period=invalid_period");
+    let response = client.get("/api/weather/ensemble?city=Jakarta&period=invalid_period");
     
     // Should either return 400 or default to current_week gracefully
     let status = response.status();
@@ -315,7 +315,7 @@ fn test_special_characters_in_city() {
     ];
     
     for uri in test_cases {
-        let mut response = client.get(uri);
+        let response = client.get(uri);
         
         // Should return 400 (invalid input) or 404 (not found), never 200
         let status = response.status();
@@ -332,7 +332,7 @@ fn test_city_name_too_long() {
     let client = TestClient::new();
     let long_city = "A".repeat(51);
     let uri = format!("/api/weather/ensemble?city={}", long_city);
-    let mut response = client.get(&uri);
+    let response = client.get(&uri);
     
     assert_bad_request(response.status());
     
@@ -351,7 +351,7 @@ fn test_city_name_too_long() {
 #[test]
 fn test_response_has_all_required_fields() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta");
+    let response = client.get("/api/weather/ensemble?city=Jakarta");
     
     assert_ok(response.status());
     
@@ -391,7 +391,7 @@ fn test_response_has_all_required_fields() {
 #[test]
 fn test_no_null_values_in_required_fields() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta");
+    let response = client.get("/api/weather/ensemble?city=Jakarta");
     
     assert_ok(response.status());
     
@@ -415,7 +415,7 @@ fn test_no_null_values_in_required_fields() {
 #[test]
 fn test_proper_data_types() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta");
+    let response = client.get("/api/weather/ensemble?city=Jakarta");
     
     assert_ok(response.status());
     
@@ -453,7 +453,7 @@ fn test_proper_data_types() {
 #[test]
 fn test_dates_in_iso_format() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta");
+    let response = client.get("/api/weather/ensemble?city=Jakarta");
     
     assert_ok(response.status());
     
@@ -467,7 +467,7 @@ fn test_dates_in_iso_format() {
 #[test]
 fn test_temperatures_are_valid_numbers() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta");
+    let response = client.get("/api/weather/ensemble?city=Jakarta");
     
     assert_ok(response.status());
     
@@ -491,7 +491,7 @@ fn test_temperatures_are_valid_numbers() {
 #[test]
 fn test_confidence_values_are_valid() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta");
+    let response = client.get("/api/weather/ensemble?city=Jakarta");
     
     assert_ok(response.status());
     
@@ -509,7 +509,7 @@ fn test_confidence_values_are_valid() {
 #[test]
 fn test_per_source_data_validation() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta");
+    let response = client.get("/api/weather/ensemble?city=Jakarta");
     
     assert_ok(response.status());
     
@@ -519,25 +519,12 @@ fn test_per_source_data_validation() {
         let per_source = &day.per_source;
         
         // At least one provider should have data
-        let has_data = per_source.open_meteo.is_some() 
-            || per_source.open_weather.is_some() 
-            || per_source.weather_api.is_some();
-        assert!(has_data, "Day {} should have data from at least one provider", i);
+        assert!(per_source.provider_count() > 0, "Day {} should have data from at least one provider", i);
         
         // Validate provider data if present
-        if let Some(ref om) = per_source.open_meteo {
-            assert!(om.temp_max >= om.temp_min, "Day {} Open-Meteo temp_max >= temp_min", i);
-            assert!(!om.condition.is_empty(), "Day {} Open-Meteo condition not empty", i);
-        }
-        
-        if let Some(ref ow) = per_source.open_weather {
-            assert!(ow.temp_max >= ow.temp_min, "Day {} OpenWeather temp_max >= temp_min", i);
-            assert!(!ow.condition.is_empty(), "Day {} OpenWeather condition not empty", i);
-        }
-        
-        if let Some(ref wa) = per_source.weather_api {
-            assert!(wa.temp_max >= wa.temp_min, "Day {} WeatherAPI temp_max >= temp_min", i);
-            assert!(!wa.condition.is_empty(), "Day {} WeatherAPI condition not empty", i);
+        for (provider_id, pf) in per_source.iter() {
+            assert!(pf.temp_max >= pf.temp_min, "Day {} {} temp_max >= temp_min", i, provider_id);
+            assert_ne!(pf.condition, Condition::Unknown, "Day {} {} condition should be known", i, provider_id);
         }
     }
 }
@@ -545,7 +532,7 @@ fn test_per_source_data_validation() {
 #[test]
 fn test_final_forecast_validation() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta");
+    let response = client.get("/api/weather/ensemble?city=Jakarta");
     
     assert_ok(response.status());
     
@@ -568,9 +555,9 @@ fn test_final_forecast_validation() {
         );
         
         // Non-empty condition
-        assert!(
-            !final_forecast.condition.is_empty(),
-            "Day {} condition should not be empty", i
+        assert_ne!(
+            final_forecast.condition, Condition::Unknown,
+            "Day {} condition should be known", i
         );
     }
 }
@@ -578,7 +565,7 @@ fn test_final_forecast_validation() {
 #[test]
 fn test_date_consistency_sequential_days() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta");
+    let response = client.get("/api/weather/ensemble?city=Jakarta");
     
     assert_ok(response.status());
     
@@ -591,7 +578,7 @@ fn test_date_consistency_sequential_days() {
 #[test]
 fn test_date_consistency_first_date_is_today() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta&period=current_week");
+    let response = client.get("/api/weather/ensemble?city=Jakarta&period=current_week");
     
     assert_ok(response.status());
     
@@ -602,7 +589,7 @@ fn test_date_consistency_first_date_is_today() {
 #[test]
 fn test_date_consistency_next_week_is_d_plus_7() {
     let client = TestClient::new();
-    let mut response = client.get("/api/weather/ensemble?city=Jakarta&period=next_week&day=0");
+    let response = client.get("/api/weather/ensemble?city=Jakarta&period=next_week&day=0");
     
     assert_ok(response.status());
     
@@ -614,7 +601,7 @@ fn test_date_consistency_next_week_is_d_plus_7() {
     let today = chrono::Local::now().naive_local().date();
     let diff = forecast_date.signed_duration_since(today).num_days();
     
-    assert!(diff >= 7 && diff <= 14, 
+    assert!((7..=14).contains(&diff), 
         "Next week date should be 7-14 days ahead (D+7), got {} days", diff);
 }
 
@@ -628,7 +615,7 @@ fn test_cache_effectiveness_second_call_faster() {
     
     // First call (cache miss)
     let start1 = Instant::now();
-    let mut response1 = client.get("/api/weather/ensemble?city=Jakarta");
+    let response1 = client.get("/api/weather/ensemble?city=Jakarta");
     let duration1 = start1.elapsed();
     assert_ok(response1.status());
     let _forecast1: EnsembleForecast = response1.json();
@@ -638,7 +625,7 @@ fn test_cache_effectiveness_second_call_faster() {
     
     // Second call (should hit cache)
     let start2 = Instant::now();
-    let mut response2 = client.get("/api/weather/ensemble?city=Jakarta");
+    let response2 = client.get("/api/weather/ensemble?city=Jakarta");
     let duration2 = start2.elapsed();
     assert_ok(response2.status());
     let _forecast2: EnsembleForecast = response2.json();
@@ -657,7 +644,7 @@ fn test_cached_request_response_time() {
     let client = TestClient::new();
     
     // Prime the cache
-    let mut response_prime = client.get("/api/weather/ensemble?city=Bandung");
+    let response_prime = client.get("/api/weather/ensemble?city=Bandung");
     assert_ok(response_prime.status());
     let _: EnsembleForecast = response_prime.json();
     
@@ -666,7 +653,7 @@ fn test_cached_request_response_time() {
     
     // Measure cached request
     let start = Instant::now();
-    let mut response = client.get("/api/weather/ensemble?city=Bandung");
+    let response = client.get("/api/weather/ensemble?city=Bandung");
     let duration = start.elapsed();
     assert_ok(response.status());
     let _: EnsembleForecast = response.json();
@@ -686,7 +673,7 @@ fn test_fresh_request_response_time() {
     
     // Use a unique city to avoid cache hits
     let start = Instant::now();
-    let mut response = client.get("/api/weather/ensemble?city=Surabaya");
+    let response = client.get("/api/weather/ensemble?city=Surabaya");
     let duration = start.elapsed();
     
     assert_ok(response.status());
@@ -710,10 +697,47 @@ fn test_multiple_cities_cache_independence() {
     
     for city in cities {
         let uri = format!("/api/weather/ensemble?city={}", city);
-        let mut response = client.get(&uri);
+        let response = client.get(&uri);
         
         assert_ok(response.status());
         let forecast: EnsembleForecast = response.json();
         assert_eq!(forecast.city, city, "Cached city should match request");
     }
 }
+
+#[test]
+fn test_admin_quota_requires_api_key() {
+    let client = TestClient::new();
+
+    let response = client.get("/admin/quota");
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client.get_with_headers("/admin/quota", vec![("X-API-Key", "wrong-key")]);
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let json: Value = response.json_value();
+    assert_eq!(json["error"], "UNAUTHORIZED");
+}
+
+#[test]
+fn test_admin_quota_with_valid_api_key() {
+    let client = TestClient::new();
+
+    let response = client.get_with_headers("/admin/quota", vec![("X-API-Key", TEST_API_KEY)]);
+    assert_ok(response.status());
+
+    let json: Value = response.json_value();
+    assert!(json["keys"].is_array(), "Quota report should list keys");
+    assert!(json["generated_at"].is_string());
+}
+
+#[test]
+fn test_admin_keys_requires_api_key() {
+    let client = TestClient::new();
+
+    let response = client.get_with_headers("/admin/keys", vec![("X-API-Key", "test-admin-kez")]);
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client.get_with_headers("/admin/keys", vec![("X-API-Key", TEST_API_KEY)]);
+    assert_ok(response.status());
+}
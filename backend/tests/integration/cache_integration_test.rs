@@ -6,29 +6,17 @@ async fn test_cache_integration_with_forecast() {
     
     let cache_key = "forecast:jakarta:current_week".to_string();
     
-    // Simulate fetching forecast
-    let data = cache.get_or_fetch(
-        cache_key.clone(),
-        || async {
-            // Simulate API call
-            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-            Ok("{\"city\":\"Jakarta\",\"days\":7}".to_string())
-        },
-    ).await.unwrap();
+    // First request misses and stores the fetched forecast
+    assert!(cache.get(&cache_key).await.is_none());
+    let data = "{\"city\":\"Jakarta\",\"days\":7}".to_string();
+    cache.insert(cache_key.clone(), data.clone()).await;
     
-    assert!(data.contains("Jakarta"));
-    
-    // Next call should be instant (from cache)
+    // Next request is served from cache
     let start = std::time::Instant::now();
-    let cached_data = cache.get_or_fetch(
-        cache_key,
-        || async {
-            // This shouldn't run
-            Err::<String, _>("Should not fetch".to_string())
-        },
-    ).await.unwrap();
+    let cached_data = cache.get(&cache_key).await.unwrap();
     let elapsed = start.elapsed();
     
     assert_eq!(data, cached_data);
+    assert!(cached_data.contains("Jakarta"));
     assert!(elapsed.as_millis() < 50); // Cache should be fast
 }
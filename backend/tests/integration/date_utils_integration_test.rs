@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use backend::utils::*;
+    use backend::utils::date_utils::*;
 
     #[test]
     fn test_full_forecast_period_workflow() {
@@ -46,6 +46,6 @@ mod tests {
         let diff = (next_monday - today).num_days();
         
         // Next Monday should be 1-13 days away
-        assert!(diff >= 1 && diff <= 13, "Next Monday distance invalid: {} days", diff);
+        assert!((1..=13).contains(&diff), "Next Monday distance invalid: {} days", diff);
     }
 }
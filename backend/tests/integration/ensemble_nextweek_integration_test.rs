@@ -2,13 +2,14 @@ use backend::services::{
     cache::ForecastCache,
     ensemble_orchestrator::EnsembleOrchestrator,
 };
-use backend::models::{City, ForecastPeriodRequest};
+use backend::models::{City, EnsembleForecast, ForecastPeriodRequest};
+use crate::test_utils::stub_registry;
 use std::sync::Arc;
 
 #[tokio::test]
 async fn test_orchestrator_current_week() {
-    let cache = Arc::new(ForecastCache::new(3600, 100));
-    let orchestrator = EnsembleOrchestrator::new(cache);
+    let cache = Arc::new(ForecastCache::<EnsembleForecast>::new(3600, 100));
+    let orchestrator = EnsembleOrchestrator::new(cache, stub_registry());
 
     let city = City {
         id: 1,
@@ -30,8 +31,8 @@ async fn test_orchestrator_current_week() {
 
 #[tokio::test]
 async fn test_orchestrator_next_week() {
-    let cache = Arc::new(ForecastCache::new(3600, 100));
-    let orchestrator = EnsembleOrchestrator::new(cache);
+    let cache = Arc::new(ForecastCache::<EnsembleForecast>::new(3600, 100));
+    let orchestrator = EnsembleOrchestrator::new(cache, stub_registry());
 
     let city = City {
         id: 1,
@@ -51,8 +52,8 @@ async fn test_orchestrator_next_week() {
 
 #[tokio::test]
 async fn test_cache_hit_current_week() {
-    let cache = Arc::new(ForecastCache::new(3600, 100));
-    let orchestrator = EnsembleOrchestrator::new(cache.clone());
+    let cache = Arc::new(ForecastCache::<EnsembleForecast>::new(3600, 100));
+    let orchestrator = EnsembleOrchestrator::new(cache.clone(), stub_registry());
 
     let city = City {
         id: 1,
@@ -69,7 +70,7 @@ async fn test_cache_hit_current_week() {
     ).await;
 
     // Check cache has entry
-    assert!(cache.size().await > 0);
+    assert!(cache.get("forecast:jakarta:current_week").await.is_some());
 
     // Second call should hit cache
     let _ = orchestrator.get_forecast(
@@ -78,13 +79,13 @@ async fn test_cache_hit_current_week() {
     ).await;
 
     // Cache still has entry
-    assert!(cache.size().await > 0);
+    assert!(cache.get("forecast:jakarta:current_week").await.is_some());
 }
 
 #[tokio::test]
 async fn test_orchestrator_invalid_next_week_day() {
-    let cache = Arc::new(ForecastCache::new(3600, 100));
-    let orchestrator = EnsembleOrchestrator::new(cache);
+    let cache = Arc::new(ForecastCache::<EnsembleForecast>::new(3600, 100));
+    let orchestrator = EnsembleOrchestrator::new(cache, stub_registry());
 
     let city = City {
         id: 1,
@@ -101,41 +102,13 @@ async fn test_orchestrator_invalid_next_week_day() {
 
     assert!(result.is_err());
     let error = result.unwrap_err();
-    assert!(error.contains("Invalid day"));
-}
-
-#[tokio::test]
-async fn test_orchestrator_cache_stats() {
-    let cache = Arc::new(ForecastCache::new(3600, 100));
-    let orchestrator = EnsembleOrchestrator::new(cache.clone());
-
-    let city = City {
-        id: 1,
-        name: "Jakarta",
-        province: "DKI Jakarta",
-        latitude: -6.2,
-        longitude: 106.8,
-    };
-
-    // Make a request to populate cache
-    let _ = orchestrator.get_forecast(
-        &city,
-        ForecastPeriodRequest::CurrentWeek,
-    ).await;
-
-    // Get stats
-    let stats_string = orchestrator.cache_stats().await;
-    
-    // Verify stats string contains expected information
-    assert!(stats_string.contains("Cache:"));
-    assert!(stats_string.contains("entries"));
-    assert!(stats_string.contains("TTL:"));
+    assert!(error.to_string().contains("Invalid day"));
 }
 
 #[tokio::test]
 async fn test_next_week_different_days() {
-    let cache = Arc::new(ForecastCache::new(3600, 100));
-    let orchestrator = EnsembleOrchestrator::new(cache.clone());
+    let cache = Arc::new(ForecastCache::<EnsembleForecast>::new(3600, 100));
+    let orchestrator = EnsembleOrchestrator::new(cache.clone(), stub_registry());
 
     let city = City {
         id: 1,
@@ -162,13 +135,14 @@ async fn test_next_week_different_days() {
     assert!(result_friday.is_ok());
 
     // Cache should have 2 entries (different keys)
-    assert_eq!(cache.size().await, 2);
+    assert!(cache.get("forecast:jakarta:next_week:0").await.is_some());
+    assert!(cache.get("forecast:jakarta:next_week:4").await.is_some());
 }
 
 #[tokio::test]
 async fn test_orchestrator_multiple_cities() {
-    let cache = Arc::new(ForecastCache::new(3600, 100));
-    let orchestrator = EnsembleOrchestrator::new(cache.clone());
+    let cache = Arc::new(ForecastCache::<EnsembleForecast>::new(3600, 100));
+    let orchestrator = EnsembleOrchestrator::new(cache.clone(), stub_registry());
 
     let jakarta = City {
         id: 1,
@@ -203,13 +177,14 @@ async fn test_orchestrator_multiple_cities() {
     assert!(result2.is_ok());
 
     // Cache should have 2 entries (different cities)
-    assert_eq!(cache.size().await, 2);
+    assert!(cache.get("forecast:jakarta:current_week").await.is_some());
+    assert!(cache.get("forecast:bandung:current_week").await.is_some());
 }
 
 #[tokio::test]
 async fn test_cache_hit_next_week() {
-    let cache = Arc::new(ForecastCache::new(3600, 100));
-    let orchestrator = EnsembleOrchestrator::new(cache.clone());
+    let cache = Arc::new(ForecastCache::<EnsembleForecast>::new(3600, 100));
+    let orchestrator = EnsembleOrchestrator::new(cache.clone(), stub_registry());
 
     let city = City {
         id: 1,
@@ -226,7 +201,8 @@ async fn test_cache_hit_next_week() {
     ).await;
 
     assert!(result1.is_ok());
-    assert_eq!(cache.size().await, 1);
+    let cached = cache.get("forecast:jakarta:next_week:0").await;
+    assert!(cached.is_some());
 
     // Second call - cache hit
     let result2 = orchestrator.get_forecast(
@@ -235,6 +211,6 @@ async fn test_cache_hit_next_week() {
     ).await;
 
     assert!(result2.is_ok());
-    // Cache size should still be 1
-    assert_eq!(cache.size().await, 1);
+    // Second call returns the cached forecast
+    assert_eq!(result2.unwrap().source_timestamp, cached.unwrap().source_timestamp);
 }
@@ -1,4 +1,4 @@
-use backend::errors::{ApiError, ErrorResponse};
+use backend::errors::ApiError;
 use backend::services::{find_city, validate_city_input};
 use rocket::http::Status;
 
@@ -91,6 +91,7 @@ async fn test_end_to_end_city_name_too_long() {
             let error = ApiError::invalid_params(&msg);
             let (status, json) = error.to_response();
             assert_eq!(status, Status::BadRequest);
+            assert_eq!(json.into_inner().message, msg);
         }
         _ => panic!("Expected InvalidInput error"),
     }
@@ -187,13 +188,14 @@ async fn test_error_response_consistency() {
 
 #[tokio::test]
 async fn test_validation_chain_multiple_cities() {
+    let too_long = "A".repeat(51);
     let test_cases = vec![
         ("Jakarta", true),
         ("Bandung", true),
         ("InvalidCity", false),
         ("", false),
         ("  Solo  ", true),
-        ("A".repeat(51).as_str(), false),
+        (too_long.as_str(), false),
     ];
     
     for (city_input, should_find) in test_cases {
@@ -1,3 +1,6 @@
+// Shared helpers; not every test module uses every helper
+#![allow(dead_code)]
+
 use rocket::local::blocking::Client;
 use rocket::local::blocking::LocalResponse;
 use rocket::http::{Status, Header};
@@ -5,11 +8,19 @@ use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::sync::Arc;
 use backend::services::{WeatherService, ForecastCache};
-use backend::models::EnsembleForecast;
+use backend::services::providers::{ProviderRegistry, UpstreamClient};
+use backend::models::{Condition, CurrentConditions, EnsembleForecast, HourlyEnsembleForecast};
 use backend::utils::Config;
 use backend::routes::routes;
 use tokio::sync::Semaphore;
-use chrono::NaiveDate;
+use chrono::{FixedOffset, NaiveDate, Utc};
+use crate::test_support::{
+    open_meteo_fixture, openweather_current_fixture, openweather_fixture, spawn_stub_server,
+    weatherapi_current_fixture, weatherapi_fixture, with_fixture_start, StubRoute,
+};
+
+/// `API_KEY` configured for the test rocket instance (admin endpoints)
+pub const TEST_API_KEY: &str = "test-admin-key";
 
 /// Test client wrapper for making HTTP requests
 pub struct TestClient {
@@ -17,22 +28,32 @@ pub struct TestClient {
 }
 
 impl TestClient {
-    /// Create a new test client with full rocket setup
+    /// Create a new test client with full rocket setup, backed by the local stub providers
     pub fn new() -> Self {
-        let config = Config::from_env();
+        let mut config = stub_config();
+        // Keep quota counters in memory during tests
+        config.quota_state_file = None;
+        config.api_key = Some(TEST_API_KEY.to_string());
         
-        let weather_service = WeatherService::new(
-            config.openweather_key.clone(),
-            config.weatherapi_key.clone(),
+        let upstream = Arc::new(
+            UpstreamClient::from_config(&config).expect("Failed to create upstream HTTP client"),
         );
+        let provider_registry = Arc::new(ProviderRegistry::from_config(&config, upstream.clone()));
+        let weather_service = WeatherService::new(provider_registry.clone());
         
         let ensemble_cache = Arc::new(ForecastCache::<EnsembleForecast>::new(3600, 100));
+        let hourly_cache = Arc::new(ForecastCache::<HourlyEnsembleForecast>::new(1800, 100));
+        let current_cache = Arc::new(ForecastCache::<CurrentConditions>::new(600, 100));
         let semaphore = Arc::new(Semaphore::new(3));
         
         let rocket = rocket::build()
             .manage(weather_service)
             .manage(semaphore)
             .manage(ensemble_cache)
+            .manage(hourly_cache)
+            .manage(current_cache)
+            .manage(provider_registry)
+            .manage(upstream)
             .manage(config)
             .mount("/", routes());
         
@@ -42,22 +63,63 @@ impl TestClient {
     }
     
     /// Make a GET request
-    pub fn get(&self, uri: &str) -> TestResponse {
-        let response = self.client.get(uri).dispatch();
+    pub fn get(&self, uri: &str) -> TestResponse<'_> {
+        let response = self.client.get(uri.to_string()).dispatch();
         TestResponse { response }
     }
     
     /// Make a GET request with custom headers
-    pub fn get_with_headers(&self, uri: &str, headers: Vec<(&str, &str)>) -> TestResponse {
-        let mut req = self.client.get(uri);
+    pub fn get_with_headers(&self, uri: &str, headers: Vec<(&str, &str)>) -> TestResponse<'_> {
+        let mut req = self.client.get(uri.to_string());
         for (name, value) in headers {
-            req.add_header(Header::new(name, value));
+            req.add_header(Header::new(name.to_string(), value.to_string()));
         }
         let response = req.dispatch();
         TestResponse { response }
     }
 }
 
+/// Config pointing every provider at a local stub server whose fixtures start today (WIB)
+pub fn stub_config() -> Config {
+    let today = Utc::now().with_timezone(&wib()).date_naive();
+    let routes = with_fixture_start(today, || {
+        vec![
+            StubRoute::new("/v1/forecast.json", 200, &weatherapi_fixture(32.0)),
+            StubRoute::new("/v1/current.json", 200, &weatherapi_current_fixture(31.0)),
+            StubRoute::new("/v1/forecast", 200, &open_meteo_fixture(30.0)),
+            StubRoute::new("/data/2.5/forecast", 200, &openweather_fixture(31.0)),
+            StubRoute::new("/data/2.5/weather", 200, &openweather_current_fixture(30.5)),
+        ]
+    });
+    let base_url = spawn_stub_server(routes);
+
+    let mut config = Config::from_env();
+    config.enabled_providers = vec![
+        "open_meteo".to_string(),
+        "open_weather".to_string(),
+        "weather_api".to_string(),
+    ];
+    config.openweather_keys = vec!["test-key".to_string()];
+    config.weatherapi_keys = vec!["test-key".to_string()];
+    config.open_meteo_base_url = base_url.clone();
+    config.openweather_base_url = base_url.clone();
+    config.weatherapi_base_url = base_url;
+    config
+}
+
+/// Provider registry backed by the stub server from [`stub_config`]
+pub fn stub_registry() -> Arc<ProviderRegistry> {
+    let mut config = stub_config();
+    config.quota_state_file = None;
+    let upstream = Arc::new(UpstreamClient::from_config(&config).expect("Failed to create upstream HTTP client"));
+    Arc::new(ProviderRegistry::from_config(&config, upstream))
+}
+
+/// UTC+7, the local time of the WIB test cities
+pub fn wib() -> FixedOffset {
+    FixedOffset::east_opt(7 * 3600).unwrap()
+}
+
 /// Test response wrapper with helper methods
 pub struct TestResponse<'a> {
     response: LocalResponse<'a>,
@@ -70,18 +132,18 @@ impl<'a> TestResponse<'a> {
     }
     
     /// Parse response body as JSON into type T
-    pub fn json<T: DeserializeOwned>(&mut self) -> T {
+    pub fn json<T: DeserializeOwned + Send + 'static>(self) -> T {
         self.response.into_json::<T>().expect("Failed to parse JSON")
     }
     
     /// Parse response body as generic JSON object
-    pub fn json_value(&mut self) -> Value {
+    pub fn json_value(self) -> Value {
         let body = self.response.into_string().expect("Failed to get body");
         serde_json::from_str(&body).expect("Failed to parse JSON")
     }
     
     /// Get the response body as string
-    pub fn body(&mut self) -> String {
+    pub fn body(self) -> String {
         self.response.into_string().expect("Failed to get body")
     }
     
@@ -150,8 +212,8 @@ pub mod assertions {
         assert!(final_forecast.temp_max >= final_forecast.temp_min, 
             "temp_max should be >= temp_min at day {}: {} >= {}", 
             index, final_forecast.temp_max, final_forecast.temp_min);
-        assert!(!final_forecast.condition.is_empty(), 
-            "Condition should not be empty at day {}", index);
+        assert_ne!(final_forecast.condition, Condition::Unknown,
+            "Condition should be known at day {}", index);
         
         // Validate confidence
         assert!(
@@ -175,21 +237,21 @@ pub mod assertions {
         assert_eq!(parts[2].len(), 2, "{} day should be 2 digits: {}", label, date);
         
         // Parse to ensure valid numbers
-        let _year: u32 = parts[0].parse().expect(&format!("{} year should be numeric: {}", label, date));
-        let month: u32 = parts[1].parse().expect(&format!("{} month should be numeric: {}", label, date));
-        let day: u32 = parts[2].parse().expect(&format!("{} day should be numeric: {}", label, date));
+        let _year: u32 = parts[0].parse().unwrap_or_else(|_| panic!("{} year should be numeric: {}", label, date));
+        let month: u32 = parts[1].parse().unwrap_or_else(|_| panic!("{} month should be numeric: {}", label, date));
+        let day: u32 = parts[2].parse().unwrap_or_else(|_| panic!("{} day should be numeric: {}", label, date));
         
-        assert!(month >= 1 && month <= 12, "{} month should be 1-12: {}", label, date);
-        assert!(day >= 1 && day <= 31, "{} day should be 1-31: {}", label, date);
+        assert!((1..=12).contains(&month), "{} month should be 1-12: {}", label, date);
+        assert!((1..=31).contains(&day), "{} day should be 1-31: {}", label, date);
     }
     
     /// Assert dates are sequential (one day apart)
     pub fn assert_sequential_dates(dates: &[String]) {
         for i in 1..dates.len() {
             let prev_date = NaiveDate::parse_from_str(&dates[i-1], "%Y-%m-%d")
-                .expect(&format!("Failed to parse date: {}", dates[i-1]));
+                .unwrap_or_else(|_| panic!("Failed to parse date: {}", dates[i-1]));
             let curr_date = NaiveDate::parse_from_str(&dates[i], "%Y-%m-%d")
-                .expect(&format!("Failed to parse date: {}", dates[i]));
+                .unwrap_or_else(|_| panic!("Failed to parse date: {}", dates[i]));
             
             let diff = curr_date.signed_duration_since(prev_date).num_days();
             assert_eq!(diff, 1, "Dates should be sequential (1 day apart) at index {}: {} -> {}", 
@@ -197,9 +259,9 @@ pub mod assertions {
         }
     }
     
-    /// Assert first date is today (for current week)
+    /// Assert first date is today in the city's local time (WIB for the test cities)
     pub fn assert_first_date_is_today(first_date: &str) {
-        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let today = chrono::Utc::now().with_timezone(&wib()).format("%Y-%m-%d").to_string();
         assert_eq!(first_date, today, "First date should be today for current week: expected {}, got {}", 
             today, first_date);
     }
@@ -220,29 +282,29 @@ pub mod assertions {
 
 /// Mock data generators for testing
 pub mod mocks {
-    use backend::models::{ProviderForecast, PerSourceData, FinalForecast, DayEnsemble, EnsembleForecast};
+    use backend::models::{Condition, ProviderForecast, PerSourceData, FinalForecast, DayEnsemble, EnsembleForecast};
     
     /// Create a mock provider forecast
-    pub fn mock_provider_forecast(date: &str, temp_max: f32, temp_min: f32, condition: &str) -> ProviderForecast {
+    pub fn mock_provider_forecast(date: &str, temp_max: f32, temp_min: f32, condition: Condition) -> ProviderForecast {
         ProviderForecast::new(
             date.to_string(),
             temp_max,
             temp_min,
-            condition.to_string(),
+            condition,
         )
     }
     
     /// Create mock per-source data with all providers
     pub fn mock_per_source_data(date: &str) -> PerSourceData {
         PerSourceData::new()
-            .with_open_meteo(mock_provider_forecast(date, 32.0, 24.0, "Partly Cloudy"))
-            .with_open_weather(mock_provider_forecast(date, 33.0, 25.0, "Sunny"))
-            .with_weather_api(mock_provider_forecast(date, 31.0, 24.5, "Clear"))
+            .with_open_meteo(mock_provider_forecast(date, 32.0, 24.0, Condition::PartlyCloudy))
+            .with_open_weather(mock_provider_forecast(date, 33.0, 25.0, Condition::Clear))
+            .with_weather_api(mock_provider_forecast(date, 31.0, 24.5, Condition::Clear))
     }
     
     /// Create a mock final forecast
-    pub fn mock_final_forecast(temp_max: f32, temp_min: f32, condition: &str, confidence: &str) -> FinalForecast {
-        FinalForecast::new(temp_max, temp_min, condition.to_string(), confidence.to_string())
+    pub fn mock_final_forecast(temp_max: f32, temp_min: f32, condition: Condition, confidence: &str) -> FinalForecast {
+        FinalForecast::new(temp_max, temp_min, condition, confidence.to_string())
     }
     
     /// Create a mock day ensemble
@@ -250,7 +312,7 @@ pub mod mocks {
         DayEnsemble::new(
             date.to_string(),
             mock_per_source_data(date),
-            mock_final_forecast(32.0, 24.5, "Partly Cloudy", "high"),
+            mock_final_forecast(32.0, 24.5, Condition::PartlyCloudy, "high"),
         )
     }
     
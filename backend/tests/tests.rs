@@ -1,3 +1,7 @@
 mod unit;
 mod integration;
 mod test_utils;
+
+#[path = "../src/services/providers/test_support.rs"]
+#[allow(dead_code)]
+mod test_support;
@@ -233,8 +233,8 @@ fn test_city_coordinates_bandung() {
     let result = find_city_by_name("Bandung");
     assert!(result.is_some());
     let city = result.unwrap();
-    assert_eq!(city.latitude, -6.9175);
-    assert_eq!(city.longitude, 107.6191);
+    assert_eq!(city.latitude, -6.9271);
+    assert_eq!(city.longitude, 107.6411);
 }
 
 #[test]
@@ -242,8 +242,8 @@ fn test_city_coordinates_surabaya() {
     let result = find_city_by_name("Surabaya");
     assert!(result.is_some());
     let city = result.unwrap();
-    assert_eq!(city.latitude, -7.2575);
-    assert_eq!(city.longitude, 112.7521);
+    assert_eq!(city.latitude, -7.2504);
+    assert_eq!(city.longitude, 112.7688);
 }
 
 #[test]
@@ -1,4 +1,5 @@
-use backend::services::{find_city, validate_city_input, get_all_cities, get_city_count};
+use backend::services::{find_city, validate_city_input, get_all_cities};
+use backend::cities::CITIES;
 use backend::errors::ApiError;
 
 #[test]
@@ -207,16 +208,14 @@ fn test_get_all_cities_all_have_coordinates() {
 }
 
 #[test]
-fn test_get_city_count_returns_positive() {
-    let count = get_city_count();
-    assert!(count > 0, "City count should be positive");
+fn test_city_count_returns_positive() {
+    assert!(!CITIES.is_empty(), "City count should be positive");
 }
 
 #[test]
-fn test_get_city_count_matches_cities_length() {
-    let count = get_city_count();
+fn test_get_all_cities_matches_cities_length() {
     let cities = get_all_cities();
-    assert_eq!(count, cities.len(), "City count should match cities array length");
+    assert_eq!(CITIES.len(), cities.len(), "City count should match cities array length");
 }
 
 #[test]
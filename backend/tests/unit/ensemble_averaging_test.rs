@@ -1,85 +1,80 @@
 #[cfg(test)]
 mod tests {
-    use backend::services::ensemble::{
-        calculate_weighted_temperature,
-        calculate_weighted_average_generic,
-    };
+    use backend::models::{Condition, PerSourceData, ProviderForecast, VoteGranularity};
+    use backend::services::ensemble_fetcher::{calculate_final_forecast, mean, range};
+
+    fn forecast(temp_max: f32, temp_min: f32) -> ProviderForecast {
+        ProviderForecast::new("2025-01-06".to_string(), temp_max, temp_min, Condition::Cloudy)
+    }
+
+    fn final_temps(per_source: &PerSourceData) -> (f32, f32) {
+        let (temp_max, temp_min, _) =
+            calculate_final_forecast(per_source, "2025-01-06".to_string(), VoteGranularity::Category).unwrap();
+        (temp_max, temp_min)
+    }
 
     #[test]
-    fn test_weighted_temperature_all_present() {
-        let result = calculate_weighted_temperature(
-            Some(32.0), // Open-Meteo (0.4)
-            Some(33.0), // OpenWeatherMap (0.35)
-            Some(31.5), // WeatherAPI (0.25)
-        );
+    fn test_average_all_present() {
+        let per_source = PerSourceData::new()
+            .with_open_meteo(forecast(32.0, 24.0))   // Open-Meteo
+            .with_open_weather(forecast(33.0, 25.0)) // OpenWeatherMap
+            .with_weather_api(forecast(31.5, 24.5)); // WeatherAPI
 
-        // Expected: 32.0*0.4 + 33.0*0.35 + 31.5*0.25
-        //         = 12.8 + 11.55 + 7.875
-        //         = 32.225
-        assert!((result - 32.225).abs() < 0.01);
+        // Expected: (32.0 + 33.0 + 31.5) / 3 = 32.1667
+        let (temp_max, temp_min) = final_temps(&per_source);
+        assert!((temp_max - 32.1667).abs() < 0.01);
+        assert!((temp_min - 24.5).abs() < 0.01);
     }
 
     #[test]
-    fn test_weighted_temperature_missing_one() {
-        let result = calculate_weighted_temperature(
-            Some(32.0), // Open-Meteo (0.4)
-            Some(33.0), // OpenWeatherMap (0.35)
-            None,       // WeatherAPI missing
-        );
+    fn test_average_missing_one() {
+        let per_source = PerSourceData::new()
+            .with_open_meteo(forecast(32.0, 24.0))
+            .with_open_weather(forecast(33.0, 25.0));
 
-        // With 3rd missing, renormalize weights to 0.4/(0.4+0.35) and 0.35/(0.4+0.35)
-        // New weights: 0.533 and 0.467
-        // Expected: 32.0*0.533 + 33.0*0.467 = 17.056 + 15.411 = 32.467
-        assert!((result - 32.467).abs() < 0.01);
+        // Missing provider is left out of the average
+        let (temp_max, _) = final_temps(&per_source);
+        assert!((temp_max - 32.5).abs() < 0.01);
     }
 
     #[test]
-    fn test_weighted_temperature_only_one() {
-        let result = calculate_weighted_temperature(
-            Some(32.0), // Open-Meteo only
-            None,
-            None,
-        );
+    fn test_average_only_one() {
+        let per_source = PerSourceData::new().with_open_meteo(forecast(32.0, 24.0));
 
-        // Only Open-Meteo available, should return 32.0
-        assert_eq!(result, 32.0);
+        // Only Open-Meteo available, should return its value
+        assert_eq!(final_temps(&per_source), (32.0, 24.0));
     }
 
     #[test]
-    fn test_weighted_temperature_all_none() {
-        let result = calculate_weighted_temperature(None, None, None);
-        assert_eq!(result, 0.0);
+    fn test_average_no_data() {
+        let result = calculate_final_forecast(&PerSourceData::new(), "2025-01-06".to_string(), VoteGranularity::Category);
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_weighted_temperature_realistic() {
+    fn test_average_realistic() {
         // Realistic scenario: Jakarta forecast
-        let result = calculate_weighted_temperature(
-            Some(32.1), // OM
-            Some(32.5), // OW
-            Some(31.8), // WA
-        );
+        let per_source = PerSourceData::new()
+            .with_open_meteo(forecast(32.1, 24.0))
+            .with_open_weather(forecast(32.5, 24.0))
+            .with_weather_api(forecast(31.8, 24.0));
 
         // Should be around 32.1-32.2
-        assert!(result >= 32.0 && result <= 32.3);
+        let (temp_max, _) = final_temps(&per_source);
+        assert!((32.0..=32.3).contains(&temp_max));
     }
 
     #[test]
-    fn test_weighted_average_generic() {
-        let values = vec![
-            (32.0, 0.4),
-            (33.0, 0.35),
-            (31.5, 0.25),
-        ];
+    fn test_mean_and_range() {
+        let values = [32.0, 33.0, 31.5];
 
-        let result = calculate_weighted_average_generic(values);
-        assert!((result - 32.225).abs() < 0.01);
+        assert!((mean(&values).unwrap() - 32.1667).abs() < 0.01);
+        assert!((range(&values).unwrap() - 1.5).abs() < 0.01);
     }
 
     #[test]
-    fn test_weighted_average_empty() {
-        let values: Vec<(f32, f32)> = vec![];
-        let result = calculate_weighted_average_generic(values);
-        assert_eq!(result, 0.0);
+    fn test_mean_and_range_empty() {
+        assert_eq!(mean(&[]), None);
+        assert_eq!(range(&[]), None);
     }
 }
@@ -1,190 +1,82 @@
 #[cfg(test)]
 mod tests {
-    use backend::services::ensemble::{
-        calculate_stddev,
-        calculate_cv,
-        calculate_confidence,
-        calculate_confidence_score,
-    };
+    use backend::models::{Condition, Intensity, PerSourceData, ProviderForecast, VoteGranularity};
+    use backend::services::confidence_calculator::calculate_confidence;
 
-    #[test]
-    fn test_stddev_identical_values() {
-        let values = vec![32.0, 32.0, 32.0];
-        let stddev = calculate_stddev(values);
-        assert_eq!(stddev, 0.0);
-    }
+    const PROVIDERS: [&str; 3] = ["open_meteo", "open_weather", "weather_api"];
 
-    #[test]
-    fn test_stddev_spread_values() {
-        let values = vec![30.0, 32.0, 34.0];
-        let stddev = calculate_stddev(values);
-        // Mean = 32.0
-        // Variance = ((30-32)^2 + (32-32)^2 + (34-32)^2) / 3
-        //          = (4 + 0 + 4) / 3 = 2.667
-        // Stddev = sqrt(2.667) ≈ 1.633
-        assert!((stddev - 1.633).abs() < 0.01);
+    /// One provider per temperature; temp_min follows temp_max 8°C lower
+    fn per_source(temps: &[f32], conditions: &[Condition]) -> PerSourceData {
+        temps.iter().zip(conditions).zip(PROVIDERS).fold(PerSourceData::new(), |data, ((temp, condition), id)| {
+            data.with_provider(id, ProviderForecast::new("2025-01-06".to_string(), *temp, temp - 8.0, *condition))
+        })
     }
 
-    #[test]
-    fn test_stddev_wide_spread() {
-        let values = vec![20.0, 30.0, 40.0];
-        let stddev = calculate_stddev(values);
-        // Mean = 30.0
-        // Variance = ((20-30)^2 + (30-30)^2 + (40-30)^2) / 3
-        //          = (100 + 0 + 100) / 3 = 66.667
-        // Stddev = sqrt(66.667) ≈ 8.165
-        assert!((stddev - 8.165).abs() < 0.01);
-    }
-
-    #[test]
-    fn test_stddev_single_value() {
-        let values = vec![32.0];
-        let stddev = calculate_stddev(values);
-        assert_eq!(stddev, 0.0);
-    }
-
-    #[test]
-    fn test_stddev_empty() {
-        let values: Vec<f32> = vec![];
-        let stddev = calculate_stddev(values);
-        assert_eq!(stddev, 0.0);
-    }
-
-    #[test]
-    fn test_cv_calculation() {
-        let values = vec![30.0, 32.0, 34.0];
-        let cv = calculate_cv(values);
-        // Mean = 32.0, stddev ≈ 1.633
-        // CV = 1.633 / 32.0 ≈ 0.051
-        assert!((cv - 0.051).abs() < 0.01);
+    fn confidence(temps: &[f32], conditions: &[Condition]) -> String {
+        calculate_confidence(&per_source(temps, conditions), (0.0, 0.0), VoteGranularity::Category)
     }
 
-    #[test]
-    fn test_cv_zero_mean() {
-        let values = vec![0.0, 0.0];
-        let cv = calculate_cv(values);
-        assert_eq!(cv, 0.0);
-    }
+    const CLOUDY: [Condition; 3] = [Condition::Cloudy, Condition::Cloudy, Condition::Cloudy];
+    const MIXED: [Condition; 3] = [Condition::Cloudy, Condition::Clear, Condition::Rain(Intensity::Moderate)];
 
     #[test]
     fn test_confidence_high() {
-        // Low stddev, high agreement → high confidence
-        let temps = vec![32.0, 32.1, 32.2]; // stddev ≈ 0.082
-        let confidence = calculate_confidence(temps, 0.95);
-        assert_eq!(confidence, "high");
+        // Close temperatures, conditions agree → high confidence
+        assert_eq!(confidence(&[32.0, 32.1, 32.2], &CLOUDY), "high");
     }
 
     #[test]
-    fn test_confidence_medium_low_stddev_low_agreement() {
-        // Low stddev but low agreement → medium
-        let temps = vec![32.0, 32.1, 32.2]; // stddev ≈ 0.082
-        let confidence = calculate_confidence(temps, 0.5);
-        assert_eq!(confidence, "medium");
+    fn test_confidence_high_despite_disagreement() {
+        // Very close temperatures outweigh disagreeing conditions
+        assert_eq!(confidence(&[32.0, 32.1, 32.2], &MIXED), "high");
     }
 
     #[test]
-    fn test_confidence_medium_moderate_stddev() {
-        // Moderate stddev → medium confidence
-        let temps = vec![30.0, 32.0, 34.0]; // stddev ≈ 1.633
-        let confidence = calculate_confidence(temps, 0.9);
-        assert_eq!(confidence, "medium");
+    fn test_confidence_medium_moderate_spread() {
+        // Up to 4°C from the mean → medium
+        assert_eq!(confidence(&[29.0, 32.0, 35.0], &CLOUDY), "medium");
     }
 
     #[test]
-    fn test_confidence_low_high_stddev() {
-        // High stddev → low confidence
-        let temps = vec![20.0, 30.0, 40.0]; // stddev ≈ 8.165
-        let confidence = calculate_confidence(temps, 0.9);
-        assert_eq!(confidence, "low");
+    fn test_confidence_medium_agreeing_but_spread_conditions() {
+        // Between 2°C and 4°C from the mean with disagreeing conditions → medium
+        assert_eq!(confidence(&[29.5, 32.0, 34.5], &MIXED), "medium");
     }
 
     #[test]
-    fn test_confidence_low_poor_agreement() {
-        // Poor agreement → low confidence
-        let temps = vec![32.0, 32.1, 32.2];
-        let confidence = calculate_confidence(temps, 0.33);
-        assert_eq!(confidence, "low");
+    fn test_confidence_low_high_spread() {
+        assert_eq!(confidence(&[20.0, 30.0, 40.0], &CLOUDY), "low");
     }
 
     #[test]
-    fn test_confidence_score_high() {
-        let temps = vec![32.0, 32.1, 32.2];
-        let score = calculate_confidence_score(temps, 0.95);
-        assert!(score > 0.8); // Should be high
+    fn test_confidence_two_providers() {
+        assert_eq!(confidence(&[32.0, 33.0], &CLOUDY), "medium");
+        assert_eq!(confidence(&[30.0, 38.0], &CLOUDY), "low");
     }
 
     #[test]
-    fn test_confidence_score_low() {
-        let temps = vec![20.0, 30.0, 40.0];
-        let score = calculate_confidence_score(temps, 0.3);
-        assert!(score < 0.3); // Should be low
+    fn test_confidence_single_provider_is_low() {
+        assert_eq!(confidence(&[32.0], &CLOUDY), "low");
     }
 
     #[test]
-    fn test_confidence_score_range() {
-        for i in 0..10 {
-            let temps = vec![32.0 + i as f32, 32.5 + i as f32];
-            let score = calculate_confidence_score(temps.clone(), 0.7);
-            assert!(score >= 0.0 && score <= 1.0, "Score out of range: {}", score);
-        }
-    }
+    fn test_partly_cloudy_agrees_with_cloudy_by_category() {
+        // Within 2°C: agreement decides between high and low
+        let conditions = [Condition::PartlyCloudy, Condition::Cloudy, Condition::Clear];
+        let data = per_source(&[30.0, 32.0, 34.0], &conditions);
 
-    #[test]
-    fn test_realistic_high_confidence() {
-        // Realistic: all providers agree closely
-        let temps = vec![32.0, 32.5, 31.8]; // OM, OW, WA
-        let score = calculate_confidence_score(temps, 1.0);
-        assert!(score > 0.7);
-    }
-
-    #[test]
-    fn test_realistic_medium_confidence() {
-        // Realistic: some disagreement
-        let temps = vec![32.0, 33.0, 31.0];
-        let score = calculate_confidence_score(temps, 0.67);
-        assert!(score >= 0.3 && score <= 0.7);
-    }
-
-    #[test]
-    fn test_realistic_low_confidence() {
-        // Realistic: large disagreement
-        let temps = vec![30.0, 35.0, 28.0];
-        let score = calculate_confidence_score(temps, 0.33);
-        assert!(score < 0.4);
+        assert_eq!(calculate_confidence(&data, (0.0, 0.0), VoteGranularity::Category), "high");
+        assert_eq!(calculate_confidence(&data, (0.0, 0.0), VoteGranularity::Exact), "low");
     }
 
     #[test]
     fn test_negative_temperatures() {
-        // Test with negative temperatures (winter scenario)
-        let temps = vec![-5.0, -4.0, -6.0];
-        let stddev = calculate_stddev(temps.clone());
-        assert!(stddev > 0.0 && stddev < 2.0);
-        
-        let confidence = calculate_confidence(temps, 0.85);
-        assert_eq!(confidence, "high");
+        // Winter scenario
+        assert_eq!(confidence(&[-5.0, -4.0, -6.0], &CLOUDY), "high");
     }
 
     #[test]
     fn test_extreme_temperature_difference() {
-        // Test with very large disagreement
-        let temps = vec![10.0, 30.0, 50.0];
-        let stddev = calculate_stddev(temps.clone());
-        assert!(stddev > 15.0);
-        
-        let confidence = calculate_confidence(temps, 0.8);
-        assert_eq!(confidence, "low");
-    }
-
-    #[test]
-    fn test_confidence_boundary_cases() {
-        // Test exact boundary values
-        // stddev exactly 2.0 with high agreement
-        let temps = vec![30.0, 32.0, 34.0]; // stddev ≈ 1.633
-        let confidence1 = calculate_confidence(temps.clone(), 0.75);
-        assert_eq!(confidence1, "high");
-        
-        // Test agreement boundary
-        let confidence2 = calculate_confidence(temps, 0.74);
-        assert_eq!(confidence2, "medium");
+        assert_eq!(confidence(&[10.0, 30.0, 50.0], &CLOUDY), "low");
     }
 }
@@ -1,4 +1,5 @@
 use backend::models::ensemble::*;
+use backend::models::{Condition, Intensity};
 
 #[test]
 fn test_provider_forecast_creation() {
@@ -6,13 +7,13 @@ fn test_provider_forecast_creation() {
         "2025-11-26".to_string(),
         32.0,
         25.0,
-        "Cloudy".to_string(),
+        Condition::Cloudy,
     );
 
     assert_eq!(forecast.date, "2025-11-26");
     assert_eq!(forecast.temp_max, 32.0);
     assert_eq!(forecast.temp_min, 25.0);
-    assert_eq!(forecast.condition, "Cloudy");
+    assert_eq!(forecast.condition, Condition::Cloudy);
 }
 
 #[test]
@@ -21,23 +22,23 @@ fn test_per_source_data_builder() {
         "2025-11-26".to_string(),
         32.0,
         25.0,
-        "Cloudy".to_string(),
+        Condition::Cloudy,
     );
 
     let ow = ProviderForecast::new(
         "2025-11-26".to_string(),
         33.0,
         25.5,
-        "Cloudy".to_string(),
+        Condition::Cloudy,
     );
 
     let per_source = PerSourceData::new()
         .with_open_meteo(om)
         .with_open_weather(ow);
 
-    assert!(per_source.open_meteo.is_some());
-    assert!(per_source.open_weather.is_some());
-    assert!(per_source.weather_api.is_none());
+    assert!(per_source.get(OPEN_METEO).is_some());
+    assert!(per_source.get(OPEN_WEATHER).is_some());
+    assert!(per_source.get(WEATHER_API).is_none());
     assert_eq!(per_source.provider_count(), 2);
 }
 
@@ -48,19 +49,19 @@ fn test_per_source_provider_count() {
             "2025-11-26".to_string(),
             32.0,
             25.0,
-            "Cloudy".to_string(),
+            Condition::Cloudy,
         ))
         .with_open_weather(ProviderForecast::new(
             "2025-11-26".to_string(),
             33.0,
             25.5,
-            "Cloudy".to_string(),
+            Condition::Cloudy,
         ))
         .with_weather_api(ProviderForecast::new(
             "2025-11-26".to_string(),
             31.5,
             24.9,
-            "Rain".to_string(),
+            Condition::Rain(Intensity::Moderate),
         ));
 
     assert_eq!(per_source.provider_count(), 3);
@@ -71,23 +72,54 @@ fn test_final_forecast_creation() {
     let final_forecast = FinalForecast::new(
         32.1,
         25.1,
-        "Cloudy".to_string(),
+        Condition::Cloudy,
         "medium".to_string(),
     );
 
     assert_eq!(final_forecast.temp_max, 32.1);
     assert_eq!(final_forecast.temp_min, 25.1);
-    assert_eq!(final_forecast.condition, "Cloudy");
+    assert_eq!(final_forecast.condition, Condition::Cloudy);
     assert_eq!(final_forecast.confidence, "medium");
 }
 
+#[test]
+fn test_final_forecast_details_serialization() {
+    let details = WeatherDetails {
+        humidity: Some(78.0),
+        precipitation_mm: Some(4.5),
+        ..WeatherDetails::default()
+    };
+    let spread = ForecastSpread {
+        temp_max: 1.5,
+        temp_min: 0.5,
+        details: WeatherDetails {
+            humidity: Some(12.0),
+            ..WeatherDetails::default()
+        },
+    };
+    let final_forecast = FinalForecast::new(32.1, 25.1, Condition::Rain(Intensity::Light), "medium".to_string())
+        .with_details(details, spread);
+
+    let json = serde_json::to_value(&final_forecast).unwrap();
+    // Details are flattened next to the temperatures; missing values are null
+    assert_eq!(json["humidity"], 78.0);
+    assert_eq!(json["precipitation_mm"], 4.5);
+    assert!(json["wind_speed"].is_null());
+    assert_eq!(json["spread"]["temp_max"], 1.5);
+    assert_eq!(json["spread"]["humidity"], 12.0);
+
+    let parsed: FinalForecast = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.details, final_forecast.details);
+    assert_eq!(parsed.spread, final_forecast.spread);
+}
+
 #[test]
 fn test_day_ensemble_creation() {
     let per_source = PerSourceData::new();
     let final_forecast = FinalForecast::new(
         32.1,
         25.1,
-        "Cloudy".to_string(),
+        Condition::Cloudy,
         "medium".to_string(),
     );
 
@@ -117,7 +149,7 @@ fn test_ensemble_forecast_creation() {
         let final_forecast = FinalForecast::new(
             32.0,
             25.0,
-            "Cloudy".to_string(),
+            Condition::Cloudy,
             "high".to_string(),
         );
         let day = DayEnsemble::new(
@@ -145,7 +177,7 @@ fn test_ensemble_forecast_serialization() {
     let final_forecast = FinalForecast::new(
         32.0,
         25.0,
-        "Cloudy".to_string(),
+        Condition::Cloudy,
         "high".to_string(),
     );
     let day = DayEnsemble::new("2025-11-26".to_string(), per_source, final_forecast);
@@ -162,104 +194,6 @@ fn test_ensemble_forecast_serialization() {
     assert_eq!(deserialized.days.len(), 1);
 }
 
-#[test]
-fn test_provider_forecast_validation() {
-    // Valid forecast
-    let valid = ProviderForecast::new(
-        "2025-11-26".to_string(),
-        32.0,
-        25.0,
-        "Cloudy".to_string(),
-    );
-    assert!(valid.is_valid());
-
-    // Invalid: temp_max < temp_min
-    let invalid = ProviderForecast::new(
-        "2025-11-26".to_string(),
-        25.0,
-        32.0,
-        "Cloudy".to_string(),
-    );
-    assert!(!invalid.is_valid());
-
-    // Invalid: temperature out of range
-    let out_of_range = ProviderForecast::new(
-        "2025-11-26".to_string(),
-        100.0,
-        25.0,
-        "Cloudy".to_string(),
-    );
-    assert!(!out_of_range.is_valid());
-
-    // Invalid: empty condition
-    let no_condition = ProviderForecast::new(
-        "2025-11-26".to_string(),
-        32.0,
-        25.0,
-        "".to_string(),
-    );
-    assert!(!no_condition.is_valid());
-}
-
-#[test]
-fn test_final_forecast_validation() {
-    // Valid
-    let valid = FinalForecast::new(
-        32.0,
-        25.0,
-        "Cloudy".to_string(),
-        "high".to_string(),
-    );
-    assert!(valid.is_valid());
-
-    // Invalid confidence level
-    let invalid_confidence = FinalForecast::new(
-        32.0,
-        25.0,
-        "Cloudy".to_string(),
-        "invalid".to_string(),
-    );
-    assert!(!invalid_confidence.is_valid());
-}
-
-#[test]
-fn test_ensemble_forecast_validation() {
-    let mut forecast = EnsembleForecast::new(
-        "Jakarta".to_string(),
-        "DKI Jakarta".to_string(),
-        "Indonesia".to_string(),
-        -6.2,
-        106.8,
-    );
-
-    // Invalid: not 7 days
-    assert!(!forecast.is_valid());
-
-    // Add 7 valid days
-    for i in 0..7 {
-        let per_source = PerSourceData::new().with_open_meteo(ProviderForecast::new(
-            format!("2025-11-{:02}", 26 + i),
-            32.0,
-            25.0,
-            "Cloudy".to_string(),
-        ));
-        let final_forecast = FinalForecast::new(
-            32.0,
-            25.0,
-            "Cloudy".to_string(),
-            "high".to_string(),
-        );
-        let day = DayEnsemble::new(
-            format!("2025-11-{:02}", 26 + i),
-            per_source,
-            final_forecast,
-        );
-        forecast.add_day(day);
-    }
-
-    assert!(forecast.is_valid());
-}
-
 #[test]
 fn test_per_source_get_max_temperatures() {
     let per_source = PerSourceData::new()
@@ -267,19 +201,19 @@ fn test_per_source_get_max_temperatures() {
             "2025-11-26".to_string(),
             32.0,
             25.0,
-            "Cloudy".to_string(),
+            Condition::Cloudy,
         ))
         .with_open_weather(ProviderForecast::new(
             "2025-11-26".to_string(),
             33.0,
             25.5,
-            "Cloudy".to_string(),
+            Condition::Cloudy,
         ))
         .with_weather_api(ProviderForecast::new(
             "2025-11-26".to_string(),
             31.5,
             24.9,
-            "Rain".to_string(),
+            Condition::Rain(Intensity::Moderate),
         ));
 
     let max_temps = per_source.get_max_temperatures();
@@ -296,13 +230,13 @@ fn test_per_source_get_min_temperatures() {
             "2025-11-26".to_string(),
             32.0,
             25.0,
-            "Cloudy".to_string(),
+            Condition::Cloudy,
         ))
         .with_open_weather(ProviderForecast::new(
             "2025-11-26".to_string(),
             33.0,
             25.5,
-            "Cloudy".to_string(),
+            Condition::Cloudy,
         ));
 
     let min_temps = per_source.get_min_temperatures();
@@ -318,19 +252,19 @@ fn test_per_source_get_conditions() {
             "2025-11-26".to_string(),
             32.0,
             25.0,
-            "Cloudy".to_string(),
+            Condition::Cloudy,
         ))
         .with_weather_api(ProviderForecast::new(
             "2025-11-26".to_string(),
             31.5,
             24.9,
-            "Rain".to_string(),
+            Condition::Rain(Intensity::Moderate),
         ));
 
     let conditions = per_source.get_conditions();
     assert_eq!(conditions.len(), 2);
-    assert!(conditions.contains(&"Cloudy".to_string()));
-    assert!(conditions.contains(&"Rain".to_string()));
+    assert!(conditions.contains(&Condition::Cloudy));
+    assert!(conditions.contains(&Condition::Rain(Intensity::Moderate)));
 }
 
 #[test]
@@ -340,13 +274,13 @@ fn test_per_source_extract_temperatures() {
             "2025-11-26".to_string(),
             32.0,
             25.0,
-            "Cloudy".to_string(),
+            Condition::Cloudy,
         ))
         .with_open_weather(ProviderForecast::new(
             "2025-11-26".to_string(),
             33.0,
             25.5,
-            "Cloudy".to_string(),
+            Condition::Cloudy,
         ));
 
     let (maxes, mins) = per_source.extract_temperatures();
@@ -364,7 +298,7 @@ fn test_ensemble_with_partial_provider_data() {
         "2025-11-26".to_string(),
         32.0,
         25.0,
-        "Cloudy".to_string(),
+        Condition::Cloudy,
     ));
 
     assert_eq!(per_source.provider_count(), 1);
@@ -372,3 +306,55 @@ fn test_ensemble_with_partial_provider_data() {
     assert_eq!(per_source.get_min_temperatures().len(), 1);
     assert_eq!(per_source.get_conditions().len(), 1);
 }
+
+#[test]
+fn test_per_source_data_accepts_additional_provider() {
+    let per_source = PerSourceData::new()
+        .with_open_meteo(ProviderForecast::new(
+            "2025-11-26".to_string(),
+            32.0,
+            25.0,
+            Condition::Cloudy,
+        ))
+        .with_provider("bmkg", ProviderForecast::new(
+            "2025-11-26".to_string(),
+            31.0,
+            24.0,
+            Condition::Rain(Intensity::Moderate),
+        ));
+
+    assert_eq!(per_source.provider_count(), 2);
+    assert_eq!(per_source.get("bmkg").unwrap().condition, Condition::Rain(Intensity::Moderate));
+    assert_eq!(per_source.get_max_temperatures().len(), 2);
+}
+
+#[test]
+fn test_per_source_serialization_keeps_legacy_keys() {
+    let per_source = PerSourceData::new().with_open_meteo(ProviderForecast::new(
+        "2025-11-26".to_string(),
+        32.0,
+        25.0,
+        Condition::Cloudy,
+    ));
+
+    let json = serde_json::to_value(&per_source).unwrap();
+    assert_eq!(json["open_meteo"]["temp_max"], 32.0);
+    assert!(json["open_weather"].is_null());
+    assert!(json["weather_api"].is_null());
+    assert!(json.as_object().unwrap().contains_key("weather_api"));
+}
+
+#[test]
+fn test_per_source_deserialization_skips_null_sources() {
+    let json = r#"{
+        "open_meteo": {"date": "2025-11-26", "temp_max": 32.0, "temp_min": 25.0, "condition": "Cloudy"},
+        "open_weather": null,
+        "weather_api": null,
+        "bmkg": {"date": "2025-11-26", "temp_max": 31.0, "temp_min": 24.0, "condition": "Rain"}
+    }"#;
+
+    let per_source: PerSourceData = serde_json::from_str(json).unwrap();
+    assert_eq!(per_source.provider_count(), 2);
+    assert!(per_source.get(OPEN_WEATHER).is_none());
+    assert!(per_source.get("bmkg").is_some());
+}
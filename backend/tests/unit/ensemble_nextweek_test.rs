@@ -1,4 +1,4 @@
-use backend::models::City;
+use backend::models::{City, ForecastPeriodRequest};
 
 #[tokio::test]
 async fn test_next_week_cache_key_generation() {
@@ -21,9 +21,13 @@ async fn test_next_week_cache_key_generation() {
 
 #[tokio::test]
 async fn test_day_offset_validation() {
-    assert!(13 <= 13); // Valid: 2 weeks (day 13 is exactly 2 weeks)
-    assert!(!(14 > 13)); // Day 14 would be invalid
-    assert!(15 > 13); // Invalid: > 2 weeks
+    // Valid: Monday..Sunday of next week
+    for day in 0..=6 {
+        assert!(ForecastPeriodRequest::from_query(Some("next_week".to_string()), Some(day)).is_ok());
+    }
+    // Invalid: beyond Sunday
+    assert!(ForecastPeriodRequest::from_query(Some("next_week".to_string()), Some(7)).is_err());
+    assert!(ForecastPeriodRequest::from_query(Some("next_week".to_string()), Some(14)).is_err());
 }
 
 #[tokio::test]
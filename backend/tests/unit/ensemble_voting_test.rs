@@ -1,122 +1,78 @@
 #[cfg(test)]
 mod tests {
-    use backend::services::ensemble::{
-        majority_vote_condition,
-        vote_condition,
-    };
+    use backend::models::{Condition, Intensity, VoteGranularity};
+
+    fn vote(conditions: &[Condition]) -> Option<Condition> {
+        Condition::vote(conditions.iter().copied(), VoteGranularity::Category)
+    }
 
     #[test]
     fn test_unanimous_agreement() {
-        let conditions = vec![
-            Some("Cloudy".to_string()),
-            Some("Cloudy".to_string()),
-            Some("Cloudy".to_string()),
-        ];
-
-        let (result, agreement) = majority_vote_condition(conditions);
-        assert_eq!(result, "Cloudy");
-        assert_eq!(agreement, 1.0); // 3/3 = 100%
+        let conditions = [Condition::Cloudy, Condition::Cloudy, Condition::Cloudy];
+        assert_eq!(vote(&conditions), Some(Condition::Cloudy));
     }
 
     #[test]
     fn test_two_thirds_majority() {
-        let conditions = vec![
-            Some("Cloudy".to_string()),
-            Some("Cloudy".to_string()),
-            Some("Rainy".to_string()),
-        ];
-
-        let (result, agreement) = majority_vote_condition(conditions);
-        assert_eq!(result, "Cloudy");
-        assert!((agreement - 0.667).abs() < 0.01); // 2/3
+        let conditions = [Condition::Cloudy, Condition::Cloudy, Condition::Rain(Intensity::Moderate)];
+        assert_eq!(vote(&conditions), Some(Condition::Cloudy));
     }
 
     #[test]
     fn test_one_third_minority() {
-        let conditions = vec![
-            Some("Rainy".to_string()),
-            Some("Cloudy".to_string()),
-            Some("Cloudy".to_string()),
-        ];
-
-        let (result, agreement) = majority_vote_condition(conditions);
-        assert_eq!(result, "Cloudy");
-        assert!((agreement - 0.667).abs() < 0.01); // 2/3
+        let conditions = [Condition::Rain(Intensity::Moderate), Condition::Cloudy, Condition::Cloudy];
+        assert_eq!(vote(&conditions), Some(Condition::Cloudy));
     }
 
     #[test]
     fn test_split_decision_tie() {
-        let conditions = vec![
-            Some("Cloudy".to_string()),
-            Some("Rainy".to_string()),
-        ];
-
-        let (result, agreement) = majority_vote_condition(conditions);
-        // Should return first winner (deterministic based on HashMap iteration)
-        assert!(result == "Cloudy" || result == "Rainy");
-        assert_eq!(agreement, 0.5); // 1/2
+        // Ties go to the more severe condition
+        let conditions = [Condition::Cloudy, Condition::Rain(Intensity::Moderate)];
+        assert_eq!(vote(&conditions), Some(Condition::Rain(Intensity::Moderate)));
     }
 
     #[test]
     fn test_all_different() {
-        let conditions = vec![
-            Some("Cloudy".to_string()),
-            Some("Rainy".to_string()),
-            Some("Sunny".to_string()),
-        ];
-
-        let (result, agreement) = majority_vote_condition(conditions);
-        // Any one would be returned as "winner"
-        assert!(["Cloudy", "Rainy", "Sunny"].contains(&result.as_str()));
-        assert!((agreement - 0.333).abs() < 0.01); // 1/3
+        let conditions = [Condition::Cloudy, Condition::Rain(Intensity::Moderate), Condition::Clear];
+        let result = vote(&conditions).unwrap();
+        assert!(conditions.contains(&result));
     }
 
     #[test]
-    fn test_with_none_values() {
-        let conditions = vec![
-            Some("Cloudy".to_string()),
-            None,
-            Some("Cloudy".to_string()),
-        ];
-
-        let (result, agreement) = majority_vote_condition(conditions);
-        assert_eq!(result, "Cloudy");
-        assert_eq!(agreement, 1.0); // 2/2 valid votes
+    fn test_with_unknown_values() {
+        // Unknown never outvotes a real condition
+        let conditions = [Condition::Cloudy, Condition::Unknown, Condition::Unknown];
+        assert_eq!(vote(&conditions), Some(Condition::Cloudy));
     }
 
     #[test]
-    fn test_all_none() {
-        let conditions: Vec<Option<String>> = vec![None, None, None];
-
-        let (result, agreement) = majority_vote_condition(conditions);
-        assert_eq!(result, "Unknown");
-        assert_eq!(agreement, 0.0);
+    fn test_all_unknown() {
+        assert_eq!(vote(&[Condition::Unknown, Condition::Unknown]), Some(Condition::Unknown));
+        assert_eq!(vote(&[]), None);
     }
 
     #[test]
-    fn test_vote_condition_simple() {
-        let conditions = vec![
-            Some("Cloudy".to_string()),
-            Some("Cloudy".to_string()),
-            Some("Rainy".to_string()),
-        ];
+    fn test_realistic_scenario() {
+        // Partly cloudy and cloudy are one group; the most common exact condition is reported
+        let conditions = [Condition::PartlyCloudy, Condition::Cloudy, Condition::Cloudy];
+        assert_eq!(vote(&conditions), Some(Condition::Cloudy));
 
-        let result = vote_condition(conditions);
-        assert_eq!(result, "Cloudy");
+        let conditions = [Condition::PartlyCloudy, Condition::PartlyCloudy, Condition::Rain(Intensity::Light)];
+        assert_eq!(vote(&conditions), Some(Condition::PartlyCloudy));
     }
 
     #[test]
-    fn test_realistic_scenario() {
-        // Real scenario: providers disagree
-        let conditions = vec![
-            Some("Partly Cloudy".to_string()),
-            Some("Cloudy".to_string()),
-            Some("Cloudy".to_string()),
+    fn test_exact_granularity_keeps_intensity() {
+        let conditions = [
+            Condition::Rain(Intensity::Light),
+            Condition::Rain(Intensity::Heavy),
+            Condition::Cloudy,
+            Condition::Cloudy,
         ];
-
-        let (result, agreement) = majority_vote_condition(conditions);
-        // Could return either depending on exact match
-        assert!(result.contains("Cloudy"));
-        assert!(agreement >= 0.333);
+        assert_eq!(Condition::vote(conditions, VoteGranularity::Exact), Some(Condition::Cloudy));
+        assert_eq!(
+            Condition::vote(conditions, VoteGranularity::Category),
+            Some(Condition::Rain(Intensity::Heavy))
+        );
     }
 }
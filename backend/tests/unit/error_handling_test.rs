@@ -37,12 +37,12 @@ fn test_api_error_provider_error_constructor() {
 #[test]
 fn test_api_error_internal_error_constructor() {
     let error = ApiError::internal_error("Database connection failed");
+    assert!(error.to_string().contains("Internal error"));
     match error {
-        ApiError::WeatherProviderError(msg) => {
-            assert!(msg.contains("Internal error"));
+        ApiError::Internal(msg) => {
             assert!(msg.contains("Database connection failed"));
         }
-        _ => panic!("Expected WeatherProviderError variant"),
+        _ => panic!("Expected Internal variant"),
     }
 }
 
@@ -202,7 +202,7 @@ fn test_error_constructors_with_empty_strings() {
     }
     
     match error4 {
-        ApiError::WeatherProviderError(_) => {},
-        _ => panic!("Expected WeatherProviderError"),
+        ApiError::Internal(_) => {},
+        _ => panic!("Expected Internal"),
     }
 }
@@ -1,4 +1,4 @@
-use backend::models::{DailyForecast, WeatherForecast, ApiResponse};
+use backend::models::{Condition, DailyForecast, Intensity, WeatherForecast, ApiResponse};
 use serde_json;
 
 #[test]
@@ -8,9 +8,12 @@ fn test_daily_forecast_creation() {
         temp_max: 32.5,
         temp_min: 24.0,
         temp_avg: 28.25,
-        condition: "Clear sky".to_string(),
-        humidity: 65,
-        wind_speed: 5.5,
+        condition: Condition::Clear,
+        humidity: Some(65),
+        wind_speed: Some(5.5),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
@@ -18,9 +21,9 @@ fn test_daily_forecast_creation() {
     assert_eq!(forecast.temp_max, 32.5);
     assert_eq!(forecast.temp_min, 24.0);
     assert_eq!(forecast.temp_avg, 28.25);
-    assert_eq!(forecast.condition, "Clear sky");
-    assert_eq!(forecast.humidity, 65);
-    assert_eq!(forecast.wind_speed, 5.5);
+    assert_eq!(forecast.condition, Condition::Clear);
+    assert_eq!(forecast.humidity, Some(65));
+    assert_eq!(forecast.wind_speed, Some(5.5));
     assert_eq!(forecast.icon, "sunny");
 }
 
@@ -51,9 +54,12 @@ fn test_daily_forecast_serialization() {
         temp_max: 32.5,
         temp_min: 24.0,
         temp_avg: 28.25,
-        condition: "Clear sky".to_string(),
-        humidity: 65,
-        wind_speed: 5.5,
+        condition: Condition::Clear,
+        humidity: Some(65),
+        wind_speed: Some(5.5),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
@@ -62,7 +68,7 @@ fn test_daily_forecast_serialization() {
     assert!(json.contains("\"temp_max\":32.5"));
     assert!(json.contains("\"temp_min\":24.0"));
     assert!(json.contains("\"temp_avg\":28.25"));
-    assert!(json.contains("\"condition\":\"Clear sky\""));
+    assert!(json.contains("\"condition\":\"Clear\""));
     assert!(json.contains("\"humidity\":65"));
     assert!(json.contains("\"wind_speed\":5.5"));
     assert!(json.contains("\"icon\":\"sunny\""));
@@ -75,7 +81,7 @@ fn test_daily_forecast_deserialization() {
         "temp_max": 32.5,
         "temp_min": 24.0,
         "temp_avg": 28.25,
-        "condition": "Clear sky",
+        "condition": "Clear",
         "humidity": 65,
         "wind_speed": 5.5,
         "icon": "sunny"
@@ -86,9 +92,9 @@ fn test_daily_forecast_deserialization() {
     assert_eq!(forecast.temp_max, 32.5);
     assert_eq!(forecast.temp_min, 24.0);
     assert_eq!(forecast.temp_avg, 28.25);
-    assert_eq!(forecast.condition, "Clear sky");
-    assert_eq!(forecast.humidity, 65);
-    assert_eq!(forecast.wind_speed, 5.5);
+    assert_eq!(forecast.condition, Condition::Clear);
+    assert_eq!(forecast.humidity, Some(65));
+    assert_eq!(forecast.wind_speed, Some(5.5));
     assert_eq!(forecast.icon, "sunny");
 }
 
@@ -99,9 +105,12 @@ fn test_weather_forecast_serialization() {
         temp_max: 32.5,
         temp_min: 24.0,
         temp_avg: 28.25,
-        condition: "Clear sky".to_string(),
-        humidity: 65,
-        wind_speed: 5.5,
+        condition: Condition::Clear,
+        humidity: Some(65),
+        wind_speed: Some(5.5),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
@@ -138,7 +147,7 @@ fn test_weather_forecast_deserialization() {
             "temp_max": 32.5,
             "temp_min": 24.0,
             "temp_avg": 28.25,
-            "condition": "Clear sky",
+            "condition": "Clear",
             "humidity": 65,
             "wind_speed": 5.5,
             "icon": "sunny"
@@ -162,9 +171,12 @@ fn test_temperature_validation_max_greater_than_min() {
         temp_max: 32.5,
         temp_min: 24.0,
         temp_avg: 28.25,
-        condition: "Clear sky".to_string(),
-        humidity: 65,
-        wind_speed: 5.5,
+        condition: Condition::Clear,
+        humidity: Some(65),
+        wind_speed: Some(5.5),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
@@ -178,9 +190,12 @@ fn test_temperature_validation_avg_between_min_max() {
         temp_max: 32.5,
         temp_min: 24.0,
         temp_avg: 28.25,
-        condition: "Clear sky".to_string(),
-        humidity: 65,
-        wind_speed: 5.5,
+        condition: Condition::Clear,
+        humidity: Some(65),
+        wind_speed: Some(5.5),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
@@ -195,13 +210,16 @@ fn test_humidity_validation_within_range() {
         temp_max: 32.5,
         temp_min: 24.0,
         temp_avg: 28.25,
-        condition: "Clear sky".to_string(),
-        humidity: 65,
-        wind_speed: 5.5,
+        condition: Condition::Clear,
+        humidity: Some(65),
+        wind_speed: Some(5.5),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
-    assert!(forecast.humidity <= 100, "humidity should be <= 100");
+    assert!(forecast.humidity.unwrap() <= 100, "humidity should be <= 100");
 }
 
 #[test]
@@ -211,13 +229,16 @@ fn test_humidity_edge_case_zero() {
         temp_max: 32.5,
         temp_min: 24.0,
         temp_avg: 28.25,
-        condition: "Clear sky".to_string(),
-        humidity: 0,
-        wind_speed: 5.5,
+        condition: Condition::Clear,
+        humidity: Some(0),
+        wind_speed: Some(5.5),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
-    assert_eq!(forecast.humidity, 0);
+    assert_eq!(forecast.humidity, Some(0));
 }
 
 #[test]
@@ -227,13 +248,16 @@ fn test_humidity_edge_case_hundred() {
         temp_max: 32.5,
         temp_min: 24.0,
         temp_avg: 28.25,
-        condition: "Clear sky".to_string(),
-        humidity: 100,
-        wind_speed: 5.5,
+        condition: Condition::Clear,
+        humidity: Some(100),
+        wind_speed: Some(5.5),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
-    assert_eq!(forecast.humidity, 100);
+    assert_eq!(forecast.humidity, Some(100));
 }
 
 #[test]
@@ -261,9 +285,12 @@ fn test_json_format_matches_expected() {
         temp_max: 32.5,
         temp_min: 24.0,
         temp_avg: 28.25,
-        condition: "Clear sky".to_string(),
-        humidity: 65,
-        wind_speed: 5.5,
+        condition: Condition::Clear,
+        humidity: Some(65),
+        wind_speed: Some(5.5),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
@@ -271,7 +298,7 @@ fn test_json_format_matches_expected() {
     assert!(json.is_object());
     
     let obj = json.as_object().unwrap();
-    assert_eq!(obj.len(), 8);
+    assert_eq!(obj.len(), 11);
     assert!(obj.contains_key("date"));
     assert!(obj.contains_key("temp_max"));
     assert!(obj.contains_key("temp_min"));
@@ -279,6 +306,9 @@ fn test_json_format_matches_expected() {
     assert!(obj.contains_key("condition"));
     assert!(obj.contains_key("humidity"));
     assert!(obj.contains_key("wind_speed"));
+    assert!(obj.contains_key("wind_gust"));
+    assert!(obj.contains_key("precipitation_mm"));
+    assert!(obj.contains_key("precipitation_probability"));
     assert!(obj.contains_key("icon"));
 }
 
@@ -290,9 +320,12 @@ fn test_weather_forecast_with_multiple_days() {
             temp_max: 32.5,
             temp_min: 24.0,
             temp_avg: 28.25,
-            condition: "Clear sky".to_string(),
-            humidity: 65,
-            wind_speed: 5.5,
+            condition: Condition::Clear,
+            humidity: Some(65),
+            wind_speed: Some(5.5),
+            wind_gust: None,
+            precipitation_mm: None,
+            precipitation_probability: None,
             icon: "sunny".to_string(),
         },
         DailyForecast {
@@ -300,9 +333,12 @@ fn test_weather_forecast_with_multiple_days() {
             temp_max: 30.0,
             temp_min: 23.0,
             temp_avg: 26.5,
-            condition: "Rainy".to_string(),
-            humidity: 85,
-            wind_speed: 8.0,
+            condition: Condition::Rain(Intensity::Moderate),
+            humidity: Some(85),
+            wind_speed: Some(8.0),
+            wind_gust: None,
+            precipitation_mm: None,
+            precipitation_probability: None,
             icon: "rainy".to_string(),
         },
     ];
@@ -329,9 +365,12 @@ fn test_daily_forecast_clone() {
         temp_max: 32.5,
         temp_min: 24.0,
         temp_avg: 28.25,
-        condition: "Clear sky".to_string(),
-        humidity: 65,
-        wind_speed: 5.5,
+        condition: Condition::Clear,
+        humidity: Some(65),
+        wind_speed: Some(5.5),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
@@ -1,4 +1,4 @@
-use backend::models::DailyForecast;
+use backend::models::{Condition, DailyForecast, Intensity};
 use backend::services::providers::open_meteo::{OpenMeteoResponse, OpenMeteoDaily};
 use serde_json;
 
@@ -10,10 +10,14 @@ fn create_mock_open_meteo_response() -> OpenMeteoResponse {
                 "2024-01-16".to_string(),
                 "2024-01-17".to_string(),
             ],
-            temperature_2m_max: vec![32.5, 30.0, 31.5],
-            temperature_2m_min: vec![24.0, 23.0, 23.5],
-            relative_humidity_2m_mean: vec![65, 85, 70],
-            weather_code: vec![0, 61, 95],
+            temperature_2m_max: vec![Some(32.5), Some(30.0), Some(31.5)],
+            temperature_2m_min: vec![Some(24.0), Some(23.0), Some(23.5)],
+            relative_humidity_2m_mean: vec![Some(65.0), Some(85.0), Some(70.0)],
+            weather_code: vec![Some(0), Some(61), Some(95)],
+            precipitation_sum: vec![Some(0.0), Some(12.4), Some(30.1)],
+            precipitation_probability_max: vec![Some(5.0), Some(80.0), Some(95.0)],
+            wind_speed_10m_max: vec![Some(10.8), Some(18.0), Some(25.2)],
+            wind_gusts_10m_max: vec![Some(20.5), Some(32.4), Some(54.0)],
         },
     }
 }
@@ -21,77 +25,77 @@ fn create_mock_open_meteo_response() -> OpenMeteoResponse {
 #[test]
 fn test_wmo_code_0_clear_sky() {
     let response = create_mock_open_meteo_response();
-    assert_eq!(response.daily.weather_code[0], 0);
+    assert_eq!(response.daily.weather_code[0], Some(0));
 }
 
 #[test]
 fn test_wmo_code_1_partly_cloudy() {
     let mut response = create_mock_open_meteo_response();
-    response.daily.weather_code[0] = 1;
-    assert_eq!(response.daily.weather_code[0], 1);
+    response.daily.weather_code[0] = Some(1);
+    assert_eq!(response.daily.weather_code[0], Some(1));
 }
 
 #[test]
 fn test_wmo_code_2_partly_cloudy() {
     let mut response = create_mock_open_meteo_response();
-    response.daily.weather_code[0] = 2;
-    assert_eq!(response.daily.weather_code[0], 2);
+    response.daily.weather_code[0] = Some(2);
+    assert_eq!(response.daily.weather_code[0], Some(2));
 }
 
 #[test]
 fn test_wmo_code_3_partly_cloudy() {
     let mut response = create_mock_open_meteo_response();
-    response.daily.weather_code[0] = 3;
-    assert_eq!(response.daily.weather_code[0], 3);
+    response.daily.weather_code[0] = Some(3);
+    assert_eq!(response.daily.weather_code[0], Some(3));
 }
 
 #[test]
 fn test_wmo_code_45_foggy() {
     let mut response = create_mock_open_meteo_response();
-    response.daily.weather_code[0] = 45;
-    assert_eq!(response.daily.weather_code[0], 45);
+    response.daily.weather_code[0] = Some(45);
+    assert_eq!(response.daily.weather_code[0], Some(45));
 }
 
 #[test]
 fn test_wmo_code_48_foggy() {
     let mut response = create_mock_open_meteo_response();
-    response.daily.weather_code[0] = 48;
-    assert_eq!(response.daily.weather_code[0], 48);
+    response.daily.weather_code[0] = Some(48);
+    assert_eq!(response.daily.weather_code[0], Some(48));
 }
 
 #[test]
 fn test_wmo_code_80_rainy() {
     let mut response = create_mock_open_meteo_response();
-    response.daily.weather_code[0] = 80;
-    assert_eq!(response.daily.weather_code[0], 80);
+    response.daily.weather_code[0] = Some(80);
+    assert_eq!(response.daily.weather_code[0], Some(80));
 }
 
 #[test]
 fn test_wmo_code_81_rainy() {
     let mut response = create_mock_open_meteo_response();
-    response.daily.weather_code[0] = 81;
-    assert_eq!(response.daily.weather_code[0], 81);
+    response.daily.weather_code[0] = Some(81);
+    assert_eq!(response.daily.weather_code[0], Some(81));
 }
 
 #[test]
 fn test_wmo_code_82_rainy() {
     let mut response = create_mock_open_meteo_response();
-    response.daily.weather_code[0] = 82;
-    assert_eq!(response.daily.weather_code[0], 82);
+    response.daily.weather_code[0] = Some(82);
+    assert_eq!(response.daily.weather_code[0], Some(82));
 }
 
 #[test]
 fn test_wmo_code_85_heavy_snow() {
     let mut response = create_mock_open_meteo_response();
-    response.daily.weather_code[0] = 85;
-    assert_eq!(response.daily.weather_code[0], 85);
+    response.daily.weather_code[0] = Some(85);
+    assert_eq!(response.daily.weather_code[0], Some(85));
 }
 
 #[test]
 fn test_wmo_code_86_heavy_snow() {
     let mut response = create_mock_open_meteo_response();
-    response.daily.weather_code[0] = 86;
-    assert_eq!(response.daily.weather_code[0], 86);
+    response.daily.weather_code[0] = Some(86);
+    assert_eq!(response.daily.weather_code[0], Some(86));
 }
 
 #[test]
@@ -153,14 +157,17 @@ fn test_normalized_forecast_structure() {
         temp_max: 32.5,
         temp_min: 24.0,
         temp_avg: 28.25,
-        condition: "Clear sky".to_string(),
-        humidity: 65,
-        wind_speed: 5.5,
+        condition: Condition::Clear,
+        humidity: Some(65),
+        wind_speed: Some(5.5),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
     assert!(!forecast.date.is_empty());
-    assert!(!forecast.condition.is_empty());
+    assert_eq!(forecast.condition.as_str(), "Clear");
     assert!(!forecast.icon.is_empty());
 }
 
@@ -171,9 +178,12 @@ fn test_normalized_forecast_has_all_fields() {
         temp_max: 32.5,
         temp_min: 24.0,
         temp_avg: 28.25,
-        condition: "Clear sky".to_string(),
-        humidity: 65,
-        wind_speed: 5.5,
+        condition: Condition::Clear,
+        humidity: Some(65),
+        wind_speed: Some(5.5),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
@@ -241,7 +251,7 @@ fn test_open_meteo_response_deserialization() {
             "time": ["2024-01-15", "2024-01-16"],
             "temperature_2m_max": [32.5, 30.0],
             "temperature_2m_min": [24.0, 23.0],
-            "relative_humidity_2m": [65, 85],
+            "relative_humidity_2m_mean": [65, 85],
             "weather_code": [0, 61]
         }
     }"#;
@@ -280,9 +290,12 @@ fn test_temperature_range_realistic() {
             temp_max: 32.5,
             temp_min: 24.0,
             temp_avg: 28.25,
-            condition: "Clear sky".to_string(),
-            humidity: 65,
-            wind_speed: 5.5,
+            condition: Condition::Clear,
+            humidity: Some(65),
+            wind_speed: Some(5.5),
+            wind_gust: None,
+            precipitation_mm: None,
+            precipitation_probability: None,
             icon: "sunny".to_string(),
         },
         DailyForecast {
@@ -290,9 +303,12 @@ fn test_temperature_range_realistic() {
             temp_max: -5.0,
             temp_min: -15.0,
             temp_avg: -10.0,
-            condition: "Snow".to_string(),
-            humidity: 85,
-            wind_speed: 8.0,
+            condition: Condition::Snow(Intensity::Moderate),
+            humidity: Some(85),
+            wind_speed: Some(8.0),
+            wind_gust: None,
+            precipitation_mm: None,
+            precipitation_probability: None,
             icon: "snowy".to_string(),
         },
     ];
@@ -311,9 +327,12 @@ fn test_multiple_providers_normalized_format() {
         temp_max: 32.5,
         temp_min: 24.0,
         temp_avg: 28.25,
-        condition: "Clear sky".to_string(),
-        humidity: 65,
-        wind_speed: 5.5,
+        condition: Condition::Clear,
+        humidity: Some(65),
+        wind_speed: Some(5.5),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
@@ -322,9 +341,12 @@ fn test_multiple_providers_normalized_format() {
         temp_max: 32.0,
         temp_min: 24.5,
         temp_avg: 28.25,
-        condition: "Clear".to_string(),
-        humidity: 60,
-        wind_speed: 6.0,
+        condition: Condition::Clear,
+        humidity: Some(60),
+        wind_speed: Some(6.0),
+        wind_gust: None,
+        precipitation_mm: None,
+        precipitation_probability: None,
         icon: "sunny".to_string(),
     };
 
@@ -349,9 +371,12 @@ fn test_forecast_array_length_seven_days() {
             temp_max: 32.5,
             temp_min: 24.0,
             temp_avg: 28.25,
-            condition: "Clear sky".to_string(),
-            humidity: 65,
-            wind_speed: 5.5,
+            condition: Condition::Clear,
+            humidity: Some(65),
+            wind_speed: Some(5.5),
+            wind_gust: None,
+            precipitation_mm: None,
+            precipitation_probability: None,
             icon: "sunny".to_string(),
         })
         .collect();
@@ -362,28 +387,36 @@ fn test_forecast_array_length_seven_days() {
 #[test]
 fn test_weather_service_creation() {
     use backend::services::weather_service::WeatherService;
+    use backend::services::providers::{OpenMeteoProvider, ProviderRegistry};
+    use std::sync::Arc;
     
-    let service = WeatherService::new(
-        "test-key".to_string(),
-        "test-key".to_string(),
-    );
+    let registry = Arc::new(ProviderRegistry::new(vec![Arc::new(OpenMeteoProvider::default())]));
+    let service = WeatherService::new(registry);
     
-    assert!(!service.openweather_key.is_empty());
-    assert!(!service.weatherapi_key.is_empty());
+    assert_eq!(service.registry.len(), 1);
 }
 
 #[test]
-fn test_weather_service_keys_storage() {
+fn test_weather_service_registry_storage() {
     use backend::services::weather_service::WeatherService;
+    use backend::services::providers::{ApiKeyPool, OpenWeatherProvider, WeatherApiProvider, ProviderRegistry, UpstreamClient};
+    use std::sync::Arc;
     
-    let openweather_key = "openweather-test-key".to_string();
-    let weatherapi_key = "weatherapi-test-key".to_string();
+    let upstream = Arc::new(UpstreamClient::default());
+    let registry = Arc::new(ProviderRegistry::new(vec![
+        Arc::new(OpenWeatherProvider::new(
+            upstream.clone(),
+            "http://localhost:9".to_string(),
+            ApiKeyPool::single("open_weather", "openweather-test-key"),
+        )),
+        Arc::new(WeatherApiProvider::new(
+            upstream,
+            "http://localhost:9".to_string(),
+            ApiKeyPool::single("weather_api", "weatherapi-test-key"),
+        )),
+    ]));
     
-    let service = WeatherService::new(
-        openweather_key.clone(),
-        weatherapi_key.clone(),
-    );
+    let service = WeatherService::new(registry);
     
-    assert_eq!(service.openweather_key, openweather_key);
-    assert_eq!(service.weatherapi_key, weatherapi_key);
+    assert_eq!(service.registry.ids(), vec!["open_weather", "weather_api"]);
 }